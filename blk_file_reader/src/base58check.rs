@@ -0,0 +1,98 @@
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+
+const ALPHABET: &'static [u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encodes the given `payload` together with the given `version` byte.
+///
+/// For more information, see the according Bitcoin wiki page on the
+/// [Base58Check encoding](https://en.bitcoin.it/wiki/Base58Check_encoding).
+pub fn encode(version: u8, payload: &[u8]) -> String {
+    let mut bytes = Vec::with_capacity(payload.len() + 5);
+    bytes.push(version);
+    bytes.extend_from_slice(payload);
+    let checksum = calculate_checksum(&bytes);
+    bytes.extend_from_slice(&checksum);
+    encode_base58(&bytes)
+}
+
+/// Calculates the first four bytes of the double-SHA256 of `bytes`.
+fn calculate_checksum(bytes: &[u8]) -> [u8; 4] {
+    let mut sha = Sha256::new();
+    sha.input(bytes);
+    let mut first_hash = [0u8; 32];
+    sha.result(&mut first_hash);
+
+    sha.reset();
+    sha.input(&first_hash);
+    let mut second_hash = [0u8; 32];
+    sha.result(&mut second_hash);
+
+    let mut checksum = [0u8; 4];
+    checksum.copy_from_slice(&second_hash[..4]);
+    checksum
+}
+
+fn encode_base58(bytes: &[u8]) -> String {
+    // Every leading zero byte is encoded as the first character of the alphabet.
+    let number_of_leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+
+    // Base58 digits in little-endian order.
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in bytes[number_of_leading_zeros..].iter() {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut encoded = String::with_capacity(number_of_leading_zeros + digits.len());
+    for _ in 0..number_of_leading_zeros {
+        encoded.push(ALPHABET[0] as char);
+    }
+    for &digit in digits.iter().rev() {
+        encoded.push(ALPHABET[digit as usize] as char);
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use data_encoding::HEXLOWER;
+
+    #[test]
+    fn encodes_genesis_block_address() {
+        // given
+        let hash = HEXLOWER
+            .decode(b"62e907b15cbf27d5425399ebf6f0fb50ebb88f18")
+            .unwrap();
+
+        // when
+        let base58check = encode(0x00, &hash);
+
+        // then
+        assert_eq!(base58check, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+    }
+
+    #[test]
+    fn encodes_testnet_address() {
+        // given
+        let hash = HEXLOWER
+            .decode(b"62e907b15cbf27d5425399ebf6f0fb50ebb88f18")
+            .unwrap();
+
+        // when
+        let base58check = encode(0x6F, &hash);
+
+        // then
+        assert_eq!(base58check, "mpXwg4jMtRhuSpVq4xS3HFHmCmWp9NyGKt");
+    }
+}
@@ -0,0 +1,156 @@
+const CHARSET: &'static [u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const BECH32_CONSTANT: u32 = 1;
+
+const BECH32M_CONSTANT: u32 = 0x2BC830A3;
+
+/// Encodes the given witness program as SegWit address.
+///
+/// Version 0 witness programs are encoded as Bech32 (see
+/// [BIP 173](https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki)),
+/// all later versions are encoded as Bech32m (see
+/// [BIP 350](https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki)).
+pub fn encode_segwit_address(hrp: &str, witness_version: u8, witness_program: &[u8]) -> String {
+    let mut data = Vec::with_capacity(1 + (witness_program.len() * 8 + 4) / 5);
+    data.push(witness_version);
+    data.append(&mut convert_bits_8_to_5(witness_program));
+
+    let constant = if witness_version == 0 {
+        BECH32_CONSTANT
+    } else {
+        BECH32M_CONSTANT
+    };
+    let checksum = create_checksum(hrp, &data, constant);
+
+    let mut encoded = String::with_capacity(hrp.len() + 1 + data.len() + checksum.len());
+    encoded.push_str(hrp);
+    encoded.push('1');
+    for &value in data.iter().chain(checksum.iter()) {
+        encoded.push(CHARSET[value as usize] as char);
+    }
+    encoded
+}
+
+/// Regroups the given bytes into 5-bit values, padding the last one with zeros.
+fn convert_bits_8_to_5(bytes: &[u8]) -> Vec<u8> {
+    let mut values = Vec::with_capacity((bytes.len() * 8 + 4) / 5);
+    let mut accumulator = 0u32;
+    let mut number_of_bits = 0u32;
+    for &byte in bytes {
+        accumulator = (accumulator << 8) | byte as u32;
+        number_of_bits += 8;
+        while number_of_bits >= 5 {
+            number_of_bits -= 5;
+            values.push(((accumulator >> number_of_bits) & 0x1F) as u8);
+        }
+    }
+    if number_of_bits > 0 {
+        values.push(((accumulator << (5 - number_of_bits)) & 0x1F) as u8);
+    }
+    values
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3B6A57B2, 0x26508E6D, 0x1EA119FA, 0x3D4233DD, 0x2A1462B3];
+    let mut checksum = 1u32;
+    for &value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1FFFFFF) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn expand_hrp(hrp: &str) -> Vec<u8> {
+    let hrp = hrp.as_bytes();
+    let mut expanded = Vec::with_capacity(hrp.len() * 2 + 1);
+    expanded.extend(hrp.iter().map(|&c| c >> 5));
+    expanded.push(0);
+    expanded.extend(hrp.iter().map(|&c| c & 0x1F));
+    expanded
+}
+
+fn create_checksum(hrp: &str, data: &[u8], constant: u32) -> [u8; 6] {
+    let mut values = expand_hrp(hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0u8; 6]);
+    let polymod = polymod(&values) ^ constant;
+    let mut checksum = [0u8; 6];
+    for (i, value) in checksum.iter_mut().enumerate() {
+        *value = ((polymod >> (5 * (5 - i))) & 0x1F) as u8;
+    }
+    checksum
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use data_encoding::HEXLOWER;
+
+    #[test]
+    fn encodes_p2wpkh_address() {
+        // given
+        let witness_program = HEXLOWER
+            .decode(b"751e76e8199196d454941c45d1b3a323f1433bd6")
+            .unwrap();
+
+        // when
+        let address = encode_segwit_address("bc", 0, &witness_program);
+
+        // then
+        assert_eq!(address, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+    }
+
+    #[test]
+    fn encodes_p2wsh_address() {
+        // given
+        let witness_program = HEXLOWER
+            .decode(b"1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262")
+            .unwrap();
+
+        // when
+        let address = encode_segwit_address("bc", 0, &witness_program);
+
+        // then
+        assert_eq!(
+            address,
+            "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3"
+        );
+    }
+
+    #[test]
+    fn encodes_p2tr_address_as_bech32m() {
+        // given
+        let witness_program = HEXLOWER
+            .decode(b"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+            .unwrap();
+
+        // when
+        let address = encode_segwit_address("bc", 1, &witness_program);
+
+        // then
+        assert_eq!(
+            address,
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0"
+        );
+    }
+
+    #[test]
+    fn encodes_testnet_address() {
+        // given
+        let witness_program = HEXLOWER
+            .decode(b"751e76e8199196d454941c45d1b3a323f1433bd6")
+            .unwrap();
+
+        // when
+        let address = encode_segwit_address("tb", 0, &witness_program);
+
+        // then
+        assert_eq!(address, "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx");
+    }
+}
@@ -0,0 +1,336 @@
+use byteorder::{LittleEndian, ReadBytesExt};
+use core_var_int::read_core_var_int;
+use domain::{BlockIndexEntry, ChainWork, Hash, BLOCK_HAVE_DATA, BLOCK_HAVE_UNDO};
+use level_db::{for_each_record, open_level_db};
+use read::ReadBlockInternals;
+use std::collections::HashMap;
+use std::io::{self, Cursor};
+use std::path::Path;
+
+/// The key prefix of block index records.
+const BLOCK_INDEX_PREFIX: u8 = b'b';
+
+/// Bitcoin Core's block index, which is stored as LevelDB database in
+/// `blocks/index`.
+///
+/// In contrast to the blk files, which contain blocks roughly in the order in
+/// which they have been downloaded, the block index allows for reading the best
+/// chain in height order.
+pub struct BlockIndex {
+    /// Entries ordered by height.
+    entries: Vec<BlockIndexEntry>,
+    /// Maps block hashes to the position of their entry within `entries`.
+    positions: HashMap<[u8; 32], usize>,
+    /// Maps positions to the position of the entry of the previous block.
+    parent_positions: Vec<Option<usize>>,
+    /// The position of the tip of the best chain.
+    best_tip_position: Option<usize>,
+}
+
+impl BlockIndex {
+    /// Reads the block index at the given path, e.g. `~/.bitcoin/blocks/index`.
+    ///
+    /// Bitcoin Core must not be running while the block index is read.
+    pub fn open(path_to_block_index: &Path) -> io::Result<BlockIndex> {
+        let database = open_level_db(path_to_block_index)?;
+        let mut entries = Vec::new();
+        for_each_record(&database, BLOCK_INDEX_PREFIX, |key, value| {
+            entries.push(read_block_index_entry(key, value)?);
+            Ok(())
+        })?;
+        Ok(BlockIndex::from_entries(entries))
+    }
+
+    /// Creates a block index from the given entries, calculating their chain
+    /// work and the best chain.
+    ///
+    /// The best chain ends in the block with the most chain work among all
+    /// blocks that are stored in blk files, together with all their ancestors,
+    /// and that are not known to be invalid.
+    pub fn from_entries(mut entries: Vec<BlockIndexEntry>) -> BlockIndex {
+        entries.sort_by_key(|entry| entry.height);
+
+        let positions: HashMap<[u8; 32], usize> = entries
+            .iter()
+            .enumerate()
+            .map(|(position, entry)| (entry.hash.0, position))
+            .collect();
+
+        let mut parent_positions = Vec::with_capacity(entries.len());
+        let mut is_complete = Vec::with_capacity(entries.len());
+        let mut best_tip_position: Option<usize> = None;
+
+        for position in 0..entries.len() {
+            // Parents have a lower height and therefore precede their children.
+            let parent_position = positions
+                .get(&entries[position].previous_block_hash.0)
+                .cloned()
+                .filter(|&parent_position| parent_position < position);
+            let block_work = ChainWork::from_bits(entries[position].bits);
+            let (chain_work, is_parent_complete) = match parent_position {
+                Some(parent_position) => (
+                    &entries[parent_position].chain_work + &block_work,
+                    is_complete[parent_position],
+                ),
+                None => (block_work, entries[position].height == 0),
+            };
+            entries[position].chain_work = chain_work;
+
+            let entry = &entries[position];
+            let is_entry_complete = is_parent_complete && entry.has_data() && !entry.is_failed();
+            if is_entry_complete {
+                let is_better_tip = match best_tip_position {
+                    Some(best_tip_position) => {
+                        entry.chain_work > entries[best_tip_position].chain_work
+                    }
+                    None => true,
+                };
+                if is_better_tip {
+                    best_tip_position = Some(position);
+                }
+            }
+
+            parent_positions.push(parent_position);
+            is_complete.push(is_entry_complete);
+        }
+
+        BlockIndex {
+            entries,
+            positions,
+            parent_positions,
+            best_tip_position,
+        }
+    }
+
+    /// Returns the number of entries, including those of stale blocks.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entry of the block with the given hash.
+    pub fn get(&self, hash: &Hash) -> Option<&BlockIndexEntry> {
+        self.positions
+            .get(&hash.0)
+            .map(|&position| &self.entries[position])
+    }
+
+    /// Returns the entries of the best chain, starting with the genesis block.
+    pub fn best_chain(&self) -> Vec<&BlockIndexEntry> {
+        let mut best_chain = Vec::new();
+        let mut position = self.best_tip_position;
+        while let Some(current_position) = position {
+            best_chain.push(&self.entries[current_position]);
+            position = self.parent_positions[current_position];
+        }
+        best_chain.reverse();
+        best_chain
+    }
+}
+
+/// Read a block index entry from the key (without prefix) and value of a block
+/// index record, i.e. a serialized `CDiskBlockIndex`.
+fn read_block_index_entry(key: &[u8], value: &[u8]) -> io::Result<BlockIndexEntry> {
+    if key.len() != 32 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("block index key has invalid length {}", key.len()),
+        ));
+    }
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(key);
+    hash.reverse();
+
+    let mut cursor = Cursor::new(value);
+    let _client_version = read_core_var_int(&mut cursor)?;
+    let height = read_core_var_int(&mut cursor)?;
+    // TODO Fix possibly truncating casts.
+    let status = read_core_var_int(&mut cursor)? as u32;
+    let number_of_transactions = read_core_var_int(&mut cursor)?;
+    let file_number = if status & (BLOCK_HAVE_DATA | BLOCK_HAVE_UNDO) != 0 {
+        Some(read_core_var_int(&mut cursor)? as u32)
+    } else {
+        None
+    };
+    let data_offset = if status & BLOCK_HAVE_DATA != 0 {
+        Some(read_core_var_int(&mut cursor)? as u32)
+    } else {
+        None
+    };
+    let undo_offset = if status & BLOCK_HAVE_UNDO != 0 {
+        Some(read_core_var_int(&mut cursor)? as u32)
+    } else {
+        None
+    };
+
+    let version = cursor.read_u32::<LittleEndian>()?;
+    let previous_block_hash = cursor.read_hash()?;
+    let merkle_root = cursor.read_hash()?;
+    let creation_time = cursor.read_u32::<LittleEndian>()?;
+    let bits = cursor.read_u32::<LittleEndian>()?;
+    let nonce = cursor.read_u32::<LittleEndian>()?;
+
+    let entry = BlockIndexEntry {
+        hash: Hash(hash),
+        height: height as u32,
+        status,
+        number_of_transactions: number_of_transactions as u32,
+        file_number,
+        data_offset,
+        undo_offset,
+        version,
+        previous_block_hash,
+        merkle_root,
+        creation_time,
+        bits,
+        nonce,
+        chain_work: ChainWork::zero(),
+    };
+
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use data_encoding::HEXLOWER;
+
+    const GENESIS_HEADER: &str = "0100000000000000000000000000000000000000000000000000000000000000\
+                                  000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa\
+                                  4b1e5e4a29ab5f49ffff001d1dac2b7c";
+
+    fn hash(byte: u8) -> Hash {
+        Hash([byte; 32])
+    }
+
+    fn entry(hash: Hash, previous_block_hash: Hash, height: u32, status: u32) -> BlockIndexEntry {
+        BlockIndexEntry {
+            hash: hash.clone(),
+            height,
+            status,
+            number_of_transactions: 1,
+            file_number: Some(0),
+            data_offset: Some(8),
+            undo_offset: None,
+            version: 1,
+            previous_block_hash,
+            merkle_root: hash,
+            creation_time: 0,
+            bits: 0x1d00ffff,
+            nonce: 0,
+            chain_work: ChainWork::zero(),
+        }
+    }
+
+    #[test]
+    fn reads_block_index_entry() {
+        // given
+        let mut key = HEXLOWER
+            .decode(b"000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f")
+            .unwrap();
+        key.reverse();
+        // version 150000, height 0, status 29, 1 transaction, file 0,
+        // data offset 8, undo offset 0
+        let mut value = vec![0x88, 0x92, 0x70, 0x00, 0x1d, 0x01, 0x00, 0x08, 0x00];
+        value.extend(HEXLOWER.decode(GENESIS_HEADER.as_bytes()).unwrap());
+
+        // when
+        let entry = read_block_index_entry(&key, &value).unwrap();
+
+        // then
+        assert_eq!(
+            format!("{:?}", entry.hash),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+        assert_eq!(entry.height, 0);
+        assert_eq!(entry.status, 29);
+        assert_eq!(entry.number_of_transactions, 1);
+        assert_eq!(entry.file_number, Some(0));
+        assert_eq!(entry.data_offset, Some(8));
+        assert_eq!(entry.undo_offset, Some(0));
+        assert_eq!(entry.version, 1);
+        assert_eq!(entry.previous_block_hash, Hash([0u8; 32]));
+        assert_eq!(
+            format!("{:?}", entry.merkle_root),
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+        );
+        assert_eq!(entry.creation_time, 1231006505);
+        assert_eq!(entry.bits, 0x1d00ffff);
+        assert_eq!(entry.nonce, 2083236893);
+        assert!(entry.has_data());
+        assert!(entry.has_undo());
+    }
+
+    #[test]
+    fn when_block_has_no_data_then_reads_no_file_position() {
+        // given
+        let key = [0u8; 32];
+        let mut value = vec![0x88, 0x92, 0x70, 0x00, 0x01, 0x00];
+        value.extend(HEXLOWER.decode(GENESIS_HEADER.as_bytes()).unwrap());
+
+        // when
+        let entry = read_block_index_entry(&key, &value).unwrap();
+
+        // then
+        assert_eq!(entry.file_number, None);
+        assert_eq!(entry.data_offset, None);
+        assert_eq!(entry.undo_offset, None);
+        assert_eq!(entry.bits, 0x1d00ffff);
+    }
+
+    #[test]
+    fn best_chain_follows_most_chain_work() {
+        // given
+        let entries = vec![
+            entry(hash(3), hash(2), 2, BLOCK_HAVE_DATA),
+            entry(hash(1), Hash([0u8; 32]), 0, BLOCK_HAVE_DATA),
+            entry(hash(2), hash(1), 1, BLOCK_HAVE_DATA),
+            entry(hash(4), hash(1), 1, BLOCK_HAVE_DATA),
+        ];
+
+        // when
+        let block_index = BlockIndex::from_entries(entries);
+
+        // then
+        let best_chain: Vec<Hash> = block_index
+            .best_chain()
+            .iter()
+            .map(|entry| entry.hash.clone())
+            .collect();
+        assert_eq!(best_chain, vec![hash(1), hash(2), hash(3)]);
+        let block_work = ChainWork::from_bits(0x1d00ffff);
+        assert_eq!(
+            block_index.get(&hash(3)).unwrap().chain_work,
+            &(&block_work + &block_work) + &block_work
+        );
+    }
+
+    #[test]
+    fn best_chain_ignores_failed_blocks_and_blocks_without_data() {
+        // given
+        let entries = vec![
+            entry(hash(1), Hash([0u8; 32]), 0, BLOCK_HAVE_DATA),
+            entry(hash(2), hash(1), 1, BLOCK_HAVE_DATA),
+            entry(hash(3), hash(1), 1, BLOCK_HAVE_DATA),
+            entry(hash(4), hash(3), 2, BLOCK_HAVE_DATA | 32),
+            entry(hash(5), hash(2), 2, 0),
+            entry(hash(6), hash(5), 3, BLOCK_HAVE_DATA),
+        ];
+
+        // when
+        let block_index = BlockIndex::from_entries(entries);
+
+        // then
+        let best_chain: Vec<Hash> = block_index
+            .best_chain()
+            .iter()
+            .map(|entry| entry.hash.clone())
+            .collect();
+        assert_eq!(best_chain, vec![hash(1), hash(2)]);
+        assert_eq!(block_index.len(), 6);
+    }
+}
@@ -1,33 +1,178 @@
+use byteorder::{ByteOrder, LittleEndian};
 use domain::Block;
+use parse_error::{shift_offset, ParseError};
 use read::ReadBlock;
+use read_options::ReadOptions;
 use std::fs::File;
-use std::io::{self, BufReader};
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use xor::XorReader;
+
+/// The number of bytes read at once while scanning for a magic number.
+const SCAN_BUFFER_SIZE: usize = 64 * 1024;
 
 /// Allows for iterating over the blocks within a blk file.
 pub struct Blocks {
-    reader: io::BufReader<File>,
+    reader: XorReader<BufReader<File>>,
+    options: ReadOptions,
     index_in_blk_file: usize,
+    is_exhausted: bool,
 }
 
 impl Blocks {
-    pub fn new(reader: BufReader<File>) -> Blocks {
+    pub fn new(reader: BufReader<File>, options: ReadOptions) -> Blocks {
+        Blocks {
+            reader: XorReader::new(reader, None),
+            options,
+            index_in_blk_file: 0,
+            is_exhausted: false,
+        }
+    }
+
+    /// Creates an iterator over the blocks of a blk file that has been
+    /// obfuscated with the given key, see `read_xor_key`.
+    pub fn with_xor_key(reader: BufReader<File>, options: ReadOptions, xor_key: [u8; 8]) -> Blocks {
         Blocks {
-            reader,
+            reader: XorReader::new(reader, Some(xor_key)),
+            options,
             index_in_blk_file: 0,
+            is_exhausted: false,
+        }
+    }
+
+    /// Continues with the block at the given offset within the blk file, see
+    /// `Block::offset_in_blk_file`.
+    ///
+    /// This allows for resuming at a previously read block without parsing
+    /// the blocks before it. Since these blocks are not counted, the index of
+    /// the block at `offset` has to be passed as `index_in_blk_file`.
+    pub fn seek_to_offset(&mut self, offset: u64, index_in_blk_file: usize) -> io::Result<()> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.index_in_blk_file = index_in_blk_file;
+        self.is_exhausted = false;
+        Ok(())
+    }
+
+    /// Continues at the next magic number after the block at `offset`, which
+    /// could not be parsed because of the given cause.
+    fn resync(&mut self, offset: u64, cause: ParseError) -> Option<io::Result<Block>> {
+        let scan = match self.scan_for_magic_number(offset) {
+            Ok(scan) => scan,
+            Err(error) => {
+                self.is_exhausted = true;
+                return Some(Err(error));
+            }
+        };
+
+        let end_offset = match scan.next_offset {
+            Some(next_offset) => next_offset,
+            None if scan.is_zero_padding => {
+                self.is_exhausted = true;
+                return None;
+            }
+            None => {
+                self.is_exhausted = true;
+                scan.end_offset
+            }
+        };
+
+        Some(Err(ParseError::Skipped {
+            offset,
+            length: end_offset - offset,
+            cause: Box::new(cause),
+        }
+        .into()))
+    }
+
+    /// Scans the blk file for the first magic number after `offset` and
+    /// positions the reader at it.
+    fn scan_for_magic_number(&mut self, offset: u64) -> io::Result<MagicNumberScan> {
+        let mut magic_number = [0u8; 4];
+        LittleEndian::write_u32(&mut magic_number, self.options.network.magic_number());
+
+        self.reader.seek(SeekFrom::Start(offset))?;
+
+        // The bytes that have not been searched yet, starting at `window_offset`.
+        let mut window = Vec::new();
+        let mut window_offset = offset;
+        let mut is_zero_padding = true;
+        let mut buffer = vec![0u8; SCAN_BUFFER_SIZE];
+        loop {
+            let number_of_bytes = match self.reader.read(&mut buffer) {
+                Ok(number_of_bytes) => number_of_bytes,
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            if number_of_bytes == 0 {
+                return Ok(MagicNumberScan {
+                    next_offset: None,
+                    end_offset: window_offset + window.len() as u64,
+                    is_zero_padding,
+                });
+            }
+
+            let bytes = &buffer[..number_of_bytes];
+            is_zero_padding = is_zero_padding && bytes.iter().all(|&byte| byte == 0);
+            window.extend_from_slice(bytes);
+
+            let next_offset = window
+                .windows(magic_number.len())
+                .enumerate()
+                .map(|(i, candidate)| (window_offset + i as u64, candidate))
+                .find(|&(candidate_offset, candidate)| {
+                    candidate_offset > offset && candidate == magic_number
+                })
+                .map(|(candidate_offset, _)| candidate_offset);
+            if let Some(next_offset) = next_offset {
+                self.reader.seek(SeekFrom::Start(next_offset))?;
+                return Ok(MagicNumberScan {
+                    next_offset: Some(next_offset),
+                    end_offset: next_offset,
+                    is_zero_padding,
+                });
+            }
+
+            // Keep the bytes a magic number spanning two reads could start with.
+            let searched_length = window.len().saturating_sub(magic_number.len() - 1);
+            window.drain(..searched_length);
+            window_offset += searched_length as u64;
         }
     }
 }
 
+/// The result of scanning a blk file for a magic number.
+struct MagicNumberScan {
+    /// The offset of the magic number, if any has been found.
+    next_offset: Option<u64>,
+    /// The offset at which the scan stopped.
+    end_offset: u64,
+    /// Whether all scanned bytes are zero.
+    is_zero_padding: bool,
+}
+
 impl Iterator for Blocks {
     type Item = io::Result<Block>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.reader.read_block(self.index_in_blk_file) {
+        if self.is_exhausted {
+            return None;
+        }
+
+        let offset = self.reader.position();
+        match self
+            .reader
+            .read_block_at(self.index_in_blk_file, offset, self.options)
+        {
             Ok(block) => {
                 self.index_in_blk_file += 1;
                 Some(Ok(block))
             }
             Err(error) => {
+                let error = shift_offset(error, offset);
+                if self.options.resync {
+                    if let Some(cause) = ParseError::from_io_error(&error).cloned() {
+                        return self.resync(offset, cause);
+                    }
+                }
                 if error.kind() == io::ErrorKind::UnexpectedEof {
                     None
                 } else {
@@ -0,0 +1,323 @@
+use domain::{Block, ScriptType};
+use std::collections::HashMap;
+
+/// The number of buckets of a `Distribution`, which suffices for any `u32`.
+const NUMBER_OF_BUCKETS: usize = 33;
+
+/// Statistics of a number of blocks, e.g. of the blocks of a blk file.
+///
+/// Statistics are collected block by block with `add_block` and can be merged,
+/// e.g. to aggregate the statistics of blk files that have been read in
+/// parallel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainStats {
+    pub number_of_blocks: u64,
+    pub number_of_transactions: u64,
+    pub number_of_segwit_transactions: u64,
+    pub number_of_inputs: u64,
+    pub number_of_outputs: u64,
+    /// The number of outputs of each script type.
+    pub script_types: HashMap<ScriptType, u64>,
+    /// The sizes of the blocks in bytes.
+    pub block_sizes: Distribution,
+    pub block_weights: Distribution,
+    /// The sizes of the transactions in bytes.
+    pub transaction_sizes: Distribution,
+    pub transaction_weights: Distribution,
+    /// The earliest creation time of the blocks.
+    ///
+    /// Since miners only loosely follow the actual time, this is not
+    /// necessarily the creation time of the first block.
+    pub first_creation_time: Option<u32>,
+    /// The latest creation time of the blocks.
+    pub last_creation_time: Option<u32>,
+}
+
+/// A distribution of values, which are counted in buckets of powers of two.
+///
+/// The first bucket counts zeros, and bucket `i > 0` counts the values from
+/// `2^(i - 1)` up to `2^i - 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    count: u64,
+    total: u64,
+    min: u32,
+    max: u32,
+    buckets: Vec<u64>,
+}
+
+impl ChainStats {
+    pub fn new() -> ChainStats {
+        ChainStats {
+            number_of_blocks: 0,
+            number_of_transactions: 0,
+            number_of_segwit_transactions: 0,
+            number_of_inputs: 0,
+            number_of_outputs: 0,
+            script_types: HashMap::new(),
+            block_sizes: Distribution::new(),
+            block_weights: Distribution::new(),
+            transaction_sizes: Distribution::new(),
+            transaction_weights: Distribution::new(),
+            first_creation_time: None,
+            last_creation_time: None,
+        }
+    }
+
+    pub fn add_block(&mut self, block: &Block) {
+        self.number_of_blocks += 1;
+        self.block_sizes.add(block.size_in_bytes);
+        self.block_weights.add(block.weight());
+        self.first_creation_time = Some(
+            self.first_creation_time
+                .map_or(block.creation_time, |time| time.min(block.creation_time)),
+        );
+        self.last_creation_time = Some(
+            self.last_creation_time
+                .map_or(block.creation_time, |time| time.max(block.creation_time)),
+        );
+
+        for transaction in block.transactions.iter() {
+            self.number_of_transactions += 1;
+            if transaction.is_segwit() {
+                self.number_of_segwit_transactions += 1;
+            }
+            self.number_of_inputs += transaction.inputs.len() as u64;
+            self.number_of_outputs += transaction.outputs.len() as u64;
+            self.transaction_sizes.add(transaction.size_in_bytes);
+            self.transaction_weights.add(transaction.weight);
+            for output in transaction.outputs.iter() {
+                *self.script_types.entry(output.script_type).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Adds the statistics of other blocks to these statistics.
+    pub fn merge(&mut self, other: &ChainStats) {
+        self.number_of_blocks += other.number_of_blocks;
+        self.number_of_transactions += other.number_of_transactions;
+        self.number_of_segwit_transactions += other.number_of_segwit_transactions;
+        self.number_of_inputs += other.number_of_inputs;
+        self.number_of_outputs += other.number_of_outputs;
+        for (&script_type, &count) in other.script_types.iter() {
+            *self.script_types.entry(script_type).or_insert(0) += count;
+        }
+        self.block_sizes.merge(&other.block_sizes);
+        self.block_weights.merge(&other.block_weights);
+        self.transaction_sizes.merge(&other.transaction_sizes);
+        self.transaction_weights.merge(&other.transaction_weights);
+        self.first_creation_time = match (self.first_creation_time, other.first_creation_time) {
+            (Some(time), Some(other_time)) => Some(time.min(other_time)),
+            (time, other_time) => time.or(other_time),
+        };
+        self.last_creation_time = match (self.last_creation_time, other.last_creation_time) {
+            (Some(time), Some(other_time)) => Some(time.max(other_time)),
+            (time, other_time) => time.or(other_time),
+        };
+    }
+
+    /// The share of SegWit transactions among all transactions, between 0 and
+    /// 1.
+    pub fn segwit_share(&self) -> Option<f64> {
+        if self.number_of_transactions == 0 {
+            None
+        } else {
+            Some(self.number_of_segwit_transactions as f64 / self.number_of_transactions as f64)
+        }
+    }
+
+    pub fn number_of_outputs_of_type(&self, script_type: ScriptType) -> u64 {
+        self.script_types.get(&script_type).cloned().unwrap_or(0)
+    }
+
+    /// The number of `OP_RETURN` outputs, see `ScriptType::OpReturn`.
+    pub fn number_of_op_return_outputs(&self) -> u64 {
+        self.number_of_outputs_of_type(ScriptType::OpReturn)
+    }
+}
+
+impl Default for ChainStats {
+    fn default() -> ChainStats {
+        ChainStats::new()
+    }
+}
+
+impl Distribution {
+    pub fn new() -> Distribution {
+        Distribution {
+            count: 0,
+            total: 0,
+            min: u32::max_value(),
+            max: 0,
+            buckets: vec![0; NUMBER_OF_BUCKETS],
+        }
+    }
+
+    pub fn add(&mut self, value: u32) {
+        self.count += 1;
+        self.total += u64::from(value);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.buckets[bucket_index(value)] += 1;
+    }
+
+    pub fn merge(&mut self, other: &Distribution) {
+        self.count += other.count;
+        self.total += other.total;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        for (bucket, other_bucket) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *bucket += other_bucket;
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn min(&self) -> Option<u32> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.min)
+        }
+    }
+
+    pub fn max(&self) -> Option<u32> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.max)
+        }
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.total as f64 / self.count as f64)
+        }
+    }
+
+    /// Returns the non-empty buckets, each given by the smallest and largest
+    /// value it counts, and its count.
+    pub fn buckets(&self) -> Vec<(u32, u32, u64)> {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(index, &count)| {
+                let (lower_bound, upper_bound) = bucket_bounds(index);
+                (lower_bound, upper_bound, count)
+            })
+            .collect()
+    }
+}
+
+impl Default for Distribution {
+    fn default() -> Distribution {
+        Distribution::new()
+    }
+}
+
+fn bucket_index(value: u32) -> usize {
+    32 - value.leading_zeros() as usize
+}
+
+fn bucket_bounds(index: usize) -> (u32, u32) {
+    if index == 0 {
+        (0, 0)
+    } else {
+        let lower_bound = 1u32 << (index - 1);
+        (lower_bound, lower_bound + (lower_bound - 1))
+    }
+}
+
+/// Formats the given Unix time, e.g. a block's creation time, as UTC date and
+/// time, e.g. `2009-01-03 18:15:05`.
+pub fn format_unix_time(unix_time: u32) -> String {
+    let seconds_of_day = unix_time % 86_400;
+    let (year, month, day) = civil_from_days(i64::from(unix_time / 86_400));
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Converts days since 1970-01-01 into a date of the proleptic Gregorian
+/// calendar, see [Howard Hinnant's algorithm](https://howardhinnant.github.io/date_algorithms.html#civil_from_days).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Days are never negative since Unix times are unsigned.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn distribution_counts_values_in_power_of_two_buckets() {
+        // given
+        let mut distribution = Distribution::new();
+        let mut other = Distribution::new();
+
+        // when
+        distribution.add(0);
+        distribution.add(285);
+        other.add(300);
+        other.add(1_000_000);
+        distribution.merge(&other);
+
+        // then
+        assert_eq!(distribution.count(), 4);
+        assert_eq!(distribution.min(), Some(0));
+        assert_eq!(distribution.max(), Some(1_000_000));
+        assert_eq!(distribution.mean(), Some(250_146.25));
+        assert_eq!(
+            distribution.buckets(),
+            vec![(0, 0, 1), (256, 511, 2), (524_288, 1_048_575, 1)]
+        );
+        assert_eq!(bucket_bounds(32), (1 << 31, u32::max_value()));
+    }
+
+    #[test]
+    fn empty_distribution_has_no_bounds() {
+        let distribution = Distribution::new();
+        assert_eq!(distribution.min(), None);
+        assert_eq!(distribution.max(), None);
+        assert_eq!(distribution.mean(), None);
+        assert!(distribution.buckets().is_empty());
+    }
+
+    #[test]
+    fn formats_unix_time() {
+        assert_eq!(format_unix_time(0), "1970-01-01 00:00:00");
+        assert_eq!(format_unix_time(1_231_006_505), "2009-01-03 18:15:05");
+        assert_eq!(format_unix_time(1_709_164_800), "2024-02-29 00:00:00");
+        assert_eq!(format_unix_time(u32::max_value()), "2106-02-07 06:28:15");
+    }
+}
@@ -0,0 +1,222 @@
+use compression::read_compressed_output;
+use core_var_int::read_core_var_int;
+use domain::{Hash, Utxo, UtxoId};
+use level_db::{get_record, open_level_db, LevelDbKey};
+use leveldb::database::Database;
+use leveldb::iterator::{self, Iterable, LevelDBIterator};
+use leveldb::options;
+use read::output_from_script;
+use read_options::ReadOptions;
+use std::io::{self, Cursor};
+use std::path::Path;
+use xor::xor;
+
+/// The key prefix of unspent output records.
+const UTXO_PREFIX: u8 = b'C';
+
+/// The key of the record that contains the hash of the best block.
+const BEST_BLOCK_KEY: &[u8] = b"B";
+
+/// The key of the record that contains the obfuscation key, i.e. the
+/// serialized string `"\0obfuscate_key"`.
+const OBFUSCATION_KEY_KEY: &[u8] = b"\x0e\x00obfuscate_key";
+
+/// Bitcoin Core's UTXO set, which is stored as LevelDB database in `chainstate`.
+///
+/// Only the per-output format of Bitcoin Core 0.15 and later is supported.
+pub struct Chainstate {
+    database: Database<LevelDbKey>,
+    obfuscation_key: Option<[u8; 8]>,
+    first_utxo_key: LevelDbKey,
+    options: ReadOptions,
+}
+
+impl Chainstate {
+    /// Opens the UTXO set at the given path, e.g. `~/.bitcoin/chainstate`.
+    ///
+    /// Bitcoin Core must not be running while the UTXO set is read.
+    pub fn open(path_to_chainstate: &Path, options: ReadOptions) -> io::Result<Chainstate> {
+        let database = open_level_db(path_to_chainstate)?;
+        let obfuscation_key = match get_record(&database, OBFUSCATION_KEY_KEY)? {
+            Some(value) => read_obfuscation_key(&value)?,
+            None => None,
+        };
+        Ok(Chainstate {
+            database,
+            obfuscation_key,
+            first_utxo_key: LevelDbKey(vec![UTXO_PREFIX]),
+            options,
+        })
+    }
+
+    /// Returns the hash of the block up to which the UTXO set is complete.
+    pub fn best_block_hash(&self) -> io::Result<Hash> {
+        let mut value = get_record(&self.database, BEST_BLOCK_KEY)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "best block is not recorded"))?;
+        self.deobfuscate(&mut value);
+        if value.len() != 32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("best block hash has invalid length {}", value.len()),
+            ));
+        }
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&value);
+        hash.reverse();
+        Ok(Hash(hash))
+    }
+
+    /// Returns an iterator over all unspent outputs, ordered by the hash of
+    /// their transaction in internal byte order.
+    pub fn utxos<'a>(&'a self) -> Utxos<'a> {
+        Utxos {
+            records: self
+                .database
+                .iter(options::ReadOptions::new())
+                .from(&self.first_utxo_key),
+            obfuscation_key: self.obfuscation_key,
+            options: self.options,
+        }
+    }
+
+    fn deobfuscate(&self, value: &mut [u8]) {
+        if let Some(ref obfuscation_key) = self.obfuscation_key {
+            xor(value, obfuscation_key, 0);
+        }
+    }
+}
+
+/// Allows for iterating over the unspent outputs of a `Chainstate`.
+pub struct Utxos<'a> {
+    records: iterator::Iterator<'a, LevelDbKey>,
+    obfuscation_key: Option<[u8; 8]>,
+    options: ReadOptions,
+}
+
+impl<'a> Iterator for Utxos<'a> {
+    type Item = io::Result<Utxo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, mut value) = self.records.next()?;
+        if key.0.first() != Some(&UTXO_PREFIX) {
+            return None;
+        }
+        if let Some(ref obfuscation_key) = self.obfuscation_key {
+            xor(&mut value, obfuscation_key, 0);
+        }
+        Some(read_utxo(&key.0[1..], &value, self.options))
+    }
+}
+
+/// Read the obfuscation key, which is serialized as vector of bytes.
+///
+/// Returns `None` if the key consists of zeros only, i.e. if the values are not
+/// obfuscated.
+fn read_obfuscation_key(value: &[u8]) -> io::Result<Option<[u8; 8]>> {
+    if value.len() != 9 || value[0] != 8 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "obfuscation key has invalid length",
+        ));
+    }
+    let mut obfuscation_key = [0u8; 8];
+    obfuscation_key.copy_from_slice(&value[1..]);
+    if obfuscation_key.iter().all(|&byte| byte == 0) {
+        Ok(None)
+    } else {
+        Ok(Some(obfuscation_key))
+    }
+}
+
+/// Read an unspent output from the key (without prefix) and de-obfuscated value
+/// of a UTXO record, i.e. a serialized `COutPoint` and `Coin`.
+fn read_utxo(key: &[u8], value: &[u8], options: ReadOptions) -> io::Result<Utxo> {
+    if key.len() < 33 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("UTXO key has invalid length {}", key.len()),
+        ));
+    }
+    let mut tx_hash = [0u8; 32];
+    tx_hash.copy_from_slice(&key[..32]);
+    tx_hash.reverse();
+    // TODO Fix possibly truncating cast.
+    let output_index = read_core_var_int(&mut Cursor::new(&key[32..]))? as u32;
+
+    let mut reader = Cursor::new(value);
+    let code = read_core_var_int(&mut reader)?;
+    let (value, script) = read_compressed_output(&mut reader)?;
+    let output = output_from_script(output_index, value, script, options);
+
+    Ok(Utxo {
+        id: UtxoId {
+            tx_hash: Hash(tx_hash),
+            output_index,
+        },
+        // TODO Fix possibly truncating cast.
+        height: (code >> 1) as u32,
+        is_coinbase: code & 1 == 1,
+        value: output.value,
+        address: output.address,
+        address_derivation: output.address_derivation,
+        script: output.script,
+        script_type: output.script_type,
+    })
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use domain::{Network, ScriptType};
+
+    #[test]
+    fn reads_utxo() {
+        // given
+        let mut key = vec![0x11; 32];
+        key.push(0x80);
+        key.push(0x00);
+        // Height 1, coinbase, 50 BTC, P2PKH.
+        let mut value = vec![0x03, 0x32, 0x00];
+        value.extend_from_slice(&[0xAB; 20]);
+
+        // when
+        let utxo = read_utxo(&key, &value, ReadOptions::new(Network::Mainnet)).unwrap();
+
+        // then
+        assert_eq!(utxo.id.tx_hash, Hash([0x11; 32]));
+        assert_eq!(utxo.id.output_index, 128);
+        assert_eq!(utxo.height, 1);
+        assert!(utxo.is_coinbase);
+        assert_eq!(utxo.value, 5_000_000_000);
+        assert_eq!(utxo.script_type, ScriptType::P2PKH);
+        assert!(utxo.address.is_some());
+    }
+
+    #[test]
+    fn reads_obfuscation_key() {
+        // given
+        let value = [0x08, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+
+        // when
+        let obfuscation_key = read_obfuscation_key(&value).unwrap();
+
+        // then
+        assert_eq!(
+            obfuscation_key,
+            Some([0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08])
+        );
+    }
+
+    #[test]
+    fn zero_obfuscation_key_is_ignored() {
+        // given
+        let value = [0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+        // when
+        let obfuscation_key = read_obfuscation_key(&value).unwrap();
+
+        // then
+        assert!(obfuscation_key.is_none());
+    }
+}
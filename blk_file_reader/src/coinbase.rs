@@ -0,0 +1,205 @@
+use disassembly::{decode_script, Instruction, Opcode};
+use domain::{Block, Transaction};
+
+const OP_1: u8 = 0x51;
+const OP_16: u8 = 0x60;
+
+/// Printable characters of coinbase scripts are only considered to be part of
+/// the tag if they form a run of at least this length, which excludes bytes of
+/// extra nonces that happen to be printable.
+const MIN_TAG_RUN_LENGTH: usize = 4;
+
+/// Reads the block height that the given coinbase script starts with, as
+/// required for version 2 blocks by [BIP 34](https://github.com/bitcoin/bips/blob/master/bip-0034.mediawiki).
+///
+/// The height is either pushed as little-endian number of up to 4 bytes or,
+/// for heights of 1 to 16, by one of `OP_1` to `OP_16`.
+pub fn read_bip34_height(coinbase_script: &[u8]) -> Option<u32> {
+    match decode_script(coinbase_script).next()? {
+        Ok(Instruction::Push { data, .. }) if !data.is_empty() && data.len() <= 4 => {
+            // Heights are never negative.
+            if data[data.len() - 1] & 0x80 != 0 {
+                return None;
+            }
+            let height = data
+                .iter()
+                .rev()
+                .fold(0u32, |height, &byte| height << 8 | u32::from(byte));
+            Some(height)
+        }
+        Ok(Instruction::Op(Opcode(opcode))) if opcode >= OP_1 && opcode <= OP_16 => {
+            Some(u32::from(opcode - OP_1 + 1))
+        }
+        _ => None,
+    }
+}
+
+/// Reads the text that miners commonly embed in their coinbase scripts, e.g.
+/// the name of their pool.
+///
+/// The tag consists of the runs of printable ASCII characters within the data
+/// pushed by the script, separated by spaces. If `has_bip34_height` is set,
+/// the first push is skipped since it holds the block height.
+pub fn read_coinbase_tag(coinbase_script: &[u8], has_bip34_height: bool) -> Option<String> {
+    let skipped_pushes = if has_bip34_height { 1 } else { 0 };
+    // Coinbase scripts may end with arbitrary bytes, so keep what could be
+    // decoded before an invalid push.
+    let runs: Vec<String> = decode_script(coinbase_script)
+        .skip(skipped_pushes)
+        .filter_map(|instruction| match instruction {
+            Ok(Instruction::Push { data, .. }) => Some(data),
+            _ => None,
+        })
+        .flat_map(|data| {
+            data.split(|&byte| !is_printable(byte))
+                .filter(|run| run.len() >= MIN_TAG_RUN_LENGTH)
+                .map(|run| run.iter().map(|&byte| byte as char).collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    if runs.is_empty() {
+        None
+    } else {
+        Some(runs.join(" "))
+    }
+}
+
+fn is_printable(byte: u8) -> bool {
+    byte >= 0x20 && byte <= 0x7E
+}
+
+impl Block {
+    /// Returns the coinbase transaction of this block, if any.
+    pub fn coinbase(&self) -> Option<&Transaction> {
+        self.transactions
+            .first()
+            .filter(|transaction| transaction.is_coinbase())
+    }
+
+    /// Returns the height this block commits to in its coinbase script, see
+    /// `read_bip34_height`.
+    ///
+    /// Only version 2 blocks and above carry a height. Note that version 2
+    /// blocks that have been mined before BIP 34 was enforced may start their
+    /// coinbase script with arbitrary data instead.
+    pub fn bip34_height(&self) -> Option<u32> {
+        if self.version < 2 {
+            return None;
+        }
+        read_bip34_height(&self.coinbase()?.inputs[0].script)
+    }
+
+    /// Returns the text tag of the coinbase script of this block, see
+    /// `read_coinbase_tag`.
+    pub fn coinbase_tag(&self) -> Option<String> {
+        let coinbase_script = &self.coinbase()?.inputs[0].script;
+        read_coinbase_tag(coinbase_script, self.bip34_height().is_some())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use data_encoding::HEXLOWER;
+    use domain::{Hash, Input};
+
+    fn block(version: u32, coinbase_script: &str) -> Block {
+        let input = Input {
+            sequence_number: u32::max_value(),
+            previous_tx_hash: Hash([0u8; 32]),
+            previous_tx_output_index: u32::max_value(),
+            script: HEXLOWER
+                .decode(coinbase_script.as_bytes())
+                .unwrap()
+                .into_boxed_slice(),
+        };
+        let transaction = Transaction {
+            tx_hash: Hash([0u8; 32]),
+            witness_hash: Hash([0u8; 32]),
+            version: 1,
+            lock_time: 0,
+            inputs: vec![input].into_boxed_slice(),
+            outputs: vec![].into_boxed_slice(),
+            script_witnesses: vec![].into_boxed_slice(),
+            size_in_bytes: 0,
+            stripped_size_in_bytes: 0,
+            weight: 0,
+        };
+        Block {
+            hash: Hash([0u8; 32]),
+            version,
+            previous_block_hash: Hash([0u8; 32]),
+            merkle_root: Hash([0u8; 32]),
+            creation_time: 0,
+            bits: 0,
+            nonce: 0,
+            transactions: vec![transaction].into_boxed_slice(),
+            index_in_blk_file: 0,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn genesis_block_has_tag_but_no_height() {
+        // given
+        let block = block(1, "04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73");
+
+        // when
+        let height = block.bip34_height();
+        let tag = block.coinbase_tag();
+
+        // then
+        assert!(block.transactions[0].is_coinbase());
+        assert_eq!(height, None);
+        assert_eq!(
+            tag,
+            Some(String::from(
+                "The Times 03/Jan/2009 Chancellor on brink of second bailout for banks"
+            ))
+        );
+    }
+
+    #[test]
+    fn reads_height_and_tag_of_version_2_block() {
+        // given
+        let block = block(0x2000_0000, "03a0bb0d0c2f736c7573682f0a7c3b01ab");
+
+        // when
+        let height = block.bip34_height();
+        let tag = block.coinbase_tag();
+
+        // then
+        assert_eq!(height, Some(900_000));
+        assert_eq!(tag, Some(String::from("/slush/")));
+    }
+
+    #[test]
+    fn reads_small_heights_from_number_opcodes() {
+        assert_eq!(
+            read_bip34_height(&[0x51, 0x04, 0x01, 0x02, 0x03, 0x04]),
+            Some(1)
+        );
+        assert_eq!(read_bip34_height(&[0x60]), Some(16));
+        assert_eq!(read_bip34_height(&[0x01, 0x11]), Some(17));
+        assert_eq!(read_bip34_height(&[0x01, 0x81]), None);
+        assert_eq!(read_bip34_height(&[]), None);
+    }
+
+    #[test]
+    fn transaction_spending_outputs_is_no_coinbase() {
+        // given
+        let mut block = block(2, "01");
+        block.transactions[0].inputs[0].previous_tx_output_index = 0;
+
+        // when
+        let coinbase = block.coinbase();
+
+        // then
+        assert!(!block.transactions[0].is_coinbase());
+        assert_eq!(coinbase, None);
+        assert_eq!(block.bip34_height(), None);
+    }
+}
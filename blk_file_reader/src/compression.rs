@@ -0,0 +1,244 @@
+use core_var_int::read_core_var_int;
+use disassembly::{MAX_SCRIPT_SIZE, OP_RETURN};
+use std::io::{self, Error, ErrorKind, Read, Result};
+use u256::U256;
+
+/// The number of special script types, see `read_compressed_script`.
+const NUMBER_OF_SPECIAL_SCRIPTS: u64 = 6;
+
+/// The prime of the field over which secp256k1 is defined.
+const SECP256K1_FIELD_PRIME: [u8; 32] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE, 0xFF, 0xFF, 0xFC, 0x2F,
+];
+
+/// Read an output in the compressed format that Bitcoin Core uses for its undo
+/// files and the UTXO set, i.e. a compressed amount followed by a compressed
+/// script.
+///
+/// Returns the value and script of the output.
+pub(crate) fn read_compressed_output<R: Read + ?Sized>(reader: &mut R) -> Result<(u64, Box<[u8]>)> {
+    let value = decompress_amount(read_core_var_int(reader)?);
+    let script = read_compressed_script(reader)?;
+    Ok((value, script))
+}
+
+/// Decompresses an amount of satoshis that has been compressed by Bitcoin Core,
+/// which shortens amounts with trailing zeros in their decimal representation.
+pub(crate) fn decompress_amount(compressed_amount: u64) -> u64 {
+    if compressed_amount == 0 {
+        return 0;
+    }
+    let mut x = compressed_amount - 1;
+    // The amount is of the form `n * 10^exponent`.
+    let exponent = x % 10;
+    x /= 10;
+    let mut n = if exponent < 9 {
+        let last_digit = x % 9 + 1;
+        x /= 9;
+        x * 10 + last_digit
+    } else {
+        x + 1
+    };
+    for _ in 0..exponent {
+        n *= 10;
+    }
+    n
+}
+
+/// Read a script that has been compressed by Bitcoin Core.
+///
+/// P2PKH, P2SH and P2PK scripts are stored as a type followed by the hash or
+/// public key, all other scripts are stored along with their length.
+///
+/// Like Bitcoin Core, scripts exceeding `MAX_SCRIPT_SIZE` are skipped and
+/// replaced by `OP_RETURN`, since they are unspendable anyway.
+pub(crate) fn read_compressed_script<R: Read + ?Sized>(reader: &mut R) -> Result<Box<[u8]>> {
+    let size = read_core_var_int(reader)?;
+    let script = match size {
+        0 => {
+            // OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG
+            let mut script = vec![0x76, 0xA9, 0x14];
+            script.extend_from_slice(&read_bytes(reader, 20)?);
+            script.extend_from_slice(&[0x88, 0xAC]);
+            script
+        }
+        1 => {
+            // OP_HASH160 <hash> OP_EQUAL
+            let mut script = vec![0xA9, 0x14];
+            script.extend_from_slice(&read_bytes(reader, 20)?);
+            script.push(0x87);
+            script
+        }
+        2 | 3 => {
+            // <compressed public key> OP_CHECKSIG
+            let mut script = vec![0x21, size as u8];
+            script.extend_from_slice(&read_bytes(reader, 32)?);
+            script.push(0xAC);
+            script
+        }
+        4 | 5 => {
+            // <uncompressed public key> OP_CHECKSIG
+            let x = read_bytes(reader, 32)?;
+            let public_key = decompress_public_key(&x, size == 5).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    "compressed public key is not on the curve",
+                )
+            })?;
+            let mut script = vec![0x41];
+            script.extend_from_slice(&public_key);
+            script.push(0xAC);
+            script
+        }
+        _ => {
+            let length = size - NUMBER_OF_SPECIAL_SCRIPTS;
+            if length > MAX_SCRIPT_SIZE as u64 {
+                skip_bytes(reader, length)?;
+                vec![OP_RETURN]
+            } else {
+                read_bytes(reader, length as usize)?
+            }
+        }
+    };
+    Ok(script.into_boxed_slice())
+}
+
+/// Calculates the uncompressed public key for the given x coordinate by
+/// solving `y^2 = x^3 + 7` for the `y` with the given parity.
+///
+/// Returns `None` if there is no point with the given x coordinate.
+fn decompress_public_key(x: &[u8], is_y_odd: bool) -> Option<[u8; 65]> {
+    let prime = U256::from_be_bytes(&SECP256K1_FIELD_PRIME);
+    let mut x_bytes = [0u8; 32];
+    x_bytes.copy_from_slice(x);
+    let x = U256::from_be_bytes(&x_bytes);
+    if x >= prime {
+        return None;
+    }
+
+    let y_squared = x
+        .mul_mod(&x, &prime)
+        .mul_mod(&x, &prime)
+        .add_mod(&U256::from_u64(7), &prime);
+    // Since the prime is congruent to 3 modulo 4, the square root is
+    // `y_squared ^ ((prime + 1) / 4)`.
+    let exponent = (prime + U256::from_u64(1)) / U256::from_u64(4);
+    let mut y = y_squared.pow_mod(&exponent, &prime);
+    if y.mul_mod(&y, &prime) != y_squared {
+        return None;
+    }
+    if y.is_odd() != is_y_odd {
+        y = prime - y;
+    }
+
+    let mut public_key = [0u8; 65];
+    public_key[0] = 0x04;
+    public_key[1..33].copy_from_slice(&x_bytes);
+    public_key[33..].copy_from_slice(&y.to_be_bytes());
+    Some(public_key)
+}
+
+fn read_bytes<R: Read + ?Sized>(reader: &mut R, length: usize) -> Result<Vec<u8>> {
+    let mut bytes = vec![0u8; length];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn skip_bytes<R: Read + ?Sized>(reader: &mut R, length: u64) -> Result<()> {
+    if io::copy(&mut Read::take(&mut *reader, length), &mut io::sink())? < length {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "compressed script is truncated",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use data_encoding::HEXLOWER;
+    use std::io::Cursor;
+
+    #[test]
+    fn decompresses_amounts() {
+        assert_eq!(decompress_amount(0x00), 0);
+        assert_eq!(decompress_amount(0x01), 1);
+        assert_eq!(decompress_amount(0x07), 1_000_000);
+        assert_eq!(decompress_amount(0x09), 100_000_000);
+        assert_eq!(decompress_amount(0x32), 5_000_000_000);
+        assert_eq!(decompress_amount(0x1406f40), 21_000_000 * 100_000_000);
+    }
+
+    #[test]
+    fn reads_compressed_p2pkh_script() {
+        // given
+        let mut compressed_script = vec![0x00];
+        compressed_script.extend_from_slice(&[0xAB; 20]);
+
+        // when
+        let script = read_compressed_script(&mut Cursor::new(compressed_script)).unwrap();
+
+        // then
+        let mut expected = vec![0x76, 0xA9, 0x14];
+        expected.extend_from_slice(&[0xAB; 20]);
+        expected.extend_from_slice(&[0x88, 0xAC]);
+        assert_eq!(&*script, &expected[..]);
+    }
+
+    #[test]
+    fn reads_uncompressed_script() {
+        // given
+        let compressed_script = vec![0x08, 0x6A, 0x01];
+
+        // when
+        let script = read_compressed_script(&mut Cursor::new(compressed_script)).unwrap();
+
+        // then
+        assert_eq!(&*script, &[0x6A, 0x01]);
+    }
+
+    #[test]
+    fn reads_compressed_p2pk_script_with_uncompressed_public_key() {
+        // given
+        // The public key of the genesis block's coinbase output, whose y
+        // coordinate is odd.
+        let public_key = HEXLOWER
+            .decode(
+                b"04678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb6\
+                  49f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5f",
+            )
+            .unwrap();
+        let mut compressed_script = vec![0x05];
+        compressed_script.extend_from_slice(&public_key[1..33]);
+
+        // when
+        let script = read_compressed_script(&mut Cursor::new(compressed_script)).unwrap();
+
+        // then
+        let mut expected = vec![0x41];
+        expected.extend_from_slice(&public_key);
+        expected.push(0xAC);
+        assert_eq!(&*script, &expected[..]);
+    }
+
+    #[test]
+    fn replaces_oversized_script_by_op_return() {
+        // given
+        // A var int of 10,007, i.e. a script of 10,001 bytes, followed by the
+        // script and the next byte.
+        let mut compressed_script = vec![0xCD, 0x17];
+        compressed_script.extend_from_slice(&[0xAB; 10_001]);
+        compressed_script.push(0x42);
+        let mut reader = Cursor::new(compressed_script);
+
+        // when
+        let script = read_compressed_script(&mut reader).unwrap();
+
+        // then
+        assert_eq!(&*script, &[OP_RETURN]);
+        assert_eq!(reader.position(), 10_003);
+    }
+}
@@ -0,0 +1,65 @@
+use byteorder::ReadBytesExt;
+use std::io::{Error, ErrorKind, Read, Result};
+
+/// Read a variable-length integer in the MSB base-128 encoding that Bitcoin
+/// Core uses for its databases and undo files.
+///
+/// In contrast to the `CompactSize` integers of the network protocol, every byte
+/// carries seven bits of the value, with the most significant bit indicating
+/// whether another byte follows. Each continuation additionally adds one, so
+/// that every value has exactly one representation.
+pub(crate) fn read_core_var_int<R: Read + ?Sized>(reader: &mut R) -> Result<u64> {
+    let mut value = 0u64;
+    loop {
+        let byte = reader.read_u8()?;
+        if value > (u64::max_value() >> 7) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "variable-length integer is too large",
+            ));
+        }
+        value = (value << 7) | u64::from(byte & 0x7F);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        value = value.checked_add(1).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                "variable-length integer is too large",
+            )
+        })?;
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::io::Cursor;
+
+    fn read(bytes: &[u8]) -> u64 {
+        read_core_var_int(&mut Cursor::new(bytes)).unwrap()
+    }
+
+    #[test]
+    fn reads_single_byte_values() {
+        assert_eq!(read(&[0x00]), 0);
+        assert_eq!(read(&[0x7F]), 127);
+    }
+
+    #[test]
+    fn reads_multi_byte_values() {
+        assert_eq!(read(&[0x80, 0x00]), 128);
+        assert_eq!(read(&[0x80, 0x7F]), 255);
+        assert_eq!(read(&[0xFE, 0x7F]), 16383);
+        assert_eq!(read(&[0xFF, 0x00]), 16384);
+        assert_eq!(read(&[0x82, 0xFE, 0x7F]), 65535);
+        assert_eq!(read(&[0x8E, 0xFE, 0xFE, 0xFF, 0x00]), 4294967296);
+    }
+
+    #[test]
+    fn when_value_is_truncated_then_reports_eof() {
+        let result = read_core_var_int(&mut Cursor::new(&[0x80u8]));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,496 @@
+use byteorder::{ByteOrder, LittleEndian};
+use data_encoding::HEXLOWER;
+use domain::{Input, Output};
+use std::error::Error;
+use std::fmt;
+
+const OP_PUSHDATA1: u8 = 0x4C;
+const OP_PUSHDATA2: u8 = 0x4D;
+const OP_PUSHDATA4: u8 = 0x4E;
+pub(crate) const OP_RETURN: u8 = 0x6A;
+
+/// Scripts larger than this are unspendable, see `MAX_SCRIPT_SIZE` of Bitcoin
+/// Core.
+pub(crate) const MAX_SCRIPT_SIZE: usize = 10_000;
+
+/// A single opcode of a bitcoin script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Opcode(pub u8);
+
+impl Opcode {
+    /// Whether this opcode pushes the data that follows it, i.e. `OP_0`,
+    /// a direct push of 1 to 75 bytes or one of the `OP_PUSHDATA` opcodes.
+    pub fn is_push(self) -> bool {
+        self.0 <= OP_PUSHDATA4
+    }
+
+    /// The name of this opcode as used by Bitcoin Core, e.g. `OP_CHECKSIG`.
+    ///
+    /// Like in Bitcoin Core, the opcodes that push a small number are named
+    /// after that number, e.g. `0`, `-1` or `16`.
+    pub fn name(self) -> &'static str {
+        match self.0 {
+            0x00 => "0",
+            0x4C => "OP_PUSHDATA1",
+            0x4D => "OP_PUSHDATA2",
+            0x4E => "OP_PUSHDATA4",
+            0x4F => "-1",
+            0x50 => "OP_RESERVED",
+            0x51 => "1",
+            0x52 => "2",
+            0x53 => "3",
+            0x54 => "4",
+            0x55 => "5",
+            0x56 => "6",
+            0x57 => "7",
+            0x58 => "8",
+            0x59 => "9",
+            0x5A => "10",
+            0x5B => "11",
+            0x5C => "12",
+            0x5D => "13",
+            0x5E => "14",
+            0x5F => "15",
+            0x60 => "16",
+            0x61 => "OP_NOP",
+            0x62 => "OP_VER",
+            0x63 => "OP_IF",
+            0x64 => "OP_NOTIF",
+            0x65 => "OP_VERIF",
+            0x66 => "OP_VERNOTIF",
+            0x67 => "OP_ELSE",
+            0x68 => "OP_ENDIF",
+            0x69 => "OP_VERIFY",
+            0x6A => "OP_RETURN",
+            0x6B => "OP_TOALTSTACK",
+            0x6C => "OP_FROMALTSTACK",
+            0x6D => "OP_2DROP",
+            0x6E => "OP_2DUP",
+            0x6F => "OP_3DUP",
+            0x70 => "OP_2OVER",
+            0x71 => "OP_2ROT",
+            0x72 => "OP_2SWAP",
+            0x73 => "OP_IFDUP",
+            0x74 => "OP_DEPTH",
+            0x75 => "OP_DROP",
+            0x76 => "OP_DUP",
+            0x77 => "OP_NIP",
+            0x78 => "OP_OVER",
+            0x79 => "OP_PICK",
+            0x7A => "OP_ROLL",
+            0x7B => "OP_ROT",
+            0x7C => "OP_SWAP",
+            0x7D => "OP_TUCK",
+            0x7E => "OP_CAT",
+            0x7F => "OP_SUBSTR",
+            0x80 => "OP_LEFT",
+            0x81 => "OP_RIGHT",
+            0x82 => "OP_SIZE",
+            0x83 => "OP_INVERT",
+            0x84 => "OP_AND",
+            0x85 => "OP_OR",
+            0x86 => "OP_XOR",
+            0x87 => "OP_EQUAL",
+            0x88 => "OP_EQUALVERIFY",
+            0x89 => "OP_RESERVED1",
+            0x8A => "OP_RESERVED2",
+            0x8B => "OP_1ADD",
+            0x8C => "OP_1SUB",
+            0x8D => "OP_2MUL",
+            0x8E => "OP_2DIV",
+            0x8F => "OP_NEGATE",
+            0x90 => "OP_ABS",
+            0x91 => "OP_NOT",
+            0x92 => "OP_0NOTEQUAL",
+            0x93 => "OP_ADD",
+            0x94 => "OP_SUB",
+            0x95 => "OP_MUL",
+            0x96 => "OP_DIV",
+            0x97 => "OP_MOD",
+            0x98 => "OP_LSHIFT",
+            0x99 => "OP_RSHIFT",
+            0x9A => "OP_BOOLAND",
+            0x9B => "OP_BOOLOR",
+            0x9C => "OP_NUMEQUAL",
+            0x9D => "OP_NUMEQUALVERIFY",
+            0x9E => "OP_NUMNOTEQUAL",
+            0x9F => "OP_LESSTHAN",
+            0xA0 => "OP_GREATERTHAN",
+            0xA1 => "OP_LESSTHANOREQUAL",
+            0xA2 => "OP_GREATERTHANOREQUAL",
+            0xA3 => "OP_MIN",
+            0xA4 => "OP_MAX",
+            0xA5 => "OP_WITHIN",
+            0xA6 => "OP_RIPEMD160",
+            0xA7 => "OP_SHA1",
+            0xA8 => "OP_SHA256",
+            0xA9 => "OP_HASH160",
+            0xAA => "OP_HASH256",
+            0xAB => "OP_CODESEPARATOR",
+            0xAC => "OP_CHECKSIG",
+            0xAD => "OP_CHECKSIGVERIFY",
+            0xAE => "OP_CHECKMULTISIG",
+            0xAF => "OP_CHECKMULTISIGVERIFY",
+            0xB0 => "OP_NOP1",
+            0xB1 => "OP_CHECKLOCKTIMEVERIFY",
+            0xB2 => "OP_CHECKSEQUENCEVERIFY",
+            0xB3 => "OP_NOP4",
+            0xB4 => "OP_NOP5",
+            0xB5 => "OP_NOP6",
+            0xB6 => "OP_NOP7",
+            0xB7 => "OP_NOP8",
+            0xB8 => "OP_NOP9",
+            0xB9 => "OP_NOP10",
+            0xBA => "OP_CHECKSIGADD",
+            0xFF => "OP_INVALIDOPCODE",
+            _ => "OP_UNKNOWN",
+        }
+    }
+}
+
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// An instruction of a bitcoin script, see `decode_script`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction<'a> {
+    /// Pushes the given data, which may be empty in case of `OP_0`.
+    Push { opcode: Opcode, data: &'a [u8] },
+    /// Any opcode that is not followed by data, including `OP_1` to `OP_16`.
+    Op(Opcode),
+}
+
+/// Returned if a push extends beyond the end of its script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptDecodeError {
+    /// The offset of the push opcode within the script.
+    pub offset: usize,
+}
+
+impl fmt::Display for ScriptDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "push at offset {} exceeds the end of the script",
+            self.offset
+        )
+    }
+}
+
+impl Error for ScriptDecodeError {}
+
+/// Decodes the given script into its instructions.
+///
+/// Scripts may contain arbitrary bytes, which is why decoding stops with an
+/// error at the first push that exceeds the end of the script.
+pub fn decode_script<'a>(script: &'a [u8]) -> Instructions<'a> {
+    Instructions {
+        script,
+        position: 0,
+    }
+}
+
+/// Allows for iterating over the instructions of a script.
+pub struct Instructions<'a> {
+    script: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Instructions<'a> {
+    fn read_push_length(&mut self, opcode: u8) -> Option<usize> {
+        let length_size = match opcode {
+            OP_PUSHDATA1 => 1,
+            OP_PUSHDATA2 => 2,
+            OP_PUSHDATA4 => 4,
+            _ => return Some(opcode as usize),
+        };
+        let length_bytes = self
+            .script
+            .get(self.position..self.position + length_size)?;
+        self.position += length_size;
+        Some(LittleEndian::read_uint(length_bytes, length_size) as usize)
+    }
+}
+
+impl<'a> Iterator for Instructions<'a> {
+    type Item = Result<Instruction<'a>, ScriptDecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.position;
+        let opcode = *self.script.get(offset)?;
+        self.position += 1;
+
+        if !Opcode(opcode).is_push() {
+            return Some(Ok(Instruction::Op(Opcode(opcode))));
+        }
+
+        let data = self.read_push_length(opcode).and_then(|length| {
+            let end = self.position.checked_add(length)?;
+            self.script.get(self.position..end)
+        });
+        match data {
+            Some(data) => {
+                self.position += data.len();
+                Some(Ok(Instruction::Push {
+                    opcode: Opcode(opcode),
+                    data,
+                }))
+            }
+            None => {
+                // Do not attempt to decode any further instructions.
+                self.position = self.script.len();
+                Some(Err(ScriptDecodeError { offset }))
+            }
+        }
+    }
+}
+
+/// Formats the given script in the ASM notation of Bitcoin Core.
+///
+/// Pushes of up to 4 bytes are shown as numbers and longer pushes as hex,
+/// while all other opcodes are shown by their name. A push that exceeds the
+/// end of the script is shown as `[error]`.
+pub fn script_to_asm(script: &[u8]) -> String {
+    format_asm(script, false)
+}
+
+/// Formats the given input script like `script_to_asm`, additionally showing
+/// the signature hash type of signatures, e.g. `[ALL]`.
+///
+/// This matches the notation Bitcoin Core uses for the `scriptSig` of inputs.
+pub fn input_script_to_asm(script: &[u8]) -> String {
+    format_asm(script, true)
+}
+
+fn format_asm(script: &[u8], decode_sighash: bool) -> String {
+    let decode_sighash = decode_sighash && !is_unspendable(script);
+    let mut asm = String::new();
+    for instruction in decode_script(script) {
+        if !asm.is_empty() {
+            asm.push(' ');
+        }
+        match instruction {
+            Ok(Instruction::Push { data, .. }) if data.len() <= 4 => {
+                asm.push_str(&decode_script_number(data).to_string());
+            }
+            Ok(Instruction::Push { data, .. }) => {
+                let sighash_type = if decode_sighash && is_signature(data) {
+                    sighash_type_name(data[data.len() - 1])
+                } else {
+                    None
+                };
+                match sighash_type {
+                    Some(sighash_type) => {
+                        asm.push_str(&HEXLOWER.encode(&data[..data.len() - 1]));
+                        asm.push('[');
+                        asm.push_str(sighash_type);
+                        asm.push(']');
+                    }
+                    None => asm.push_str(&HEXLOWER.encode(data)),
+                }
+            }
+            Ok(Instruction::Op(opcode)) => asm.push_str(opcode.name()),
+            Err(_) => asm.push_str("[error]"),
+        }
+    }
+    asm
+}
+
+/// Whether the given script can never be spent, see `CScript::IsUnspendable`.
+fn is_unspendable(script: &[u8]) -> bool {
+    script.first() == Some(&OP_RETURN) || script.len() > MAX_SCRIPT_SIZE
+}
+
+/// Decodes a little-endian number with a sign bit, as pushed onto the stack.
+fn decode_script_number(bytes: &[u8]) -> i64 {
+    let mut number = 0i64;
+    for (i, &byte) in bytes.iter().enumerate() {
+        number |= i64::from(byte) << (8 * i);
+    }
+    match bytes.last() {
+        Some(&last) if last & 0x80 != 0 => -(number & !(0x80i64 << (8 * (bytes.len() - 1)))),
+        _ => number,
+    }
+}
+
+/// Whether the given push is a strictly DER encoded signature followed by a
+/// defined signature hash type, see [BIP 66](https://github.com/bitcoin/bips/blob/master/bip-0066.mediawiki).
+pub(crate) fn is_signature(signature: &[u8]) -> bool {
+    let length = signature.len();
+    if length < 9 || length > 73 {
+        return false;
+    }
+    if signature[0] != 0x30 || signature[1] as usize != length - 3 {
+        return false;
+    }
+
+    let r_length = signature[3] as usize;
+    if 5 + r_length >= length {
+        return false;
+    }
+    let s_length = signature[5 + r_length] as usize;
+    if r_length + s_length + 7 != length {
+        return false;
+    }
+
+    if signature[2] != 0x02 || r_length == 0 || signature[4] & 0x80 != 0 {
+        return false;
+    }
+    if r_length > 1 && signature[4] == 0x00 && signature[5] & 0x80 == 0 {
+        return false;
+    }
+
+    if signature[r_length + 4] != 0x02 || s_length == 0 || signature[r_length + 6] & 0x80 != 0 {
+        return false;
+    }
+    if s_length > 1 && signature[r_length + 6] == 0x00 && signature[r_length + 7] & 0x80 == 0 {
+        return false;
+    }
+
+    true
+}
+
+fn sighash_type_name(sighash_type: u8) -> Option<&'static str> {
+    match sighash_type {
+        0x01 => Some("ALL"),
+        0x02 => Some("NONE"),
+        0x03 => Some("SINGLE"),
+        0x81 => Some("ALL|ANYONECANPAY"),
+        0x82 => Some("NONE|ANYONECANPAY"),
+        0x83 => Some("SINGLE|ANYONECANPAY"),
+        _ => None,
+    }
+}
+
+impl Input {
+    /// Returns the script of this input in ASM notation, see
+    /// `input_script_to_asm`.
+    pub fn script_asm(&self) -> String {
+        input_script_to_asm(&self.script)
+    }
+}
+
+impl Output {
+    /// Returns the script of this output in ASM notation, see
+    /// `script_to_asm`.
+    pub fn script_asm(&self) -> String {
+        script_to_asm(&self.script)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn decodes_pushes_and_opcodes() {
+        // given
+        let script = [0x00, 0x4C, 0x02, 0xAB, 0xCD, 0x51, 0xAC];
+
+        // when
+        let instructions: Result<Vec<_>, _> = decode_script(&script).collect();
+
+        // then
+        assert_eq!(
+            instructions.unwrap(),
+            vec![
+                Instruction::Push {
+                    opcode: Opcode(0x00),
+                    data: &[],
+                },
+                Instruction::Push {
+                    opcode: Opcode(0x4C),
+                    data: &[0xAB, 0xCD],
+                },
+                Instruction::Op(Opcode(0x51)),
+                Instruction::Op(Opcode(0xAC)),
+            ]
+        );
+    }
+
+    #[test]
+    fn when_push_exceeds_script_then_reports_error_and_stops() {
+        // given
+        let script = [0x76, 0x4D, 0x05, 0x00, 0x01, 0x76];
+
+        // when
+        let instructions: Vec<_> = decode_script(&script).collect();
+
+        // then
+        assert_eq!(
+            instructions,
+            vec![
+                Ok(Instruction::Op(Opcode(0x76))),
+                Err(ScriptDecodeError { offset: 1 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_p2pkh_script_as_asm() {
+        // given
+        let script = HEXLOWER
+            .decode(b"76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac")
+            .unwrap();
+
+        // when
+        let asm = script_to_asm(&script);
+
+        // then
+        assert_eq!(
+            asm,
+            "OP_DUP OP_HASH160 62e907b15cbf27d5425399ebf6f0fb50ebb88f18 OP_EQUALVERIFY OP_CHECKSIG"
+        );
+    }
+
+    #[test]
+    fn formats_small_pushes_as_numbers() {
+        // given
+        let script = [
+            0x00, 0x4F, 0x60, 0x01, 0x81, 0x02, 0xFF, 0x00, 0x03, 0x4B, 0x2F, 0x06,
+        ];
+
+        // when
+        let asm = script_to_asm(&script);
+
+        // then
+        assert_eq!(asm, "0 -1 16 -1 255 405323");
+    }
+
+    #[test]
+    fn formats_truncated_push_as_error() {
+        assert_eq!(script_to_asm(&[0x6A, 0x05, 0x01]), "OP_RETURN [error]");
+    }
+
+    #[test]
+    fn formats_unknown_opcodes() {
+        assert_eq!(
+            script_to_asm(&[0xB1, 0xBA, 0xC0, 0xFF]),
+            "OP_CHECKLOCKTIMEVERIFY OP_CHECKSIGADD OP_UNKNOWN OP_INVALIDOPCODE"
+        );
+    }
+
+    #[test]
+    fn formats_signature_hash_type_of_input_scripts() {
+        // given
+        let signature = "3044022057dbbf2a5b5ac4ce32c8bc4dfd9c5f0bc2b8d2bd14bd8e43a4d3b6ec0bbc4b6c02200f9e6a0c5d5d1d6f3a6b6f2a8f5b0c8d3a5e7c9b1d2f4a6c8e0b2d4f6a8c0e2d01";
+        let script = HEXLOWER
+            .decode(format!("47{}", signature).as_bytes())
+            .unwrap();
+
+        // when
+        let input_asm = input_script_to_asm(&script);
+        let output_asm = script_to_asm(&script);
+
+        // then
+        assert_eq!(
+            input_asm,
+            format!("{}[ALL]", &signature[..signature.len() - 2])
+        );
+        assert_eq!(output_asm, signature);
+    }
+}
@@ -1,14 +1,33 @@
+use super::AddressPayload;
+
 /// Represents a Bitcoin address.
 ///
 /// For more information, see the according Bitcoin wiki pages on
 /// [addresses](https://en.bitcoin.it/wiki/Address) and the
-/// [Base58Check encoding](https://en.bitcoin.it/wiki/Base58Check_encoding).
+/// [Base58Check encoding](https://en.bitcoin.it/wiki/Base58Check_encoding), as
+/// well as [BIP 173](https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki)
+/// and [BIP 350](https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki)
+/// for native SegWit addresses.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Address {
-    /// The raw 160-bit hash of the Bitcoin address.
-    /// TODO Use a wrapper-type to represent address hashes.
-    pub hash: [u8; 20],
+    /// The hash or witness program that is encoded by the Bitcoin address.
+    pub payload: AddressPayload,
+
+    /// The encoded address, i.e. the string representation that is commonly
+    /// shown to users.
+    pub encoded: String,
+
+    /// The encoding that has been used for `Address::encoded`.
+    pub encoding: AddressEncoding,
+}
 
-    /// The Base58Check-encoded `Address::hash`.
-    pub base58check: String,
+/// The encodings that are used for the string representation of addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AddressEncoding {
+    /// Used for P2PKH and P2SH addresses.
+    Base58Check,
+    /// Used for version 0 witness programs, i.e. P2WPKH and P2WSH addresses.
+    Bech32,
+    /// Used for witness programs of version 1 and higher, e.g. P2TR addresses.
+    Bech32m,
 }
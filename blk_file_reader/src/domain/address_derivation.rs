@@ -0,0 +1,9 @@
+/// Describes how the address of an output has been obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AddressDerivation {
+    /// The address is contained in the output script, e.g. the public key hash
+    /// of a P2PKH output or the witness program of a P2WSH output.
+    Direct,
+    /// The address is the P2PKH address of the public key of a P2PK output.
+    FromPublicKey,
+}
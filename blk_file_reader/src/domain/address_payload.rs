@@ -0,0 +1,80 @@
+/// The raw data that is encoded within an address.
+///
+/// Legacy addresses carry a 160-bit hash of a public key or script, whereas
+/// native SegWit addresses carry a versioned witness program (see
+/// [BIP 141](https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki#witness-program)).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AddressPayload {
+    /// The hash of a public key, as used by P2PKH addresses.
+    PubKeyHash([u8; 20]),
+
+    /// The hash of a script, as used by P2SH addresses.
+    ScriptHash([u8; 20]),
+
+    /// A witness program, as used by P2WPKH, P2WSH and P2TR addresses.
+    WitnessProgram {
+        version: u8,
+        program: WitnessProgram,
+    },
+}
+
+/// The program of a witness output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WitnessProgram {
+    /// A 20-byte program, i.e. the public key hash of a P2WPKH output.
+    Program20([u8; 20]),
+
+    /// A 32-byte program, i.e. the script hash of a P2WSH output or the
+    /// output key of a P2TR output.
+    Program32([u8; 32]),
+
+    /// A program of any other length, as allowed for future witness versions.
+    Other(Vec<u8>),
+}
+
+impl AddressPayload {
+    /// Returns the raw bytes of the hash or witness program.
+    pub fn as_bytes(&self) -> &[u8] {
+        match *self {
+            AddressPayload::PubKeyHash(ref hash) => hash,
+            AddressPayload::ScriptHash(ref hash) => hash,
+            AddressPayload::WitnessProgram { ref program, .. } => program.as_bytes(),
+        }
+    }
+
+    /// Returns the witness version, or `None` if this is not a witness program.
+    pub fn witness_version(&self) -> Option<u8> {
+        match *self {
+            AddressPayload::WitnessProgram { version, .. } => Some(version),
+            _ => None,
+        }
+    }
+}
+
+impl WitnessProgram {
+    /// Creates a `WitnessProgram` from the given bytes.
+    pub fn from_bytes(bytes: &[u8]) -> WitnessProgram {
+        match bytes.len() {
+            20 => {
+                let mut program = [0u8; 20];
+                program.copy_from_slice(bytes);
+                WitnessProgram::Program20(program)
+            }
+            32 => {
+                let mut program = [0u8; 32];
+                program.copy_from_slice(bytes);
+                WitnessProgram::Program32(program)
+            }
+            _ => WitnessProgram::Other(bytes.to_vec()),
+        }
+    }
+
+    /// Returns the raw bytes of this witness program.
+    pub fn as_bytes(&self) -> &[u8] {
+        match *self {
+            WitnessProgram::Program20(ref program) => program,
+            WitnessProgram::Program32(ref program) => program,
+            WitnessProgram::Other(ref program) => program,
+        }
+    }
+}
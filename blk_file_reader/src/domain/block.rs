@@ -12,4 +12,41 @@ pub struct Block {
     pub nonce: u32,
     pub transactions: Box<[Transaction]>,
     pub index_in_blk_file: usize,
+    /// The offset of the block's magic number within its blk file.
+    pub offset_in_blk_file: u64,
+    /// The size of the serialized block, excluding its magic number and size.
+    pub size_in_bytes: u32,
+}
+
+impl Block {
+    /// The offset within the blk file right after this block, i.e. where the
+    /// next block starts.
+    pub fn end_offset_in_blk_file(&self) -> u64 {
+        self.offset_in_blk_file + 8 + u64::from(self.size_in_bytes)
+    }
+
+    /// The size of this block serialized without the witnesses of its
+    /// transactions.
+    pub fn stripped_size_in_bytes(&self) -> u32 {
+        let witness_size: u32 = self
+            .transactions
+            .iter()
+            .map(|transaction| transaction.size_in_bytes - transaction.stripped_size_in_bytes)
+            .sum();
+        self.size_in_bytes - witness_size
+    }
+
+    /// The weight of this block as defined by [BIP 141](https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki),
+    /// which must not exceed 4,000,000.
+    pub fn weight(&self) -> u32 {
+        self.stripped_size_in_bytes() * 3 + self.size_in_bytes
+    }
+
+    /// The number of transactions of this block that carry witnesses.
+    pub fn number_of_segwit_transactions(&self) -> usize {
+        self.transactions
+            .iter()
+            .filter(|transaction| transaction.is_segwit())
+            .count()
+    }
 }
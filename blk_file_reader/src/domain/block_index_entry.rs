@@ -0,0 +1,53 @@
+use super::{ChainWork, Hash};
+
+/// The block has been fully stored in a blk file.
+pub(crate) const BLOCK_HAVE_DATA: u32 = 8;
+/// The undo data of the block has been stored in a rev file.
+pub(crate) const BLOCK_HAVE_UNDO: u32 = 16;
+/// The block, or one of its ancestors, failed validation.
+const BLOCK_FAILED_MASK: u32 = 32 | 64;
+
+/// An entry of Bitcoin Core's block index, which records where a block is
+/// stored and how far it has been validated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockIndexEntry {
+    pub hash: Hash,
+    pub height: u32,
+    /// The validation and storage flags of Bitcoin Core's `BlockStatus`.
+    pub status: u32,
+    pub number_of_transactions: u32,
+    /// The number of the blk and rev file that contain the block, e.g. `1208`
+    /// for `blk01208.dat`.
+    pub file_number: Option<u32>,
+    /// The offset of the block within its blk file, pointing past the magic
+    /// number and block size.
+    pub data_offset: Option<u32>,
+    /// The offset of the block's undo data within its rev file.
+    pub undo_offset: Option<u32>,
+    pub version: u32,
+    pub previous_block_hash: Hash,
+    pub merkle_root: Hash,
+    pub creation_time: u32,
+    pub bits: u32,
+    pub nonce: u32,
+    /// The total work of the chain up to and including this block, as
+    /// calculated by `BlockIndex`.
+    pub chain_work: ChainWork,
+}
+
+impl BlockIndexEntry {
+    /// Whether the block is stored in a blk file.
+    pub fn has_data(&self) -> bool {
+        self.status & BLOCK_HAVE_DATA != 0
+    }
+
+    /// Whether the undo data of the block is stored in a rev file.
+    pub fn has_undo(&self) -> bool {
+        self.status & BLOCK_HAVE_UNDO != 0
+    }
+
+    /// Whether the block or one of its ancestors is known to be invalid.
+    pub fn is_failed(&self) -> bool {
+        self.status & BLOCK_FAILED_MASK != 0
+    }
+}
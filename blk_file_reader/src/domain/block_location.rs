@@ -0,0 +1,25 @@
+/// Where a block is stored within a directory of blk files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockLocation {
+    /// The index of the blk file, e.g. `1208` for `blk01208.dat`.
+    pub blk_file_index: usize,
+    pub index_in_blk_file: usize,
+    /// The offset of the block's magic number within its blk file.
+    pub offset_in_blk_file: u64,
+}
+
+/// Where a transaction is stored within a directory of blk files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionLocation {
+    /// The location of the block that contains the transaction.
+    pub block: BlockLocation,
+    /// The position of the transaction among the transactions of its block.
+    pub index_in_block: usize,
+}
+
+impl BlockLocation {
+    /// The file name of the blk file, e.g. `blk01208.dat`.
+    pub fn blk_file_name(&self) -> String {
+        format!("blk{:05}.dat", self.blk_file_index)
+    }
+}
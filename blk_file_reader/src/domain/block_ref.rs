@@ -0,0 +1,45 @@
+use super::{Block, Hash, TransactionRef};
+use read_options::ReadOptions;
+
+/// A `Block` whose scripts and witnesses borrow from the underlying blk file
+/// data instead of being copied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockRef<'a> {
+    pub hash: Hash,
+    pub version: u32,
+    pub previous_block_hash: Hash,
+    pub merkle_root: Hash,
+    pub creation_time: u32,
+    pub bits: u32,
+    pub nonce: u32,
+    pub transactions: Box<[TransactionRef<'a>]>,
+    pub index_in_blk_file: usize,
+    /// The offset of the block's magic number within its blk file.
+    pub offset_in_blk_file: u64,
+    /// The size of the serialized block, excluding its magic number and size.
+    pub size_in_bytes: u32,
+}
+
+impl<'a> BlockRef<'a> {
+    /// Copies this block into an owned `Block`.
+    pub fn to_block(&self, options: ReadOptions) -> Block {
+        Block {
+            hash: self.hash.clone(),
+            version: self.version,
+            previous_block_hash: self.previous_block_hash.clone(),
+            merkle_root: self.merkle_root.clone(),
+            creation_time: self.creation_time,
+            bits: self.bits,
+            nonce: self.nonce,
+            transactions: self
+                .transactions
+                .iter()
+                .map(|transaction| transaction.to_transaction(options))
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+            index_in_blk_file: self.index_in_blk_file,
+            offset_in_blk_file: self.offset_in_blk_file,
+            size_in_bytes: self.size_in_bytes,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use super::{Address, AddressDerivation, ScriptType};
+
+/// The outputs that have been spent by the transactions of a block, as stored
+/// by Bitcoin Core in rev files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockUndo {
+    /// One entry for every transaction of the block except for the coinbase
+    /// transaction, in the order of the transactions.
+    pub transactions: Box<[TransactionUndo]>,
+}
+
+/// The outputs that have been spent by a transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionUndo {
+    /// One entry for every input of the transaction, in the order of the
+    /// inputs.
+    pub spent_outputs: Box<[SpentOutput]>,
+}
+
+/// An output that has been spent by an input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpentOutput {
+    /// The height of the block that contains the transaction which created the
+    /// output.
+    pub height: u32,
+    /// Whether the output has been created by a coinbase transaction.
+    pub is_coinbase: bool,
+    pub value: u64,
+    pub address: Option<Address>,
+    /// How `address` has been obtained, `None` if there is no address.
+    pub address_derivation: Option<AddressDerivation>,
+    pub script: Box<[u8]>,
+    pub script_type: ScriptType,
+}
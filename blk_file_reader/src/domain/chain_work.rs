@@ -0,0 +1,81 @@
+use data_encoding::HEXLOWER;
+use std::fmt;
+use u256::U256;
+
+/// The expected number of hashes that were required to produce a chain of
+/// blocks, as 256-bit big-endian integer.
+///
+/// Bitcoin Core considers the valid chain with the most chain work to be the
+/// best chain.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ChainWork(pub [u8; 32]);
+
+impl ChainWork {
+    pub fn zero() -> ChainWork {
+        ChainWork([0u8; 32])
+    }
+
+    /// Calculates the work of a single block with the given `bits`, i.e.
+    /// `2^256 / (target + 1)`.
+    ///
+    /// Returns zero if `bits` does not encode a valid target.
+    pub fn from_bits(bits: u32) -> ChainWork {
+        match U256::from_compact(bits) {
+            Some(target) if !target.is_zero() => {
+                // 2^256 does not fit into 256 bits, but it is equal to
+                // (2^256 - target - 1) / (target + 1) + 1.
+                let work = (!target / (target + U256::from_u64(1))) + U256::from_u64(1);
+                ChainWork(work.to_be_bytes())
+            }
+            _ => ChainWork::zero(),
+        }
+    }
+}
+
+impl<'a> ::std::ops::Add<&'a ChainWork> for &'a ChainWork {
+    type Output = ChainWork;
+
+    fn add(self, other: &ChainWork) -> ChainWork {
+        let sum = U256::from_be_bytes(&self.0) + U256::from_be_bytes(&other.0);
+        ChainWork(sum.to_be_bytes())
+    }
+}
+
+impl fmt::Debug for ChainWork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", HEXLOWER.encode(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn calculates_work_of_genesis_block() {
+        // when
+        let chain_work = ChainWork::from_bits(0x1d00ffff);
+
+        // then
+        assert_eq!(
+            format!("{:?}", chain_work),
+            "0000000000000000000000000000000000000000000000000000000100010001"
+        );
+    }
+
+    #[test]
+    fn adds_chain_work() {
+        // given
+        let chain_work = ChainWork::from_bits(0x1d00ffff);
+
+        // when
+        let sum = &chain_work + &chain_work;
+
+        // then
+        assert_eq!(
+            format!("{:?}", sum),
+            "0000000000000000000000000000000000000000000000000000000200020002"
+        );
+    }
+}
@@ -1,9 +1,29 @@
-use data_encoding::HEXLOWER;
+use data_encoding::{HEXLOWER, HEXLOWER_PERMISSIVE};
 use std::fmt;
+use std::io;
 
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Hash(pub [u8; 32]);
 
+impl Hash {
+    /// Parses a hash from its hex representation, as shown by block explorers
+    /// and Bitcoin Core's RPC interface.
+    pub fn from_hex(hex: &str) -> io::Result<Hash> {
+        let bytes = HEXLOWER_PERMISSIVE
+            .decode(hex.trim().as_bytes())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        if bytes.len() != 32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected 32 bytes, got {}", bytes.len()),
+            ));
+        }
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&bytes);
+        Ok(Hash(hash))
+    }
+}
+
 impl fmt::Debug for Hash {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", HEXLOWER.encode(&self.0))
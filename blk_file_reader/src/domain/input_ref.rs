@@ -0,0 +1,22 @@
+use super::{Hash, Input};
+
+/// An `Input` whose script borrows from the underlying blk file data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputRef<'a> {
+    pub sequence_number: u32,
+    pub previous_tx_hash: Hash,
+    pub previous_tx_output_index: u32,
+    pub script: &'a [u8],
+}
+
+impl<'a> InputRef<'a> {
+    /// Copies this input into an owned `Input`.
+    pub fn to_input(&self) -> Input {
+        Input {
+            sequence_number: self.sequence_number,
+            previous_tx_hash: self.previous_tx_hash.clone(),
+            previous_tx_output_index: self.previous_tx_output_index,
+            script: Box::from(self.script),
+        }
+    }
+}
@@ -1,16 +1,52 @@
 mod address;
+mod address_derivation;
+mod address_payload;
 mod block;
+mod block_index_entry;
+mod block_location;
+mod block_ref;
+mod block_undo;
+mod chain_work;
 mod hash;
 mod input;
+mod input_ref;
+mod multisig;
+mod network;
 mod output;
+mod output_ref;
+mod script_type;
 mod script_witness;
+mod script_witness_ref;
+mod spend_type;
+mod taproot_spend;
 mod transaction;
+mod transaction_ref;
+mod utxo;
 
-pub use self::address::Address;
+pub use self::address::{Address, AddressEncoding};
+pub use self::address_derivation::AddressDerivation;
+pub use self::address_payload::{AddressPayload, WitnessProgram};
 pub use self::block::Block;
+pub use self::block_index_entry::BlockIndexEntry;
+pub(crate) use self::block_index_entry::{BLOCK_HAVE_DATA, BLOCK_HAVE_UNDO};
+pub use self::block_location::{BlockLocation, TransactionLocation};
+pub use self::block_ref::BlockRef;
+pub use self::block_undo::{BlockUndo, SpentOutput, TransactionUndo};
+pub use self::chain_work::ChainWork;
 pub use self::hash::Hash;
 pub use self::input::Input;
+pub use self::input_ref::InputRef;
+pub use self::multisig::{Multisig, MultisigParticipant};
+pub use self::network::{Network, UnknownNetworkError};
 pub use self::output::Output;
+pub use self::output_ref::OutputRef;
+pub use self::script_type::ScriptType;
+pub(crate) use self::script_type::{is_valid_public_key_size, read_multisig, read_witness_program};
 pub use self::script_witness::ScriptWitness;
 pub use self::script_witness::ScriptWitnessItem;
+pub use self::script_witness_ref::{ScriptWitnessItemRefs, ScriptWitnessRef};
+pub use self::spend_type::SpendType;
+pub use self::taproot_spend::{ControlBlock, TaprootSpend, TAPSCRIPT_LEAF_VERSION};
 pub use self::transaction::Transaction;
+pub use self::transaction_ref::TransactionRef;
+pub use self::utxo::{Utxo, UtxoId};
@@ -0,0 +1,19 @@
+use super::Address;
+
+/// The participants of a bare m-of-n multisig output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Multisig {
+    /// The number of signatures required to spend the output (m).
+    pub required_signatures: u8,
+    /// The participants in the order in which their public keys appear in the
+    /// script (n).
+    pub participants: Box<[MultisigParticipant]>,
+}
+
+/// A single public key of a bare multisig output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultisigParticipant {
+    pub public_key: Box<[u8]>,
+    /// The P2PKH address of the public key.
+    pub address: Address,
+}
@@ -0,0 +1,104 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// The Bitcoin network that a blk file belongs to.
+///
+/// The network determines the magic number that prefixes every block within a
+/// blk file as well as the version bytes and human-readable parts that are used
+/// for encoding addresses.
+/// For more information, see the according Bitcoin wiki page on
+/// [network magic numbers](https://en.bitcoin.it/wiki/Protocol_documentation#Message_structure).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    /// The magic number which identifies blocks of this network, as read in
+    /// little-endian byte order.
+    pub fn magic_number(&self) -> u32 {
+        match *self {
+            Network::Mainnet => 0xD9B4BEF9,
+            Network::Testnet => 0x0709110B,
+            Network::Signet => 0x40CF030A,
+            Network::Regtest => 0xDAB5BFFA,
+        }
+    }
+
+    /// The version byte of base58check-encoded pay-to-pubkey-hash addresses.
+    pub fn pub_key_hash_address_version(&self) -> u8 {
+        match *self {
+            Network::Mainnet => 0x00,
+            Network::Testnet | Network::Signet | Network::Regtest => 0x6F,
+        }
+    }
+
+    /// The version byte of base58check-encoded pay-to-script-hash addresses.
+    pub fn script_hash_address_version(&self) -> u8 {
+        match *self {
+            Network::Mainnet => 0x05,
+            Network::Testnet | Network::Signet | Network::Regtest => 0xC4,
+        }
+    }
+
+    /// The human-readable part of Bech32- and Bech32m-encoded SegWit addresses.
+    pub fn bech32_hrp(&self) -> &'static str {
+        match *self {
+            Network::Mainnet => "bc",
+            Network::Testnet | Network::Signet => "tb",
+            Network::Regtest => "bcrt",
+        }
+    }
+}
+
+impl Default for Network {
+    fn default() -> Network {
+        Network::Mainnet
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Signet => "signet",
+            Network::Regtest => "regtest",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Network {
+    type Err = UnknownNetworkError;
+
+    fn from_str(name: &str) -> Result<Network, UnknownNetworkError> {
+        match name.to_lowercase().as_str() {
+            "mainnet" | "main" => Ok(Network::Mainnet),
+            "testnet" | "test" => Ok(Network::Testnet),
+            "signet" => Ok(Network::Signet),
+            "regtest" => Ok(Network::Regtest),
+            _ => Err(UnknownNetworkError(String::from(name))),
+        }
+    }
+}
+
+/// Returned when parsing a `Network` from an unknown network name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownNetworkError(pub String);
+
+impl fmt::Display for UnknownNetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown network {} (expected mainnet, testnet, signet or regtest)",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownNetworkError {}
@@ -1,9 +1,14 @@
-use super::Address;
+use super::{Address, AddressDerivation, Multisig, ScriptType};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Output {
     pub index: u32,
     pub value: u64,
     pub address: Option<Address>,
+    /// How `address` has been obtained, `None` if there is no address.
+    pub address_derivation: Option<AddressDerivation>,
     pub script: Box<[u8]>,
+    pub script_type: ScriptType,
+    /// The threshold and participants if this is a bare multisig output.
+    pub multisig: Option<Multisig>,
 }
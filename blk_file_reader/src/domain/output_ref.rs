@@ -0,0 +1,27 @@
+use super::{Output, ScriptType};
+use read;
+use read_options::ReadOptions;
+
+/// An `Output` whose script borrows from the underlying blk file data.
+///
+/// In contrast to `Output`, the script type and address are not determined
+/// while reading, so that scanning blocks does not pay for them unless needed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputRef<'a> {
+    pub index: u32,
+    pub value: u64,
+    pub script: &'a [u8],
+}
+
+impl<'a> OutputRef<'a> {
+    /// Classifies the script of this output.
+    pub fn script_type(&self) -> ScriptType {
+        ScriptType::from_script(self.script)
+    }
+
+    /// Copies this output into an owned `Output`, determining its address as
+    /// specified by the given `options`.
+    pub fn to_output(&self, options: ReadOptions) -> Output {
+        read::output_from_script(self.index, self.value, Box::from(self.script), options)
+    }
+}
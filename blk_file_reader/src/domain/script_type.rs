@@ -0,0 +1,298 @@
+use std::fmt;
+
+const OP_0: u8 = 0x00;
+const OP_PUSHDATA1: u8 = 0x4C;
+const OP_PUSHDATA2: u8 = 0x4D;
+const OP_PUSHDATA4: u8 = 0x4E;
+const OP_1NEGATE: u8 = 0x4F;
+const OP_1: u8 = 0x51;
+const OP_16: u8 = 0x60;
+const OP_RETURN: u8 = 0x6A;
+const OP_DUP: u8 = 0x76;
+const OP_EQUAL: u8 = 0x87;
+const OP_EQUALVERIFY: u8 = 0x88;
+const OP_HASH160: u8 = 0xA9;
+const OP_CHECKSIG: u8 = 0xAC;
+const OP_CHECKMULTISIG: u8 = 0xAE;
+
+/// The type of an output script.
+///
+/// The classification follows the standard script templates of Bitcoin Core.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScriptType {
+    /// Pay to public key.
+    P2PK,
+    /// Pay to public key hash.
+    P2PKH,
+    /// Pay to script hash, see [BIP 16](https://github.com/bitcoin/bips/blob/master/bip-0016.mediawiki).
+    P2SH,
+    /// Pay to witness public key hash, see [BIP 141](https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki).
+    P2WPKH,
+    /// Pay to witness script hash, see [BIP 141](https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki).
+    P2WSH,
+    /// Pay to taproot, see [BIP 341](https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki).
+    P2TR,
+    /// Bare m-of-n multisig.
+    Multisig,
+    /// Provably unspendable `OP_RETURN` output, commonly used to embed data.
+    OpReturn,
+    /// A witness program of a version or length that has no meaning yet.
+    WitnessUnknown,
+    /// Any script that does not match one of the above templates.
+    NonStandard,
+}
+
+impl ScriptType {
+    /// Classifies the given output script.
+    pub fn from_script(script: &[u8]) -> ScriptType {
+        if is_p2sh(script) {
+            return ScriptType::P2SH;
+        }
+
+        if let Some((witness_version, witness_program)) = read_witness_program(script) {
+            return match (witness_version, witness_program.len()) {
+                (0, 20) => ScriptType::P2WPKH,
+                (0, 32) => ScriptType::P2WSH,
+                (1, 32) => ScriptType::P2TR,
+                _ => ScriptType::WitnessUnknown,
+            };
+        }
+
+        if !script.is_empty() && script[0] == OP_RETURN && is_push_only(&script[1..]) {
+            ScriptType::OpReturn
+        } else if is_p2pk(script) {
+            ScriptType::P2PK
+        } else if is_p2pkh(script) {
+            ScriptType::P2PKH
+        } else if is_multisig(script) {
+            ScriptType::Multisig
+        } else {
+            ScriptType::NonStandard
+        }
+    }
+
+    /// Returns the name of this script type as used by Bitcoin Core's RPC
+    /// interface, e.g. `pubkeyhash` or `witness_v0_keyhash`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ScriptType::P2PK => "pubkey",
+            ScriptType::P2PKH => "pubkeyhash",
+            ScriptType::P2SH => "scripthash",
+            ScriptType::P2WPKH => "witness_v0_keyhash",
+            ScriptType::P2WSH => "witness_v0_scripthash",
+            ScriptType::P2TR => "witness_v1_taproot",
+            ScriptType::Multisig => "multisig",
+            ScriptType::OpReturn => "nulldata",
+            ScriptType::WitnessUnknown => "witness_unknown",
+            ScriptType::NonStandard => "nonstandard",
+        }
+    }
+}
+
+impl fmt::Display for ScriptType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+fn is_p2sh(script: &[u8]) -> bool {
+    script.len() == 23 && script[0] == OP_HASH160 && script[1] == 20 && script[22] == OP_EQUAL
+}
+
+fn is_p2pkh(script: &[u8]) -> bool {
+    script.len() == 25
+        && script[0] == OP_DUP
+        && script[1] == OP_HASH160
+        && script[2] == 20
+        && script[23] == OP_EQUALVERIFY
+        && script[24] == OP_CHECKSIG
+}
+
+fn is_p2pk(script: &[u8]) -> bool {
+    match script.len() {
+        35 | 67 => {
+            let public_key_length = script[0] as usize;
+            public_key_length == script.len() - 2
+                && is_valid_public_key_size(&script[1..script.len() - 1])
+                && script[script.len() - 1] == OP_CHECKSIG
+        }
+        _ => false,
+    }
+}
+
+fn is_multisig(script: &[u8]) -> bool {
+    read_multisig(script).is_some()
+}
+
+/// Reads the number of required signatures and the public keys of the given
+/// bare multisig script.
+///
+/// Returns `None` if the script is not an m-of-n `OP_CHECKMULTISIG` script.
+pub(crate) fn read_multisig(script: &[u8]) -> Option<(u8, Vec<&[u8]>)> {
+    if script.len() < 3 || script[script.len() - 1] != OP_CHECKMULTISIG {
+        return None;
+    }
+
+    let required_signatures = small_integer(script[0])?;
+    let number_of_public_keys = small_integer(script[script.len() - 2])?;
+
+    let mut remainder = &script[1..script.len() - 2];
+    let mut public_keys = Vec::with_capacity(number_of_public_keys);
+    while !remainder.is_empty() {
+        let public_key_length = remainder[0] as usize;
+        if remainder.len() < 1 + public_key_length
+            || !is_valid_public_key_size(&remainder[1..1 + public_key_length])
+        {
+            return None;
+        }
+        public_keys.push(&remainder[1..1 + public_key_length]);
+        remainder = &remainder[1 + public_key_length..];
+    }
+
+    if public_keys.len() == number_of_public_keys && required_signatures <= number_of_public_keys {
+        Some((required_signatures as u8, public_keys))
+    } else {
+        None
+    }
+}
+
+/// Reads the witness version and witness program of the given output script.
+///
+/// Returns `None` if the script is not a witness program as defined by
+/// [BIP 141](https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki#witness-program),
+/// or if it is a version 0 witness program of invalid length.
+pub(crate) fn read_witness_program(script: &[u8]) -> Option<(u8, &[u8])> {
+    if script.len() < 4 || script.len() > 42 {
+        return None;
+    }
+    let witness_version = match script[0] {
+        OP_0 => 0,
+        opcode @ OP_1..=OP_16 => opcode - OP_1 + 1,
+        _ => return None,
+    };
+    if script[1] as usize != script.len() - 2 {
+        return None;
+    }
+    let witness_program = &script[2..];
+    if witness_version == 0 && witness_program.len() != 20 && witness_program.len() != 32 {
+        return None;
+    }
+    Some((witness_version, witness_program))
+}
+
+/// Checks whether the given public key has a valid size for its header byte.
+pub(crate) fn is_valid_public_key_size(public_key: &[u8]) -> bool {
+    match public_key.first() {
+        Some(&0x02) | Some(&0x03) => public_key.len() == 33,
+        Some(&0x04) | Some(&0x06) | Some(&0x07) => public_key.len() == 65,
+        _ => false,
+    }
+}
+
+/// Decodes `OP_1` to `OP_16` into the according integer.
+fn small_integer(opcode: u8) -> Option<usize> {
+    match opcode {
+        OP_1..=OP_16 => Some((opcode - OP_1 + 1) as usize),
+        _ => None,
+    }
+}
+
+/// Checks whether the given script consists of push operations only.
+fn is_push_only(script: &[u8]) -> bool {
+    let mut position = 0;
+    while position < script.len() {
+        let opcode = script[position];
+        position += 1;
+        let push_length = match opcode {
+            0x01..=0x4B => opcode as usize,
+            OP_PUSHDATA1 | OP_PUSHDATA2 | OP_PUSHDATA4 => {
+                let length_size = match opcode {
+                    OP_PUSHDATA1 => 1,
+                    OP_PUSHDATA2 => 2,
+                    _ => 4,
+                };
+                if position + length_size > script.len() {
+                    return false;
+                }
+                let mut push_length = 0usize;
+                for i in 0..length_size {
+                    push_length |= (script[position + i] as usize) << (8 * i);
+                }
+                position += length_size;
+                push_length
+            }
+            OP_0 | OP_1NEGATE..=OP_16 => 0,
+            _ => return false,
+        };
+        position += push_length;
+    }
+    position == script.len()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use data_encoding::HEXLOWER;
+
+    fn classify(hex: &[u8]) -> ScriptType {
+        ScriptType::from_script(&HEXLOWER.decode(hex).unwrap())
+    }
+
+    #[test]
+    fn classifies_p2pk() {
+        let script_type = classify(b"4104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac");
+        assert_eq!(script_type, ScriptType::P2PK);
+    }
+
+    #[test]
+    fn classifies_p2pkh() {
+        let script_type = classify(b"76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac");
+        assert_eq!(script_type, ScriptType::P2PKH);
+    }
+
+    #[test]
+    fn classifies_p2sh() {
+        let script_type = classify(b"a914748284390f9e263a4b766a75d0633c50426eb87587");
+        assert_eq!(script_type, ScriptType::P2SH);
+    }
+
+    #[test]
+    fn classifies_witness_programs() {
+        assert_eq!(
+            classify(b"0014751e76e8199196d454941c45d1b3a323f1433bd6"),
+            ScriptType::P2WPKH
+        );
+        assert_eq!(
+            classify(b"00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262"),
+            ScriptType::P2WSH
+        );
+        assert_eq!(
+            classify(b"512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+            ScriptType::P2TR
+        );
+        assert_eq!(classify(b"52020001"), ScriptType::WitnessUnknown);
+    }
+
+    #[test]
+    fn classifies_bare_multisig() {
+        let script_type = classify(b"5121022afc20bf379bc96a2f4e9e63ffceb8652b2b6a097f63fbee6ecec2a49a48010e2103a767c7221e9f15f870f1ad9311f5ab937d79fcaeee15bb2c722bca515581b4c052ae");
+        assert_eq!(script_type, ScriptType::Multisig);
+    }
+
+    #[test]
+    fn classifies_op_return() {
+        assert_eq!(classify(b"6a0568656c6c6f"), ScriptType::OpReturn);
+        assert_eq!(classify(b"6a"), ScriptType::OpReturn);
+    }
+
+    #[test]
+    fn classifies_op_return_with_non_push_operations_as_non_standard() {
+        assert_eq!(classify(b"6a76"), ScriptType::NonStandard);
+    }
+
+    #[test]
+    fn classifies_empty_script_as_non_standard() {
+        assert_eq!(classify(b""), ScriptType::NonStandard);
+    }
+}
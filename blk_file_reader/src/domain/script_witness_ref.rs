@@ -0,0 +1,87 @@
+use super::ScriptWitness;
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::Cursor;
+
+/// A `ScriptWitness` that borrows its items from the underlying blk file data.
+///
+/// The items are kept in their serialized form and are only split up when
+/// iterating over them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptWitnessRef<'a> {
+    item_count: usize,
+    serialized_items: &'a [u8],
+}
+
+impl<'a> ScriptWitnessRef<'a> {
+    /// Creates a script witness from the given serialized items, i.e. the
+    /// length-prefixed items without the leading item count.
+    ///
+    /// The caller is responsible for `serialized_items` containing exactly
+    /// `item_count` well-formed items.
+    pub(crate) fn new(item_count: usize, serialized_items: &'a [u8]) -> ScriptWitnessRef<'a> {
+        ScriptWitnessRef {
+            item_count,
+            serialized_items,
+        }
+    }
+
+    /// Returns the number of items of this witness.
+    pub fn len(&self) -> usize {
+        self.item_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.item_count == 0
+    }
+
+    /// Returns an iterator over the items of this witness.
+    pub fn items(&self) -> ScriptWitnessItemRefs<'a> {
+        ScriptWitnessItemRefs {
+            remaining_items: self.item_count,
+            cursor: Cursor::new(self.serialized_items),
+        }
+    }
+
+    /// Copies this witness into an owned `ScriptWitness`.
+    pub fn to_script_witness(&self) -> ScriptWitness {
+        ScriptWitness {
+            items: self.items().map(|item| item.to_vec()).collect(),
+        }
+    }
+}
+
+/// Iterates over the items of a `ScriptWitnessRef`.
+pub struct ScriptWitnessItemRefs<'a> {
+    remaining_items: usize,
+    cursor: Cursor<&'a [u8]>,
+}
+
+impl<'a> Iterator for ScriptWitnessItemRefs<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.remaining_items == 0 {
+            return None;
+        }
+        self.remaining_items -= 1;
+
+        let item_length = match self.cursor.read_u8().ok()? {
+            0xFD => self.cursor.read_u16::<LittleEndian>().ok()? as usize,
+            0xFE => self.cursor.read_u32::<LittleEndian>().ok()? as usize,
+            0xFF => self.cursor.read_u64::<LittleEndian>().ok()? as usize,
+            length => length as usize,
+        };
+        let bytes: &'a [u8] = *self.cursor.get_ref();
+        let start = self.cursor.position() as usize;
+        let end = start.checked_add(item_length)?;
+        if end > bytes.len() {
+            return None;
+        }
+        self.cursor.set_position(end as u64);
+        Some(&bytes[start..end])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining_items))
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+/// The way in which an input spends its output, as far as it can be told from
+/// the input script and witness alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpendType {
+    /// The input of a coinbase transaction, which does not spend any output.
+    Coinbase,
+    /// Spends a P2PK output with a single signature.
+    P2PK,
+    /// Spends a P2PKH output with a signature and a public key.
+    P2PKH,
+    /// Spends a bare multisig output with a number of signatures.
+    Multisig,
+    /// Spends a P2SH output with a redeem script that is not a witness
+    /// program.
+    P2SH,
+    /// Spends a P2SH output whose redeem script is a P2WPKH witness program.
+    P2SHP2WPKH,
+    /// Spends a P2SH output whose redeem script is a P2WSH witness program.
+    P2SHP2WSH,
+    /// Spends a P2WPKH output with a signature and a public key.
+    P2WPKH,
+    /// Spends a P2WSH output by revealing its witness script.
+    P2WSH,
+    /// Spends a P2TR output with a signature for its output key.
+    P2TRKeyPath,
+    /// Spends a P2TR output by revealing one of its leaf scripts.
+    P2TRScriptPath,
+    /// Any input that does not match one of the above patterns.
+    NonStandard,
+}
+
+impl SpendType {
+    /// Returns the name of this spend type, e.g. `p2sh-p2wpkh` or
+    /// `p2tr-script-path`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            SpendType::Coinbase => "coinbase",
+            SpendType::P2PK => "p2pk",
+            SpendType::P2PKH => "p2pkh",
+            SpendType::Multisig => "multisig",
+            SpendType::P2SH => "p2sh",
+            SpendType::P2SHP2WPKH => "p2sh-p2wpkh",
+            SpendType::P2SHP2WSH => "p2sh-p2wsh",
+            SpendType::P2WPKH => "p2wpkh",
+            SpendType::P2WSH => "p2wsh",
+            SpendType::P2TRKeyPath => "p2tr-key-path",
+            SpendType::P2TRScriptPath => "p2tr-script-path",
+            SpendType::NonStandard => "nonstandard",
+        }
+    }
+}
+
+impl fmt::Display for SpendType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
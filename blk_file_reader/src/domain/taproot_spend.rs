@@ -0,0 +1,214 @@
+use super::{ScriptWitness, ScriptWitnessRef};
+
+/// The first byte of an annex, which distinguishes it from the other witness
+/// items.
+const ANNEX_TAG: u8 = 0x50;
+
+/// The leaf version of scripts that are executed as tapscript, see
+/// [BIP 342](https://github.com/bitcoin/bips/blob/master/bip-0342.mediawiki).
+pub const TAPSCRIPT_LEAF_VERSION: u8 = 0xC0;
+
+const CONTROL_BLOCK_BASE_SIZE: usize = 33;
+const CONTROL_BLOCK_NODE_SIZE: usize = 32;
+const CONTROL_BLOCK_MAX_NODE_COUNT: usize = 128;
+
+/// The decoded witness of an input that spends a P2TR output, see
+/// [BIP 341](https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki#script-validation-rules).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaprootSpend<'a> {
+    /// The output key is spent directly with a Schnorr signature.
+    KeyPath {
+        /// The 64-byte signature, followed by the signature hash type if it
+        /// is not `SIGHASH_DEFAULT`.
+        signature: &'a [u8],
+        annex: Option<&'a [u8]>,
+    },
+    /// One of the scripts committed to by the output key is revealed and
+    /// executed.
+    ScriptPath {
+        /// The witness items the leaf script is executed with.
+        arguments: Box<[&'a [u8]]>,
+        leaf_script: &'a [u8],
+        control_block: ControlBlock<'a>,
+        annex: Option<&'a [u8]>,
+    },
+}
+
+/// Proves that a leaf script is committed to by the output key of a P2TR
+/// output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlBlock<'a> {
+    /// The version of the leaf script, e.g. `TAPSCRIPT_LEAF_VERSION`.
+    pub leaf_version: u8,
+    /// The parity of the y-coordinate of the output key.
+    pub output_key_parity: u8,
+    /// The 32-byte x-only internal key.
+    pub internal_key: &'a [u8],
+    /// The 32-byte hashes of the script tree from the leaf up to the root.
+    pub merkle_path: Box<[&'a [u8]]>,
+}
+
+impl<'a> TaprootSpend<'a> {
+    /// Decodes the given witness items of an input that spends a P2TR output.
+    ///
+    /// Returns `None` if the items do not form a valid key path or script path
+    /// spend. Since the witness alone does not reveal the type of the spent
+    /// output, witnesses of other outputs may still decode successfully.
+    pub fn from_witness_items(items: &[&'a [u8]]) -> Option<TaprootSpend<'a>> {
+        let (items, annex) = match items.split_last() {
+            Some((last, rest)) if !rest.is_empty() && last.first() == Some(&ANNEX_TAG) => {
+                (rest, Some(*last))
+            }
+            _ => (items, None),
+        };
+
+        match items.len() {
+            0 => None,
+            1 => {
+                let signature = items[0];
+                if signature.len() == 64 || signature.len() == 65 {
+                    Some(TaprootSpend::KeyPath { signature, annex })
+                } else {
+                    None
+                }
+            }
+            length => {
+                let control_block = ControlBlock::from_bytes(items[length - 1])?;
+                Some(TaprootSpend::ScriptPath {
+                    arguments: Box::from(&items[..length - 2]),
+                    leaf_script: items[length - 2],
+                    control_block,
+                    annex,
+                })
+            }
+        }
+    }
+
+    /// The annex of the witness, which is reserved for future extensions.
+    pub fn annex(&self) -> Option<&'a [u8]> {
+        match *self {
+            TaprootSpend::KeyPath { annex, .. } | TaprootSpend::ScriptPath { annex, .. } => annex,
+        }
+    }
+}
+
+impl<'a> ControlBlock<'a> {
+    /// Decodes the given control block.
+    ///
+    /// Returns `None` if it is not 33 bytes long plus 32 bytes for each of at
+    /// most 128 nodes of the merkle path.
+    pub fn from_bytes(bytes: &'a [u8]) -> Option<ControlBlock<'a>> {
+        if bytes.len() < CONTROL_BLOCK_BASE_SIZE
+            || (bytes.len() - CONTROL_BLOCK_BASE_SIZE) % CONTROL_BLOCK_NODE_SIZE != 0
+            || (bytes.len() - CONTROL_BLOCK_BASE_SIZE) / CONTROL_BLOCK_NODE_SIZE
+                > CONTROL_BLOCK_MAX_NODE_COUNT
+        {
+            return None;
+        }
+
+        Some(ControlBlock {
+            leaf_version: bytes[0] & 0xFE,
+            output_key_parity: bytes[0] & 0x01,
+            internal_key: &bytes[1..CONTROL_BLOCK_BASE_SIZE],
+            merkle_path: bytes[CONTROL_BLOCK_BASE_SIZE..]
+                .chunks(CONTROL_BLOCK_NODE_SIZE)
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+        })
+    }
+}
+
+impl ScriptWitness {
+    /// Decodes this witness as the witness of a P2TR spend, see
+    /// `TaprootSpend::from_witness_items`.
+    pub fn taproot_spend(&self) -> Option<TaprootSpend> {
+        let items: Vec<&[u8]> = self.items.iter().map(|item| &item[..]).collect();
+        TaprootSpend::from_witness_items(&items)
+    }
+}
+
+impl<'a> ScriptWitnessRef<'a> {
+    /// Decodes this witness as the witness of a P2TR spend, see
+    /// `TaprootSpend::from_witness_items`.
+    pub fn taproot_spend(&self) -> Option<TaprootSpend<'a>> {
+        let items: Vec<&'a [u8]> = self.items().collect();
+        TaprootSpend::from_witness_items(&items)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn decodes_key_path_spend_with_annex() {
+        // given
+        let witness = ScriptWitness {
+            items: vec![vec![0x01; 64], vec![ANNEX_TAG, 0x02]],
+        };
+
+        // when
+        let taproot_spend = witness.taproot_spend().unwrap();
+
+        // then
+        assert_eq!(
+            taproot_spend,
+            TaprootSpend::KeyPath {
+                signature: &[0x01; 64][..],
+                annex: Some(&[ANNEX_TAG, 0x02][..]),
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_script_path_spend() {
+        // given
+        let mut control_block = vec![TAPSCRIPT_LEAF_VERSION | 0x01];
+        control_block.extend_from_slice(&[0x02; 32]);
+        control_block.extend_from_slice(&[0x03; 32]);
+        control_block.extend_from_slice(&[0x04; 32]);
+        let witness = ScriptWitness {
+            items: vec![vec![0x05; 64], vec![0x20, 0xAC], control_block],
+        };
+
+        // when
+        let taproot_spend = witness.taproot_spend().unwrap();
+
+        // then
+        match taproot_spend {
+            TaprootSpend::ScriptPath {
+                arguments,
+                leaf_script,
+                control_block,
+                annex,
+            } => {
+                assert_eq!(&arguments[..], &[&[0x05; 64][..]]);
+                assert_eq!(leaf_script, &[0x20, 0xAC]);
+                assert_eq!(control_block.leaf_version, TAPSCRIPT_LEAF_VERSION);
+                assert_eq!(control_block.output_key_parity, 1);
+                assert_eq!(control_block.internal_key, &[0x02; 32][..]);
+                assert_eq!(
+                    &control_block.merkle_path[..],
+                    &[&[0x03; 32][..], &[0x04; 32][..]]
+                );
+                assert_eq!(annex, None);
+            }
+            other => panic!("expected script path spend, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_control_blocks_of_invalid_length() {
+        // given
+        let witness = ScriptWitness {
+            items: vec![vec![0xAC], vec![TAPSCRIPT_LEAF_VERSION; 34]],
+        };
+
+        // when
+        let taproot_spend = witness.taproot_spend();
+
+        // then
+        assert_eq!(taproot_spend, None);
+    }
+}
@@ -13,5 +13,29 @@ pub struct Transaction {
     pub outputs: Box<[Output]>,
     pub script_witnesses: Box<[ScriptWitness]>,
     pub size_in_bytes: u32,
+    /// The size of the transaction serialized without its witnesses.
+    pub stripped_size_in_bytes: u32,
     pub weight: u32,
 }
+
+impl Transaction {
+    /// Whether this is the coinbase transaction of its block, i.e. whether its
+    /// only input spends the null outpoint.
+    pub fn is_coinbase(&self) -> bool {
+        self.inputs.len() == 1
+            && self.inputs[0].previous_tx_hash.0 == [0u8; 32]
+            && self.inputs[0].previous_tx_output_index == u32::max_value()
+    }
+
+    /// Whether this transaction has been serialized with witnesses, see
+    /// [BIP 144](https://github.com/bitcoin/bips/blob/master/bip-0144.mediawiki).
+    pub fn is_segwit(&self) -> bool {
+        !self.script_witnesses.is_empty()
+    }
+
+    /// The virtual size of this transaction, i.e. its weight divided by 4 and
+    /// rounded up, which fee rates are based on.
+    pub fn virtual_size(&self) -> u32 {
+        (self.weight + 3) / 4
+    }
+}
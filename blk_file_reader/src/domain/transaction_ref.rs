@@ -0,0 +1,52 @@
+use super::{Hash, InputRef, OutputRef, ScriptWitnessRef, Transaction};
+use read_options::ReadOptions;
+
+/// A `Transaction` whose scripts and witnesses borrow from the underlying blk
+/// file data instead of being copied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionRef<'a> {
+    pub tx_hash: Hash,
+    pub witness_hash: Hash,
+    pub version: u32,
+    pub lock_time: u32,
+    pub inputs: Box<[InputRef<'a>]>,
+    pub outputs: Box<[OutputRef<'a>]>,
+    pub script_witnesses: Box<[ScriptWitnessRef<'a>]>,
+    pub size_in_bytes: u32,
+    /// The size of the transaction serialized without its witnesses.
+    pub stripped_size_in_bytes: u32,
+    pub weight: u32,
+}
+
+impl<'a> TransactionRef<'a> {
+    /// Copies this transaction into an owned `Transaction`.
+    pub fn to_transaction(&self, options: ReadOptions) -> Transaction {
+        Transaction {
+            tx_hash: self.tx_hash.clone(),
+            witness_hash: self.witness_hash.clone(),
+            version: self.version,
+            lock_time: self.lock_time,
+            inputs: self
+                .inputs
+                .iter()
+                .map(InputRef::to_input)
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+            outputs: self
+                .outputs
+                .iter()
+                .map(|output| output.to_output(options))
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+            script_witnesses: self
+                .script_witnesses
+                .iter()
+                .map(ScriptWitnessRef::to_script_witness)
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+            size_in_bytes: self.size_in_bytes,
+            stripped_size_in_bytes: self.stripped_size_in_bytes,
+            weight: self.weight,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use super::{Address, AddressDerivation, Hash, ScriptType};
+
+/// Identifies an output by the hash of its transaction and its index therein.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UtxoId {
+    pub tx_hash: Hash,
+    pub output_index: u32,
+}
+
+/// An unspent output, as stored by Bitcoin Core in its `chainstate` database.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Utxo {
+    pub id: UtxoId,
+    /// The height of the block that contains the transaction which created the
+    /// output.
+    pub height: u32,
+    /// Whether the output has been created by a coinbase transaction.
+    pub is_coinbase: bool,
+    pub value: u64,
+    pub address: Option<Address>,
+    /// How `address` has been obtained, `None` if there is no address.
+    pub address_derivation: Option<AddressDerivation>,
+    pub script: Box<[u8]>,
+    pub script_type: ScriptType,
+}
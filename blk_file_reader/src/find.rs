@@ -0,0 +1,113 @@
+use domain::{Block, BlockLocation, BlockRef, Hash, TransactionLocation};
+use mapped_blk_file::MappedBlkFile;
+use parse_error::is_skipped_range;
+use rayon::prelude::*;
+use read_options::ReadOptions;
+use std::io;
+use std::sync::Mutex;
+use util::read_indexed_blk_files;
+
+/// Scans the blk files at the given path in parallel for the block with the
+/// given hash.
+///
+/// Returns `None` if none of the blk files contains the block. If the block
+/// has not been found and a blk file could not be read completely, the first
+/// error is returned instead, since the block might be stored in the unread
+/// part. Use a `HashIndex` for repeated lookups.
+pub fn find_block(
+    path_to_blk_files: &str,
+    hash: &Hash,
+    options: ReadOptions,
+) -> io::Result<Option<(BlockLocation, Block)>> {
+    scan_blk_files(path_to_blk_files, options, |location, block| {
+        if block.hash == *hash {
+            Some((location, block.to_block(options)))
+        } else {
+            None
+        }
+    })
+}
+
+/// Scans the blk files at the given path in parallel for the transaction with
+/// the given hash, see `find_block`.
+///
+/// Returns the location of the transaction together with the block that
+/// contains it. Since the hashes of some early coinbase transactions are not
+/// unique, any of the blocks containing such a transaction is returned.
+pub fn find_transaction(
+    path_to_blk_files: &str,
+    tx_hash: &Hash,
+    options: ReadOptions,
+) -> io::Result<Option<(TransactionLocation, Block)>> {
+    scan_blk_files(path_to_blk_files, options, |location, block| {
+        block
+            .transactions
+            .iter()
+            .position(|transaction| transaction.tx_hash == *tx_hash)
+            .map(|index_in_block| {
+                let transaction_location = TransactionLocation {
+                    block: location,
+                    index_in_block,
+                };
+                (transaction_location, block.to_block(options))
+            })
+    })
+}
+
+/// Maps the blk files at the given path and returns the first match that is
+/// found among their blocks.
+fn scan_blk_files<T, F>(
+    path_to_blk_files: &str,
+    options: ReadOptions,
+    find: F,
+) -> io::Result<Option<T>>
+where
+    T: Send,
+    F: Fn(BlockLocation, &BlockRef) -> Option<T> + Sync,
+{
+    let first_error = Mutex::new(None);
+    let found = read_indexed_blk_files(path_to_blk_files)?
+        .into_par_iter()
+        .find_map_any(|(blk_file_index, blk_file_path)| {
+            match scan_blk_file(blk_file_index, &blk_file_path, options, &find) {
+                Ok(found) => found,
+                Err(error) => {
+                    first_error.lock().unwrap().get_or_insert(error);
+                    None
+                }
+            }
+        });
+    match (found, first_error.into_inner().unwrap()) {
+        (Some(found), _) => Ok(Some(found)),
+        (None, Some(error)) => Err(error),
+        (None, None) => Ok(None),
+    }
+}
+
+fn scan_blk_file<T, F>(
+    blk_file_index: usize,
+    blk_file_path: &str,
+    options: ReadOptions,
+    find: &F,
+) -> io::Result<Option<T>>
+where
+    F: Fn(BlockLocation, &BlockRef) -> Option<T>,
+{
+    let blk_file = MappedBlkFile::open(blk_file_path, options)?;
+    for block in blk_file.blocks() {
+        let block = match block {
+            Ok(block) => block,
+            Err(ref error) if is_skipped_range(error) => continue,
+            Err(error) => return Err(error),
+        };
+        let location = BlockLocation {
+            blk_file_index,
+            index_in_blk_file: block.index_in_blk_file,
+            offset_in_blk_file: block.offset_in_blk_file,
+        };
+        if let Some(found) = find(location, &block) {
+            return Ok(Some(found));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,342 @@
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
+use domain::{BlockLocation, Hash, TransactionLocation};
+use mapped_blk_file::MappedBlkFile;
+use memmap::Mmap;
+use parse_error::is_skipped_range;
+use rayon::prelude::*;
+use read_options::ReadOptions;
+use std::cmp::Ordering;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::ops::Range;
+use util::{read_blocks_with_options, read_indexed_blk_files};
+
+const MAGIC: &'static [u8; 4] = b"BRHX";
+const VERSION: u32 = 2;
+
+/// Magic, version and number of blk files.
+const HEADER_LENGTH: usize = 12;
+/// Index of a blk file and end offset of its last indexed block.
+const BLK_FILE_ENTRY_LENGTH: usize = 12;
+/// Number of block and transaction entries.
+const COUNTS_LENGTH: usize = 16;
+/// Hash, blk file index, index in blk file and offset in blk file.
+const BLOCK_ENTRY_LENGTH: usize = 48;
+/// A block entry followed by the index in the block.
+const TRANSACTION_ENTRY_LENGTH: usize = 52;
+
+/// A sidecar index of the blocks and transactions within a directory of blk
+/// files, which allows for looking them up by hash without scanning the blk
+/// files.
+///
+/// The index is stored as a file of block and transaction entries that are
+/// sorted by hash. Opened index files are memory-mapped, so a lookup only
+/// touches the few pages of its binary search.
+///
+/// Bitcoin Core preallocates blk files in chunks of zeros, so their length
+/// does not tell whether blocks have been added. The index records the end
+/// offset of the last indexed block of every blk file instead, see
+/// `is_up_to_date`.
+pub struct HashIndex {
+    data: IndexData,
+    /// The index of every indexed blk file and the offset right after its last
+    /// indexed block.
+    blk_files: Vec<(usize, u64)>,
+    /// The byte range of the block entries within the data.
+    block_entries: Range<usize>,
+    /// The byte range of the transaction entries within the data.
+    transaction_entries: Range<usize>,
+}
+
+enum IndexData {
+    Mapped(Mmap),
+    Built(Box<[u8]>),
+}
+
+/// The entries of a single blk file.
+struct BlkFileEntries {
+    blk_file_index: usize,
+    end_offset: u64,
+    blocks: Vec<([u8; 32], BlockLocation)>,
+    transactions: Vec<([u8; 32], TransactionLocation)>,
+}
+
+impl HashIndex {
+    /// Indexes the blocks and transactions of all blk files at the given path,
+    /// which are read in parallel.
+    ///
+    /// Fails if a blk file cannot be read completely, except for byte ranges
+    /// that have been skipped because `ReadOptions::resync` is set. The index
+    /// is held in memory and can be stored with `write`.
+    pub fn build(path_to_blk_files: &str, options: ReadOptions) -> io::Result<HashIndex> {
+        let entries = read_indexed_blk_files(path_to_blk_files)?
+            .into_par_iter()
+            .map(|(blk_file_index, blk_file_path)| {
+                read_blk_file_entries(blk_file_index, &blk_file_path, options)
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut blk_files = Vec::with_capacity(entries.len());
+        let mut blocks = Vec::new();
+        let mut transactions = Vec::new();
+        for blk_file_entries in entries {
+            blk_files.push((blk_file_entries.blk_file_index, blk_file_entries.end_offset));
+            blocks.extend(blk_file_entries.blocks);
+            transactions.extend(blk_file_entries.transactions);
+        }
+        blocks.par_sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        transactions.par_sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        let mut bytes = Vec::with_capacity(
+            HEADER_LENGTH
+                + blk_files.len() * BLK_FILE_ENTRY_LENGTH
+                + COUNTS_LENGTH
+                + blocks.len() * BLOCK_ENTRY_LENGTH
+                + transactions.len() * TRANSACTION_ENTRY_LENGTH,
+        );
+        bytes.extend_from_slice(MAGIC);
+        bytes.write_u32::<LittleEndian>(VERSION)?;
+        bytes.write_u32::<LittleEndian>(blk_files.len() as u32)?;
+        for &(blk_file_index, end_offset) in blk_files.iter() {
+            bytes.write_u32::<LittleEndian>(blk_file_index as u32)?;
+            bytes.write_u64::<LittleEndian>(end_offset)?;
+        }
+        bytes.write_u64::<LittleEndian>(blocks.len() as u64)?;
+        bytes.write_u64::<LittleEndian>(transactions.len() as u64)?;
+        for (hash, location) in blocks {
+            bytes.extend_from_slice(&hash);
+            write_block_location(&mut bytes, &location)?;
+        }
+        for (hash, location) in transactions {
+            bytes.extend_from_slice(&hash);
+            write_block_location(&mut bytes, &location.block)?;
+            bytes.write_u32::<LittleEndian>(location.index_in_block as u32)?;
+        }
+
+        HashIndex::from_data(IndexData::Built(bytes.into_boxed_slice()))
+    }
+
+    /// Maps the index file at the given path into memory.
+    pub fn open(path_to_index: &str) -> io::Result<HashIndex> {
+        let file = File::open(path_to_index)?;
+        HashIndex::from_data(IndexData::Mapped(unsafe { Mmap::map(&file)? }))
+    }
+
+    /// Stores this index in a file at the given path, replacing any existing
+    /// file.
+    pub fn write(&self, path_to_index: &str) -> io::Result<()> {
+        let temporary_path = format!("{}.tmp", path_to_index);
+        File::create(&temporary_path)?.write_all(self.bytes())?;
+        fs::rename(&temporary_path, path_to_index)
+    }
+
+    /// Whether the blk files at the given path are the ones that have been
+    /// indexed, with no blocks having been written to them since.
+    ///
+    /// Every blk file is read from the end of its last indexed block, where
+    /// only zero padding or byte ranges skipped because `ReadOptions::resync`
+    /// is set may follow.
+    pub fn is_up_to_date(&self, path_to_blk_files: &str, options: ReadOptions) -> io::Result<bool> {
+        let blk_files = read_indexed_blk_files(path_to_blk_files)?;
+        if blk_files.len() != self.blk_files.len() {
+            return Ok(false);
+        }
+        for (&(blk_file_index, ref blk_file_path), &(indexed_blk_file_index, end_offset)) in
+            blk_files.iter().zip(self.blk_files.iter())
+        {
+            if blk_file_index != indexed_blk_file_index
+                || fs::metadata(blk_file_path)?.len() < end_offset
+                || has_block_after(blk_file_path, end_offset, options)?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn number_of_blocks(&self) -> usize {
+        self.block_entries.len() / BLOCK_ENTRY_LENGTH
+    }
+
+    pub fn number_of_transactions(&self) -> usize {
+        self.transaction_entries.len() / TRANSACTION_ENTRY_LENGTH
+    }
+
+    /// Looks up the location of the block with the given hash.
+    pub fn block_location(&self, hash: &Hash) -> Option<BlockLocation> {
+        let entries = &self.bytes()[self.block_entries.clone()];
+        let entry = find_entry(entries, BLOCK_ENTRY_LENGTH, hash)?;
+        Some(read_block_location(&entry[32..]))
+    }
+
+    /// Looks up the location of the transaction with the given hash.
+    ///
+    /// Since the hashes of some early coinbase transactions are not unique,
+    /// any of the locations of such a transaction is returned.
+    pub fn transaction_location(&self, tx_hash: &Hash) -> Option<TransactionLocation> {
+        let entries = &self.bytes()[self.transaction_entries.clone()];
+        let entry = find_entry(entries, TRANSACTION_ENTRY_LENGTH, tx_hash)?;
+        Some(TransactionLocation {
+            block: read_block_location(&entry[32..]),
+            index_in_block: LittleEndian::read_u32(&entry[48..]) as usize,
+        })
+    }
+
+    fn from_data(data: IndexData) -> io::Result<HashIndex> {
+        let (blk_files, block_entries, transaction_entries) = {
+            let bytes = match data {
+                IndexData::Mapped(ref mmap) => &mmap[..],
+                IndexData::Built(ref bytes) => &bytes[..],
+            };
+            if bytes.len() < HEADER_LENGTH || &bytes[..4] != MAGIC {
+                return Err(invalid_index_error("not a hash index"));
+            }
+            let version = LittleEndian::read_u32(&bytes[4..]);
+            if version != VERSION {
+                return Err(invalid_index_error(&format!(
+                    "unsupported version {}",
+                    version
+                )));
+            }
+
+            let number_of_blk_files = LittleEndian::read_u32(&bytes[8..]) as usize;
+            let counts_offset = HEADER_LENGTH + number_of_blk_files * BLK_FILE_ENTRY_LENGTH;
+            if bytes.len() < counts_offset + COUNTS_LENGTH {
+                return Err(invalid_index_error("truncated header"));
+            }
+            let blk_files = bytes[HEADER_LENGTH..counts_offset]
+                .chunks(BLK_FILE_ENTRY_LENGTH)
+                .map(|entry| {
+                    (
+                        LittleEndian::read_u32(entry) as usize,
+                        LittleEndian::read_u64(&entry[4..]),
+                    )
+                })
+                .collect();
+
+            // TODO Fix possibly truncating casts.
+            let number_of_blocks = LittleEndian::read_u64(&bytes[counts_offset..]) as usize;
+            let number_of_transactions =
+                LittleEndian::read_u64(&bytes[counts_offset + 8..]) as usize;
+            let blocks_offset = counts_offset + COUNTS_LENGTH;
+            let transactions_offset = blocks_offset + number_of_blocks * BLOCK_ENTRY_LENGTH;
+            let end_offset =
+                transactions_offset + number_of_transactions * TRANSACTION_ENTRY_LENGTH;
+            if bytes.len() != end_offset {
+                return Err(invalid_index_error("unexpected length"));
+            }
+
+            (
+                blk_files,
+                blocks_offset..transactions_offset,
+                transactions_offset..end_offset,
+            )
+        };
+
+        Ok(HashIndex {
+            data,
+            blk_files,
+            block_entries,
+            transaction_entries,
+        })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self.data {
+            IndexData::Mapped(ref mmap) => &mmap[..],
+            IndexData::Built(ref bytes) => &bytes[..],
+        }
+    }
+}
+
+fn read_blk_file_entries(
+    blk_file_index: usize,
+    blk_file_path: &str,
+    options: ReadOptions,
+) -> io::Result<BlkFileEntries> {
+    let blk_file = MappedBlkFile::open(blk_file_path, options)?;
+    let mut blocks = Vec::new();
+    let mut transactions = Vec::new();
+    let mut end_offset = 0;
+    for block in blk_file.blocks() {
+        let block = match block {
+            Ok(block) => block,
+            Err(ref error) if is_skipped_range(error) => continue,
+            Err(error) => return Err(error),
+        };
+        let location = BlockLocation {
+            blk_file_index,
+            index_in_blk_file: block.index_in_blk_file,
+            offset_in_blk_file: block.offset_in_blk_file,
+        };
+        blocks.push((block.hash.0, location));
+        end_offset = block.offset_in_blk_file + 8 + u64::from(block.size_in_bytes);
+        for (index_in_block, transaction) in block.transactions.iter().enumerate() {
+            let transaction_location = TransactionLocation {
+                block: location,
+                index_in_block,
+            };
+            transactions.push((transaction.tx_hash.0, transaction_location));
+        }
+    }
+    Ok(BlkFileEntries {
+        blk_file_index,
+        end_offset,
+        blocks,
+        transactions,
+    })
+}
+
+/// Whether a block can be read from the blk file at the given path, starting
+/// at the given offset.
+fn has_block_after(blk_file_path: &str, offset: u64, options: ReadOptions) -> io::Result<bool> {
+    let mut blocks = read_blocks_with_options(blk_file_path, options)?;
+    // The index of the block is irrelevant, only whether there is one.
+    blocks.seek_to_offset(offset, 0)?;
+    for block in blocks {
+        match block {
+            Ok(_) => return Ok(true),
+            Err(ref error) if is_skipped_range(error) => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(false)
+}
+
+/// Binary searches the given entries, which are sorted by the hash they start
+/// with, for the entry of the given hash.
+fn find_entry<'a>(entries: &'a [u8], entry_length: usize, hash: &Hash) -> Option<&'a [u8]> {
+    let mut low = 0;
+    let mut high = entries.len() / entry_length;
+    while low < high {
+        let middle = low + (high - low) / 2;
+        let entry = &entries[middle * entry_length..(middle + 1) * entry_length];
+        match entry[..32].cmp(&hash.0[..]) {
+            Ordering::Less => low = middle + 1,
+            Ordering::Greater => high = middle,
+            Ordering::Equal => return Some(entry),
+        }
+    }
+    None
+}
+
+fn read_block_location(bytes: &[u8]) -> BlockLocation {
+    BlockLocation {
+        blk_file_index: LittleEndian::read_u32(bytes) as usize,
+        index_in_blk_file: LittleEndian::read_u32(&bytes[4..]) as usize,
+        offset_in_blk_file: LittleEndian::read_u64(&bytes[8..]),
+    }
+}
+
+fn write_block_location(bytes: &mut Vec<u8>, location: &BlockLocation) -> io::Result<()> {
+    bytes.write_u32::<LittleEndian>(location.blk_file_index as u32)?;
+    bytes.write_u32::<LittleEndian>(location.index_in_blk_file as u32)?;
+    bytes.write_u64::<LittleEndian>(location.offset_in_blk_file)
+}
+
+fn invalid_index_error(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid hash index ({})", reason),
+    )
+}
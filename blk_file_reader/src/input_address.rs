@@ -0,0 +1,398 @@
+use disassembly::{decode_script, is_signature, Instruction, Opcode};
+use domain::{
+    is_valid_public_key_size, Address, Input, Network, ScriptType, ScriptWitness,
+    ScriptWitnessItem, SpendType, TaprootSpend, Transaction, TAPSCRIPT_LEAF_VERSION,
+};
+use read::{calculate_hash160, p2pkh_address, p2sh_address, witness_program_address};
+
+const OP_CHECKSIG: u8 = 0xAC;
+const OP_CHECKMULTISIGVERIFY: u8 = 0xAF;
+
+/// The address of the output spent by an input, as derived from the input
+/// script and witness, see `derive_input_address`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputAddress {
+    /// The type of the spent output, i.e. `P2PKH`, `P2SH` or `P2WPKH`.
+    pub script_type: ScriptType,
+    pub address: Address,
+}
+
+/// Derives the address of the output spent by an input from the public key or
+/// redeem script revealed by its input script and witness.
+///
+/// The following spends are recognized:
+///
+/// * P2PKH, whose input script pushes a signature and a public key.
+/// * P2SH, including P2SH-P2WPKH and P2SH-P2WSH, whose input script ends with
+///   the push of a redeem script. Since any data could be pushed last, only
+///   standard redeem scripts and scripts ending in an `OP_CHECKSIG` family
+///   opcode are accepted.
+/// * P2WPKH, whose witness holds a signature and a compressed public key.
+///
+/// Returns `None` for all other inputs. In particular, P2PK and bare multisig
+/// spends do not reveal their public keys, while P2WSH and P2TR spends cannot
+/// be told apart without the spent output.
+pub fn derive_input_address(
+    script: &[u8],
+    witness: Option<&ScriptWitness>,
+    network: Network,
+) -> Option<InputAddress> {
+    let pushes = read_pushes(script)?;
+    if pushes.is_empty() {
+        let witness_items = witness.map(|witness| &witness.items[..]).unwrap_or(&[]);
+        return derive_p2wpkh_address(witness_items, network);
+    }
+
+    if pushes.len() == 2 && is_signature(pushes[0]) && is_valid_public_key_size(pushes[1]) {
+        return Some(InputAddress {
+            script_type: ScriptType::P2PKH,
+            address: p2pkh_address(pushes[1], network),
+        });
+    }
+
+    let redeem_script = pushes[pushes.len() - 1];
+    if is_redeem_script(redeem_script) {
+        return Some(InputAddress {
+            script_type: ScriptType::P2SH,
+            address: p2sh_address(redeem_script, network),
+        });
+    }
+
+    None
+}
+
+/// Returns the data pushed by the given input script, or `None` if it contains
+/// anything but data pushes.
+fn read_pushes(script: &[u8]) -> Option<Vec<&[u8]>> {
+    decode_script(script)
+        .map(|instruction| match instruction {
+            Ok(Instruction::Push { data, .. }) => Some(data),
+            _ => None,
+        })
+        .collect()
+}
+
+fn derive_p2wpkh_address(
+    witness_items: &[ScriptWitnessItem],
+    network: Network,
+) -> Option<InputAddress> {
+    let public_key = read_p2wpkh_public_key(witness_items)?;
+    Some(InputAddress {
+        script_type: ScriptType::P2WPKH,
+        address: witness_program_address(0, &calculate_hash160(public_key), network),
+    })
+}
+
+/// Returns the public key of the given witness if it consists of a signature
+/// and a public key, as required for spending P2WPKH outputs.
+fn read_p2wpkh_public_key(witness_items: &[ScriptWitnessItem]) -> Option<&[u8]> {
+    if witness_items.len() != 2 || !is_signature(&witness_items[0]) {
+        return None;
+    }
+
+    // Segwit requires compressed public keys.
+    let public_key = &witness_items[1];
+    match public_key.first() {
+        Some(&0x02) | Some(&0x03) if public_key.len() == 33 => Some(public_key),
+        _ => None,
+    }
+}
+
+/// Whether the given data, pushed last by an input script, is likely to be the
+/// redeem script of a P2SH output.
+fn is_redeem_script(script: &[u8]) -> bool {
+    match ScriptType::from_script(script) {
+        ScriptType::P2PK
+        | ScriptType::P2PKH
+        | ScriptType::Multisig
+        | ScriptType::P2WPKH
+        | ScriptType::P2WSH => true,
+        _ => match decode_script(script).last() {
+            Some(Ok(Instruction::Op(Opcode(opcode)))) => {
+                opcode >= OP_CHECKSIG && opcode <= OP_CHECKMULTISIGVERIFY
+            }
+            _ => false,
+        },
+    }
+}
+
+impl SpendType {
+    /// Classifies an input by the patterns of its input script and witness.
+    ///
+    /// Since the spent output is unknown, the classification is a heuristic.
+    /// For instance, a witness is only considered to spend a P2TR output by
+    /// script path if its leaf script is tapscript, while all other witnesses
+    /// that are neither P2WPKH nor P2TR spends are considered P2WSH spends.
+    /// Coinbase inputs cannot be told apart by their script, see
+    /// `Transaction::input_spend_types`.
+    pub fn from_input(script: &[u8], witness: Option<&ScriptWitness>) -> SpendType {
+        let pushes = match read_pushes(script) {
+            Some(pushes) => pushes,
+            None => return SpendType::NonStandard,
+        };
+        let witness = witness.filter(|witness| !witness.items.is_empty());
+
+        match (pushes.len(), witness) {
+            (0, Some(witness)) => classify_witness(witness),
+            (0, None) => SpendType::NonStandard,
+            (1, Some(_)) => match ScriptType::from_script(pushes[0]) {
+                ScriptType::P2WPKH => SpendType::P2SHP2WPKH,
+                ScriptType::P2WSH => SpendType::P2SHP2WSH,
+                _ => SpendType::NonStandard,
+            },
+            (_, Some(_)) => SpendType::NonStandard,
+            (_, None) => classify_input_script(&pushes),
+        }
+    }
+}
+
+/// Classifies an input without input script by its witness.
+fn classify_witness(witness: &ScriptWitness) -> SpendType {
+    if read_p2wpkh_public_key(&witness.items).is_some() {
+        return SpendType::P2WPKH;
+    }
+
+    match witness.taproot_spend() {
+        Some(TaprootSpend::KeyPath { .. }) => SpendType::P2TRKeyPath,
+        Some(TaprootSpend::ScriptPath {
+            ref control_block, ..
+        }) if control_block.leaf_version == TAPSCRIPT_LEAF_VERSION => SpendType::P2TRScriptPath,
+        _ => SpendType::P2WSH,
+    }
+}
+
+/// Classifies an input without witness by the data pushed by its input script.
+fn classify_input_script(pushes: &[&[u8]]) -> SpendType {
+    let last_push = pushes[pushes.len() - 1];
+    if pushes.len() == 2 && is_signature(pushes[0]) && is_valid_public_key_size(pushes[1]) {
+        SpendType::P2PKH
+    } else if pushes.len() == 1 && is_signature(last_push) {
+        SpendType::P2PK
+    } else if pushes.len() > 1
+        && pushes[0].is_empty()
+        && pushes[1..].iter().all(|push| is_signature(push))
+    {
+        // The leading push works around an off-by-one error of
+        // `OP_CHECKMULTISIG`.
+        SpendType::Multisig
+    } else if is_redeem_script(last_push) {
+        SpendType::P2SH
+    } else {
+        SpendType::NonStandard
+    }
+}
+
+impl Input {
+    /// Classifies this input, see `SpendType::from_input`.
+    pub fn spend_type(&self, witness: Option<&ScriptWitness>) -> SpendType {
+        SpendType::from_input(&self.script, witness)
+    }
+
+    /// Derives the address of the output spent by this input, see
+    /// `derive_input_address`.
+    pub fn derive_address(
+        &self,
+        witness: Option<&ScriptWitness>,
+        network: Network,
+    ) -> Option<InputAddress> {
+        derive_input_address(&self.script, witness, network)
+    }
+}
+
+impl Transaction {
+    /// Derives the addresses of the outputs spent by the inputs of this
+    /// transaction, see `derive_input_address`.
+    ///
+    /// The returned vector has an entry for each input, which is `None` for
+    /// the input of a coinbase transaction and inputs whose address cannot be
+    /// derived.
+    pub fn input_addresses(&self, network: Network) -> Vec<Option<InputAddress>> {
+        if self.is_coinbase() {
+            return vec![None];
+        }
+        self.inputs
+            .iter()
+            .enumerate()
+            .map(|(input_index, input)| {
+                input.derive_address(self.script_witnesses.get(input_index), network)
+            })
+            .collect()
+    }
+
+    /// Classifies the inputs of this transaction, see `SpendType::from_input`.
+    pub fn input_spend_types(&self) -> Vec<SpendType> {
+        if self.is_coinbase() {
+            return vec![SpendType::Coinbase];
+        }
+        self.inputs
+            .iter()
+            .enumerate()
+            .map(|(input_index, input)| input.spend_type(self.script_witnesses.get(input_index)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use data_encoding::HEXLOWER;
+
+    const SIGNATURE: &'static str = "3044022057dbbf2a5b5ac4ce32c8bc4dfd9c5f0bc2b8d2bd14bd8e43a4d3b6ec0bbc4b6c02200f9e6a0c5d5d1d6f3a6b6f2a8f5b0c8d3a5e7c9b1d2f4a6c8e0b2d4f6a8c0e2d01";
+    const PUBLIC_KEY: &'static str =
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    fn decode(hex: &str) -> Vec<u8> {
+        HEXLOWER.decode(hex.as_bytes()).unwrap()
+    }
+
+    fn push(data: &[u8]) -> Vec<u8> {
+        let mut script = vec![data.len() as u8];
+        script.extend_from_slice(data);
+        script
+    }
+
+    #[test]
+    fn derives_p2pkh_address_from_input_script() {
+        // given
+        let mut script = push(&decode(SIGNATURE));
+        script.extend(push(&decode(PUBLIC_KEY)));
+
+        // when
+        let input_address = derive_input_address(&script, None, Network::Mainnet).unwrap();
+
+        // then
+        assert_eq!(input_address.script_type, ScriptType::P2PKH);
+        assert_eq!(
+            input_address.address.encoded,
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"
+        );
+    }
+
+    #[test]
+    fn derives_p2wpkh_address_from_witness() {
+        // given
+        let witness = ScriptWitness {
+            items: vec![decode(SIGNATURE), decode(PUBLIC_KEY)],
+        };
+
+        // when
+        let input_address = derive_input_address(&[], Some(&witness), Network::Mainnet).unwrap();
+
+        // then
+        assert_eq!(input_address.script_type, ScriptType::P2WPKH);
+        assert_eq!(
+            input_address.address.encoded,
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+    }
+
+    #[test]
+    fn derives_p2sh_address_from_nested_witness_program() {
+        // given
+        let script = push(&decode("0014751e76e8199196d454941c45d1b3a323f1433bd6"));
+        let witness = ScriptWitness {
+            items: vec![decode(SIGNATURE), decode(PUBLIC_KEY)],
+        };
+
+        // when
+        let input_address =
+            derive_input_address(&script, Some(&witness), Network::Mainnet).unwrap();
+
+        // then
+        assert_eq!(input_address.script_type, ScriptType::P2SH);
+        assert_eq!(
+            input_address.address.encoded,
+            "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN"
+        );
+    }
+
+    #[test]
+    fn derives_p2sh_address_from_multisig_redeem_script() {
+        // given
+        let mut script = vec![0x00];
+        script.extend(push(&decode(SIGNATURE)));
+        script.extend(push(&decode(
+            "51210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f8179851ae",
+        )));
+
+        // when
+        let input_address = derive_input_address(&script, None, Network::Mainnet).unwrap();
+
+        // then
+        assert_eq!(input_address.script_type, ScriptType::P2SH);
+        assert_eq!(
+            input_address.address.encoded,
+            "3DicS6C8JZm59RsrgXr56iVHzYdQngiehV"
+        );
+    }
+
+    #[test]
+    fn cannot_derive_address_of_p2pk_spend() {
+        // given
+        let script = push(&decode(SIGNATURE));
+
+        // when
+        let input_address = derive_input_address(&script, None, Network::Mainnet);
+
+        // then
+        assert_eq!(input_address, None);
+    }
+
+    #[test]
+    fn classifies_input_scripts() {
+        // given
+        let signature = push(&decode(SIGNATURE));
+        let mut p2pkh_script = signature.clone();
+        p2pkh_script.extend(push(&decode(PUBLIC_KEY)));
+        let mut multisig_script = vec![0x00];
+        multisig_script.extend(&signature);
+        multisig_script.extend(&signature);
+
+        // when
+        let p2pkh = SpendType::from_input(&p2pkh_script, None);
+        let p2pk = SpendType::from_input(&signature, None);
+        let multisig = SpendType::from_input(&multisig_script, None);
+        let non_standard = SpendType::from_input(&[0x6A], None);
+
+        // then
+        assert_eq!(p2pkh, SpendType::P2PKH);
+        assert_eq!(p2pk, SpendType::P2PK);
+        assert_eq!(multisig, SpendType::Multisig);
+        assert_eq!(non_standard, SpendType::NonStandard);
+    }
+
+    #[test]
+    fn classifies_witnesses() {
+        // given
+        let p2wpkh_witness = ScriptWitness {
+            items: vec![decode(SIGNATURE), decode(PUBLIC_KEY)],
+        };
+        let nested_script = push(&decode("0014751e76e8199196d454941c45d1b3a323f1433bd6"));
+        let key_path_witness = ScriptWitness {
+            items: vec![vec![0x01; 64]],
+        };
+        let mut control_block = vec![TAPSCRIPT_LEAF_VERSION];
+        control_block.extend_from_slice(&[0x02; 32]);
+        let script_path_witness = ScriptWitness {
+            items: vec![vec![0x01; 64], vec![0xAC], control_block],
+        };
+        let p2wsh_witness = ScriptWitness {
+            items: vec![vec![], decode(SIGNATURE), vec![0x51, 0xAE]],
+        };
+
+        // when
+        let p2wpkh = SpendType::from_input(&[], Some(&p2wpkh_witness));
+        let p2sh_p2wpkh = SpendType::from_input(&nested_script, Some(&p2wpkh_witness));
+        let key_path = SpendType::from_input(&[], Some(&key_path_witness));
+        let script_path = SpendType::from_input(&[], Some(&script_path_witness));
+        let p2wsh = SpendType::from_input(&[], Some(&p2wsh_witness));
+
+        // then
+        assert_eq!(p2wpkh, SpendType::P2WPKH);
+        assert_eq!(p2sh_p2wpkh, SpendType::P2SHP2WPKH);
+        assert_eq!(key_path, SpendType::P2TRKeyPath);
+        assert_eq!(script_path, SpendType::P2TRScriptPath);
+        assert_eq!(p2wsh, SpendType::P2WSH);
+    }
+}
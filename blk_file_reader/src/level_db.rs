@@ -0,0 +1,66 @@
+use db_key::Key;
+use leveldb::database::Database;
+use leveldb::iterator::{Iterable, LevelDBIterator};
+use leveldb::kv::KV;
+use leveldb::options::{Options, ReadOptions};
+use std::io;
+use std::path::Path;
+
+/// A raw LevelDB key as used by Bitcoin Core, i.e. a one-byte prefix that
+/// denotes the type of the record followed by its serialized key.
+pub(crate) struct LevelDbKey(pub Vec<u8>);
+
+impl Key for LevelDbKey {
+    fn from_u8(key: &[u8]) -> LevelDbKey {
+        LevelDbKey(key.to_vec())
+    }
+
+    fn as_slice<T, F: Fn(&[u8]) -> T>(&self, f: F) -> T {
+        f(&self.0)
+    }
+}
+
+/// Opens one of Bitcoin Core's LevelDB databases.
+///
+/// LevelDB allows only a single process to open a database, so Bitcoin Core
+/// must not be running while the database is read. Since LevelDB may compact a
+/// database when opening it, consider reading from a copy.
+pub(crate) fn open_level_db(path: &Path) -> io::Result<Database<LevelDbKey>> {
+    Database::open(path, Options::new()).map_err(|error| {
+        io::Error::new(
+            io::ErrorKind::Other,
+            format!("could not open {} ({})", path.display(), error),
+        )
+    })
+}
+
+/// Returns the value of the record with the given key, or `None` if there is
+/// no such record.
+pub(crate) fn get_record(
+    database: &Database<LevelDbKey>,
+    key: &[u8],
+) -> io::Result<Option<Vec<u8>>> {
+    database
+        .get(ReadOptions::new(), LevelDbKey(key.to_vec()))
+        .map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string()))
+}
+
+/// Calls `f` with the key (without prefix) and value of every record whose key
+/// starts with the given prefix, in ascending key order.
+pub(crate) fn for_each_record<F>(
+    database: &Database<LevelDbKey>,
+    prefix: u8,
+    mut f: F,
+) -> io::Result<()>
+where
+    F: FnMut(&[u8], &[u8]) -> io::Result<()>,
+{
+    let first_key = LevelDbKey(vec![prefix]);
+    for (key, value) in database.iter(ReadOptions::new()).from(&first_key) {
+        if key.0.first() != Some(&prefix) {
+            break;
+        }
+        f(&key.0[1..], &value)?;
+    }
+    Ok(())
+}
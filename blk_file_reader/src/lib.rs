@@ -1,16 +1,63 @@
 extern crate byteorder;
 extern crate crypto;
 extern crate data_encoding;
+extern crate db_key;
 extern crate keys;
+extern crate leveldb;
+extern crate memmap;
+extern crate rayon;
 extern crate script;
 #[macro_use]
 extern crate serde_derive;
 
+mod base58check;
+mod bech32;
+mod block_index;
 mod blocks;
+mod chain_stats;
+mod chainstate;
+mod coinbase;
+mod compression;
+mod core_var_int;
+mod disassembly;
 mod domain;
+mod find;
+mod hash_index;
+mod input_address;
+mod level_db;
+mod mapped_blk_file;
+mod par_blocks;
+mod parse_error;
+mod positioned_read;
 mod read;
+mod read_options;
+mod read_ref;
+mod u256;
+mod undo;
 mod util;
+mod validation;
+mod write;
+mod xor;
 
+pub use block_index::BlockIndex;
 pub use blocks::Blocks;
+pub use chain_stats::{format_unix_time, ChainStats, Distribution};
+pub use chainstate::{Chainstate, Utxos};
+pub use coinbase::{read_bip34_height, read_coinbase_tag};
+pub use disassembly::{
+    decode_script, input_script_to_asm, script_to_asm, Instruction, Instructions, Opcode,
+    ScriptDecodeError,
+};
 pub use domain::*;
+pub use find::{find_block, find_transaction};
+pub use hash_index::HashIndex;
+pub use input_address::{derive_input_address, InputAddress};
+pub use mapped_blk_file::{BlockRefs, MappedBlkFile};
+pub use par_blocks::ParBlocks;
+pub use parse_error::{is_skipped_range, ParseError};
+pub use read::ReadBlock;
+pub use read_options::ReadOptions;
 pub use util::*;
+pub use validation::{validate_block, ValidationError};
+pub use write::WriteBlock;
+pub use xor::{read_xor_key, XOR_KEY_FILE_NAME};
@@ -1,20 +1,86 @@
 extern crate blk_file_reader;
 extern crate clap;
+extern crate csv;
+extern crate data_encoding;
 #[macro_use]
 extern crate log;
+extern crate rayon;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 extern crate simplelog;
 
-use blk_file_reader::{read_blk_files, read_blocks};
-use clap::{crate_version, App, Arg};
-use simplelog::{Config, LogLevelFilter, SimpleLogger};
+mod record_printer;
+mod stats_report;
+
+use blk_file_reader::{
+    blk_file_index, find_block, find_transaction, is_skipped_range, par_read_blocks,
+    read_blk_files, read_blocks_with_options, read_located_block, Block, ChainStats, Hash,
+    HashIndex, Input, Network, Output, ReadOptions, Transaction,
+};
+use clap::{crate_version, App, AppSettings, Arg, SubCommand};
+use data_encoding::HEXLOWER;
+use rayon::prelude::*;
+use record_printer::{Format, RecordPrinter, Records};
+use simplelog::{Config, LogLevelFilter, SimpleLogger, WriteLogger};
+use stats_report::{print_stats_report, BlkFileStats};
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
 use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 fn main() {
     let matches = App::new("blk_file_reader")
         .version(crate_version!())
         .about("Read bitcoin blk files")
-        .arg(
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            SubCommand::with_name("decode-tx")
+                .about("Decode raw transactions, read from stdin as one hex string per line"),
+        ).subcommand(
+            SubCommand::with_name("find-block")
+                .about("Find the block with the given hash in a directory of blk files")
+                .arg(
+                    Arg::with_name("PATH")
+                        .required(true)
+                        .index(1)
+                        .help("Path to the blk files that should be searched"),
+                ).arg(
+                    Arg::with_name("HASH")
+                        .required(true)
+                        .index(2)
+                        .validator(validate_hash)
+                        .help("Hash of the block in hex"),
+                ).arg(index_arg()),
+        ).subcommand(
+            SubCommand::with_name("find-tx")
+                .about("Find the transaction with the given hash in a directory of blk files")
+                .arg(
+                    Arg::with_name("PATH")
+                        .required(true)
+                        .index(1)
+                        .help("Path to the blk files that should be searched"),
+                ).arg(
+                    Arg::with_name("TXID")
+                        .required(true)
+                        .index(2)
+                        .validator(validate_hash)
+                        .help("Hash of the transaction in hex"),
+                ).arg(index_arg()),
+        ).subcommand(
+            SubCommand::with_name("stats")
+                .about("Report statistics of the blocks of a blk file or a directory of blk files")
+                .arg(
+                    Arg::with_name("PATH")
+                        .required(true)
+                        .index(1)
+                        .help("Path to the blk files that should be read"),
+                ),
+        ).arg(
             Arg::with_name("PATH")
                 .required(true)
                 .index(1)
@@ -23,7 +89,7 @@ fn main() {
             Arg::with_name("full")
                 .short("f")
                 .long("full")
-                .help("Print full block information"),
+                .help("Print full block information, showing scripts in ASM notation"),
         ).arg(
             // TODO Meaningful if PATH is a directory?
             Arg::with_name("skip")
@@ -38,13 +104,114 @@ fn main() {
                 .long("limit")
                 .help("Maximum number of blocks to read")
                 .takes_value(true),
+        ).arg(
+            Arg::with_name("network")
+                .global(true)
+                .short("n")
+                .long("network")
+                .help("Network the blk files belong to")
+                .possible_values(&["mainnet", "testnet", "signet", "regtest"])
+                .default_value("mainnet"),
+        ).arg(
+            Arg::with_name("no-p2pk-address-derivation")
+                .global(true)
+                .long("no-p2pk-address-derivation")
+                .help("Do not derive P2PKH addresses for P2PK outputs"),
+        ).arg(
+            Arg::with_name("validate")
+                .global(true)
+                .long("validate")
+                .help("Check proof of work, merkle root and witness commitment of every block"),
+        ).arg(
+            Arg::with_name("resync")
+                .global(true)
+                .long("resync")
+                .help("Skip corrupt data by scanning forward to the next magic number"),
+        ).arg(
+            Arg::with_name("threads")
+                .global(true)
+                .short("t")
+                .long("threads")
+                .help("Number of blk files to read in parallel (defaults to the number of CPUs)")
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("format")
+                .global(true)
+                .long("format")
+                .help("Print records to stdout in the given format instead of logging blocks")
+                .possible_values(&["json", "ndjson", "csv"])
+                .takes_value(true),
+        ).arg(
+            Arg::with_name("records")
+                .global(true)
+                .long("records")
+                .help("Kind of records printed with --format")
+                .possible_values(&["blocks", "transactions", "outputs"])
+                .default_value("blocks"),
         ).get_matches();
 
     configure_logger(&matches);
-    let path = matches.value_of("PATH").unwrap();
+    let network = matches
+        .value_of("network")
+        .unwrap()
+        .parse::<Network>()
+        .unwrap();
+    let options = ReadOptions {
+        derive_p2pk_addresses: !matches.is_present("no-p2pk-address-derivation"),
+        validate_blocks: matches.is_present("validate"),
+        resync: matches.is_present("resync"),
+        ..ReadOptions::new(network)
+    };
+    let printer = matches.value_of("format").map(|format| {
+        let records = matches
+            .value_of("records")
+            .unwrap()
+            .parse::<Records>()
+            .unwrap();
+        RecordPrinter::new(format.parse::<Format>().unwrap(), records, network)
+    });
+
+    let number_of_threads = matches
+        .value_of("threads")
+        .unwrap_or("0")
+        .parse::<usize>()
+        .unwrap();
+
+    if matches.subcommand_matches("decode-tx").is_some() {
+        let is_complete = decode_transactions(options, printer.as_ref());
+        finish(printer.as_ref());
+        if !is_complete {
+            process::exit(1);
+        }
+        return;
+    }
+    if let Some(find_matches) = matches.subcommand_matches("find-block") {
+        build_thread_pool(number_of_threads)
+            .install(|| print_found_block(find_matches, options, printer.as_ref()));
+        finish(printer.as_ref());
+        return;
+    }
+    if let Some(find_matches) = matches.subcommand_matches("find-tx") {
+        build_thread_pool(number_of_threads)
+            .install(|| print_found_transaction(find_matches, options, printer.as_ref()));
+        finish(printer.as_ref());
+        return;
+    }
 
+    if let Some(stats_matches) = matches.subcommand_matches("stats") {
+        let path = stats_matches.value_of("PATH").unwrap();
+        let is_complete = build_thread_pool(number_of_threads)
+            .install(|| print_stats(path, options, printer.as_ref()));
+        finish(printer.as_ref());
+        if !is_complete {
+            process::exit(1);
+        }
+        return;
+    }
+
+    let path = matches.value_of("PATH").unwrap();
     if Path::new(path).is_dir() {
-        print_blk_files(path);
+        print_blk_files(path, options, number_of_threads, printer.as_ref());
     } else {
         let number_of_blocks_to_skip = matches
             .value_of("skip")
@@ -56,8 +223,29 @@ fn main() {
         } else {
             usize::max_value()
         };
-        print_blk_file(path, number_of_blocks_to_skip, limit);
+        print_blk_file(
+            path,
+            options,
+            number_of_blocks_to_skip,
+            limit,
+            printer.as_ref(),
+        );
     }
+    finish(printer.as_ref());
+}
+
+fn index_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("index")
+        .short("i")
+        .long("index")
+        .help("Sidecar index file to look up hashes in, which is built if it is missing or out of date")
+        .takes_value(true)
+}
+
+fn validate_hash(hash: String) -> Result<(), String> {
+    Hash::from_hex(&hash)
+        .map(|_| ())
+        .map_err(|error| error.to_string())
 }
 
 fn configure_logger(matches: &clap::ArgMatches) {
@@ -66,29 +254,67 @@ fn configure_logger(matches: &clap::ArgMatches) {
     } else {
         LogLevelFilter::Info
     };
-    SimpleLogger::init(log_level, Config::default()).unwrap();
+    if matches.is_present("format") {
+        // Keep stdout free for the printed records.
+        WriteLogger::init(log_level, Config::default(), io::stderr()).unwrap();
+    } else {
+        SimpleLogger::init(log_level, Config::default()).unwrap();
+    }
 }
 
-fn print_blk_files(blk_file_dir: &str) {
+fn print_blk_files(
+    blk_file_dir: &str,
+    options: ReadOptions,
+    number_of_threads: usize,
+    printer: Option<&RecordPrinter>,
+) {
     info!("Read blk files at {}", blk_file_dir);
-    let mut blk_file_counter = 0;
-    // TODO Return error instead of panicking.
-    for blk_file in read_blk_files(blk_file_dir).unwrap() {
-        print_blk_file(&blk_file, 0, usize::max_value());
-        blk_file_counter += 1;
-    }
-    info!("Processed {} blk files", blk_file_counter);
+    let thread_pool = build_thread_pool(number_of_threads);
+    let blocks = par_read_blocks(blk_file_dir, options).unwrap();
+    let block_counter = AtomicUsize::new(0);
+    thread_pool.install(|| {
+        blocks.for_each(|(blk_file_index, block)| match block {
+            Ok(ref block) => {
+                print_block(block, printer);
+                block_counter.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(ref error) if is_skipped_range(error) => {
+                warn!("Skipped data in blk file {} ({})", blk_file_index, error);
+            }
+            Err(ref error) => {
+                error!(
+                    "Could not read blk file {} (reason: {})",
+                    blk_file_index, error
+                );
+            }
+        });
+    });
+    info!(
+        "Processed {} blocks in {}",
+        block_counter.load(Ordering::Relaxed),
+        blk_file_dir
+    );
 }
 
-fn print_blk_file(blk_file_path: &str, number_of_blocks_to_skip: usize, limit: usize) {
+fn print_blk_file(
+    blk_file_path: &str,
+    options: ReadOptions,
+    number_of_blocks_to_skip: usize,
+    limit: usize,
+    printer: Option<&RecordPrinter>,
+) {
     info!("Read {}", blk_file_path);
     // TODO Return error instead of panicking.
-    let blocks = read_blocks(blk_file_path).unwrap();
+    let blocks = read_blocks_with_options(blk_file_path, options).unwrap();
     let blocks = blocks.skip(number_of_blocks_to_skip);
     let mut block_counter = 0;
     for block in blocks {
         match block {
-            Ok(ref block) => debug!("{:#?}", block),
+            Ok(ref block) => print_block(block, printer),
+            Err(ref error) if is_skipped_range(error) => {
+                warn!("Skipped data ({})", error);
+                continue;
+            }
             Err(ref error) => {
                 error!("Could not read file (reason: {})", error.description());
                 break;
@@ -102,3 +328,430 @@ fn print_blk_file(blk_file_path: &str, number_of_blocks_to_skip: usize, limit: u
     }
     info!("Processed {} blocks in {}", block_counter, blk_file_path);
 }
+
+/// Prints the statistics of the `stats` subcommand for every blk file at the
+/// given path as well as their total.
+///
+/// Returns whether all blk files have been read completely. The statistics of
+/// the others only cover the blocks before the first error.
+fn print_stats(path: &str, options: ReadOptions, printer: Option<&RecordPrinter>) -> bool {
+    let blk_file_stats = if Path::new(path).is_dir() {
+        collect_blk_files_stats(path, options)
+    } else {
+        let name = Path::new(path)
+            .file_name()
+            .map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned());
+        vec![collect_blk_file_stats(name, path, options)]
+    };
+    let mut total = ChainStats::new();
+    for blk_file_stats in blk_file_stats.iter() {
+        total.merge(&blk_file_stats.stats);
+    }
+    let number_of_incomplete_blk_files = blk_file_stats
+        .iter()
+        .filter(|blk_file_stats| !blk_file_stats.is_complete)
+        .count();
+    let is_complete = number_of_incomplete_blk_files == 0;
+
+    match printer {
+        Some(printer) => {
+            for blk_file_stats in blk_file_stats.iter() {
+                printer
+                    .print_stats(
+                        &blk_file_stats.name,
+                        &blk_file_stats.stats,
+                        blk_file_stats.is_complete,
+                    )
+                    .unwrap_or_else(|error| exit_on_print_error(&error));
+            }
+            printer
+                .print_stats("total", &total, is_complete)
+                .unwrap_or_else(|error| exit_on_print_error(&error));
+        }
+        None => print_stats_report(&blk_file_stats, &total)
+            .unwrap_or_else(|error| exit_on_print_error(&error)),
+    }
+
+    if !is_complete {
+        error!(
+            "Could not read {} of {} blk files completely",
+            number_of_incomplete_blk_files,
+            blk_file_stats.len()
+        );
+    }
+    is_complete
+}
+
+/// Collects the statistics of the blk files at the given path in parallel,
+/// ordered by blk file.
+///
+/// Blk files without any blocks are included with empty statistics.
+fn collect_blk_files_stats(blk_file_dir: &str, options: ReadOptions) -> Vec<BlkFileStats> {
+    info!("Read blk files at {}", blk_file_dir);
+    // TODO Return error instead of panicking.
+    let blk_file_indexes: Vec<usize> = read_blk_files(blk_file_dir)
+        .unwrap()
+        .iter()
+        .filter_map(|blk_file_path| blk_file_index(blk_file_path))
+        .collect();
+    let blocks = par_read_blocks(blk_file_dir, options).unwrap();
+    let mut stats_by_blk_file = blocks
+        .fold(BTreeMap::new, |mut stats_by_blk_file, (blk_file_index, block)| {
+            let entry = stats_by_blk_file
+                .entry(blk_file_index)
+                .or_insert_with(|| (ChainStats::new(), true));
+            match block {
+                Ok(ref block) => entry.0.add_block(block),
+                Err(ref error) if is_skipped_range(error) => {
+                    warn!("Skipped data in blk file {} ({})", blk_file_index, error);
+                }
+                Err(ref error) => {
+                    error!(
+                        "Could not read blk file {} (reason: {})",
+                        blk_file_index, error
+                    );
+                    entry.1 = false;
+                }
+            }
+            stats_by_blk_file
+        }).reduce(BTreeMap::new, |mut stats_by_blk_file, other| {
+            for (blk_file_index, (stats, is_complete)) in other {
+                let entry = stats_by_blk_file
+                    .entry(blk_file_index)
+                    .or_insert_with(|| (ChainStats::new(), true));
+                entry.0.merge(&stats);
+                entry.1 &= is_complete;
+            }
+            stats_by_blk_file
+        });
+    for blk_file_index in blk_file_indexes {
+        stats_by_blk_file
+            .entry(blk_file_index)
+            .or_insert_with(|| (ChainStats::new(), true));
+    }
+    stats_by_blk_file
+        .into_iter()
+        .map(|(blk_file_index, (stats, is_complete))| BlkFileStats {
+            name: format!("blk{:05}.dat", blk_file_index),
+            stats,
+            is_complete,
+        }).collect()
+}
+
+fn collect_blk_file_stats(name: String, blk_file_path: &str, options: ReadOptions) -> BlkFileStats {
+    info!("Read {}", blk_file_path);
+    // TODO Return error instead of panicking.
+    let blocks = read_blocks_with_options(blk_file_path, options).unwrap();
+    let mut stats = ChainStats::new();
+    let mut is_complete = true;
+    for block in blocks {
+        match block {
+            Ok(ref block) => stats.add_block(block),
+            Err(ref error) if is_skipped_range(error) => warn!("Skipped data ({})", error),
+            Err(ref error) => {
+                error!("Could not read file (reason: {})", error);
+                is_complete = false;
+                break;
+            }
+        }
+    }
+    BlkFileStats {
+        name,
+        stats,
+        is_complete,
+    }
+}
+
+fn build_thread_pool(number_of_threads: usize) -> rayon::ThreadPool {
+    // TODO Return error instead of panicking.
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(number_of_threads)
+        .build()
+        .unwrap()
+}
+
+/// Prints the block of the `find-block` subcommand, which is looked up in the
+/// index given by `--index` or searched for in all blk files otherwise.
+fn print_found_block(
+    matches: &clap::ArgMatches,
+    options: ReadOptions,
+    printer: Option<&RecordPrinter>,
+) {
+    let path = matches.value_of("PATH").unwrap();
+    let hash = Hash::from_hex(matches.value_of("HASH").unwrap()).unwrap();
+    let found = match matches.value_of("index") {
+        Some(path_to_index) => {
+            open_hash_index(path, path_to_index, options).and_then(|hash_index| {
+                match hash_index.block_location(&hash) {
+                    Some(location) => read_located_block(path, &location, options)
+                        .map(|block| Some((location, block))),
+                    None => Ok(None),
+                }
+            })
+        }
+        None => find_block(path, &hash, options),
+    };
+
+    match found {
+        Ok(Some((location, block))) => {
+            info!(
+                "Found block {:?} in {} at offset {} (block {} of the file)",
+                hash,
+                location.blk_file_name(),
+                location.offset_in_blk_file,
+                location.index_in_blk_file
+            );
+            match printer {
+                Some(printer) => printer
+                    .print_found_block(&block, &location)
+                    .unwrap_or_else(|error| exit_on_print_error(&error)),
+                None => println!("{:#?}", FullBlock(&block)),
+            }
+        }
+        Ok(None) => {
+            error!("Block {:?} not found in {}", hash, path);
+            process::exit(1);
+        }
+        Err(ref error) => {
+            error!("Could not find block {:?} (reason: {})", hash, error);
+            process::exit(1);
+        }
+    }
+}
+
+/// Prints the transaction of the `find-tx` subcommand, see
+/// `print_found_block`.
+fn print_found_transaction(
+    matches: &clap::ArgMatches,
+    options: ReadOptions,
+    printer: Option<&RecordPrinter>,
+) {
+    let path = matches.value_of("PATH").unwrap();
+    let tx_hash = Hash::from_hex(matches.value_of("TXID").unwrap()).unwrap();
+    let found = match matches.value_of("index") {
+        Some(path_to_index) => {
+            open_hash_index(path, path_to_index, options).and_then(|hash_index| {
+                match hash_index.transaction_location(&tx_hash) {
+                    Some(location) => read_located_block(path, &location.block, options)
+                        .map(|block| Some((location, block))),
+                    None => Ok(None),
+                }
+            })
+        }
+        None => find_transaction(path, &tx_hash, options),
+    };
+
+    match found {
+        Ok(Some((location, block))) => {
+            info!(
+                "Found transaction {:?} in {} at offset {} (transaction {} of block {:?})",
+                tx_hash,
+                location.block.blk_file_name(),
+                location.block.offset_in_blk_file,
+                location.index_in_block,
+                block.hash
+            );
+            let transaction = &block.transactions[location.index_in_block];
+            match printer {
+                Some(printer) => printer
+                    .print_found_transaction(transaction, &block.hash, &location)
+                    .unwrap_or_else(|error| exit_on_print_error(&error)),
+                None => println!("{:#?}", FullTransaction(transaction)),
+            }
+        }
+        Ok(None) => {
+            error!("Transaction {:?} not found in {}", tx_hash, path);
+            process::exit(1);
+        }
+        Err(ref error) => {
+            error!("Could not find transaction {:?} (reason: {})", tx_hash, error);
+            process::exit(1);
+        }
+    }
+}
+
+/// Opens the index file at the given path, which is (re)built first if it is
+/// missing or does not cover the current blk files.
+fn open_hash_index(
+    path_to_blk_files: &str,
+    path_to_index: &str,
+    options: ReadOptions,
+) -> io::Result<HashIndex> {
+    if Path::new(path_to_index).exists() {
+        match HashIndex::open(path_to_index) {
+            Ok(hash_index) => {
+                if hash_index.is_up_to_date(path_to_blk_files, options)? {
+                    return Ok(hash_index);
+                }
+                info!("Index {} is out of date", path_to_index);
+            }
+            // E.g. an index written by an older version.
+            Err(ref error) if error.kind() == io::ErrorKind::InvalidData => {
+                info!("Index {} cannot be used (reason: {})", path_to_index, error);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    info!("Build index of {} at {}", path_to_blk_files, path_to_index);
+    let hash_index = HashIndex::build(path_to_blk_files, options)?;
+    hash_index.write(path_to_index)?;
+    info!(
+        "Indexed {} blocks and {} transactions",
+        hash_index.number_of_blocks(),
+        hash_index.number_of_transactions()
+    );
+    Ok(hash_index)
+}
+
+/// Logs the given block for `--full`, unless records are printed with
+/// `--format`.
+fn print_block(block: &Block, printer: Option<&RecordPrinter>) {
+    match printer {
+        Some(printer) => printer
+            .print_block(block)
+            .unwrap_or_else(|error| exit_on_print_error(&error)),
+        None => debug!("{:#?}", FullBlock(block)),
+    }
+}
+
+fn finish(printer: Option<&RecordPrinter>) {
+    if let Some(printer) = printer {
+        printer
+            .finish()
+            .unwrap_or_else(|error| exit_on_print_error(&error));
+    }
+}
+
+/// Exits once stdout can no longer be written to, e.g. because the records are
+/// piped into `head`.
+fn exit_on_print_error(error: &io::Error) -> ! {
+    if error.kind() == io::ErrorKind::BrokenPipe {
+        process::exit(0);
+    }
+    error!("Could not print records (reason: {})", error);
+    process::exit(1);
+}
+
+/// Prints the transactions whose hex strings are read from stdin, one per line.
+///
+/// Returns whether all lines have been decoded.
+fn decode_transactions(options: ReadOptions, printer: Option<&RecordPrinter>) -> bool {
+    let mut number_of_failed_lines = 0;
+    let stdin = io::stdin();
+    for (line_index, line) in stdin.lock().lines().enumerate() {
+        // TODO Return error instead of panicking.
+        let line = line.unwrap();
+        if line.trim().is_empty() {
+            continue;
+        }
+        match Transaction::from_hex(&line, options) {
+            Ok(ref transaction) => match printer {
+                Some(printer) => printer
+                    .print_transaction(transaction, None)
+                    .unwrap_or_else(|error| exit_on_print_error(&error)),
+                None => println!("{:#?}", FullTransaction(transaction)),
+            },
+            Err(ref error) => {
+                error!(
+                    "Could not decode transaction in line {} (reason: {})",
+                    line_index + 1,
+                    error
+                );
+                number_of_failed_lines += 1;
+            }
+        }
+    }
+
+    if number_of_failed_lines > 0 {
+        error!("Could not decode {} lines", number_of_failed_lines);
+    }
+    number_of_failed_lines == 0
+}
+
+/// Formats a block for `--full`, showing scripts in ASM notation and witness
+/// items as hex instead of byte arrays.
+struct FullBlock<'a>(&'a Block);
+
+struct FullTransaction<'a>(&'a Transaction);
+
+struct FullInput<'a>(&'a Input);
+
+struct FullOutput<'a>(&'a Output);
+
+impl<'a> fmt::Debug for FullBlock<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let block = self.0;
+        let transactions: Vec<_> = block.transactions.iter().map(FullTransaction).collect();
+        f.debug_struct("Block")
+            .field("hash", &block.hash)
+            .field("version", &block.version)
+            .field("previous_block_hash", &block.previous_block_hash)
+            .field("merkle_root", &block.merkle_root)
+            .field("creation_time", &block.creation_time)
+            .field("bits", &block.bits)
+            .field("nonce", &block.nonce)
+            .field("transactions", &transactions)
+            .field("index_in_blk_file", &block.index_in_blk_file)
+            .field("offset_in_blk_file", &block.offset_in_blk_file)
+            .field("size_in_bytes", &block.size_in_bytes)
+            .field("stripped_size_in_bytes", &block.stripped_size_in_bytes())
+            .field("weight", &block.weight())
+            .finish()
+    }
+}
+
+impl<'a> fmt::Debug for FullTransaction<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let transaction = self.0;
+        let inputs: Vec<_> = transaction.inputs.iter().map(FullInput).collect();
+        let outputs: Vec<_> = transaction.outputs.iter().map(FullOutput).collect();
+        let script_witnesses: Vec<Vec<String>> = transaction
+            .script_witnesses
+            .iter()
+            .map(|witness| witness.items.iter().map(|item| HEXLOWER.encode(item)).collect())
+            .collect();
+        f.debug_struct("Transaction")
+            .field("tx_hash", &transaction.tx_hash)
+            .field("witness_hash", &transaction.witness_hash)
+            .field("version", &transaction.version)
+            .field("lock_time", &transaction.lock_time)
+            .field("inputs", &inputs)
+            .field("outputs", &outputs)
+            .field("script_witnesses", &script_witnesses)
+            .field("size_in_bytes", &transaction.size_in_bytes)
+            .field(
+                "stripped_size_in_bytes",
+                &transaction.stripped_size_in_bytes,
+            )
+            .field("weight", &transaction.weight)
+            .field("virtual_size", &transaction.virtual_size())
+            .finish()
+    }
+}
+
+impl<'a> fmt::Debug for FullInput<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let input = self.0;
+        f.debug_struct("Input")
+            .field("sequence_number", &input.sequence_number)
+            .field("previous_tx_hash", &input.previous_tx_hash)
+            .field("previous_tx_output_index", &input.previous_tx_output_index)
+            .field("script", &input.script_asm())
+            .finish()
+    }
+}
+
+impl<'a> fmt::Debug for FullOutput<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let output = self.0;
+        f.debug_struct("Output")
+            .field("index", &output.index)
+            .field("value", &output.value)
+            .field("address", &output.address)
+            .field("address_derivation", &output.address_derivation)
+            .field("script", &output.script_asm())
+            .field("script_type", &output.script_type)
+            .field("multisig", &output.multisig)
+            .finish()
+    }
+}
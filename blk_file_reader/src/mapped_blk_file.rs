@@ -0,0 +1,148 @@
+use byteorder::{ByteOrder, LittleEndian};
+use domain::BlockRef;
+use memmap::Mmap;
+use parse_error::{shift_offset, ParseError};
+use read_options::ReadOptions;
+use read_ref::read_block_ref;
+use std::fs::File;
+use std::io::{self, Read};
+use xor::{read_xor_key_for_file, xor};
+
+/// A memory-mapped blk file.
+///
+/// Blocks are read directly from the mapped file, with their scripts and
+/// witnesses borrowing from the map instead of being copied. This makes it the
+/// preferred way of scanning large numbers of blk files.
+///
+/// Obfuscated blk files (see `read_xor_key`) cannot be read from the map
+/// directly and are de-obfuscated into memory instead.
+pub struct MappedBlkFile {
+    data: BlkFileData,
+    options: ReadOptions,
+}
+
+enum BlkFileData {
+    /// Empty blk files cannot be mapped.
+    Empty,
+    Mapped(Mmap),
+    Deobfuscated(Box<[u8]>),
+}
+
+impl MappedBlkFile {
+    /// Maps the blk file at the given path into memory.
+    ///
+    /// The blk file must not be modified while it is mapped. Bitcoin Core only
+    /// appends to the latest blk file, which is why callers should not map the
+    /// blk file that is currently being written to.
+    pub fn open(path_to_blk_file: &str, options: ReadOptions) -> io::Result<MappedBlkFile> {
+        let xor_key = read_xor_key_for_file(path_to_blk_file)?;
+        let mut file = File::open(path_to_blk_file)?;
+        let data = if file.metadata()?.len() == 0 {
+            BlkFileData::Empty
+        } else if let Some(ref xor_key) = xor_key {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+            xor(&mut bytes, xor_key, 0);
+            BlkFileData::Deobfuscated(bytes.into_boxed_slice())
+        } else {
+            BlkFileData::Mapped(unsafe { Mmap::map(&file)? })
+        };
+        Ok(MappedBlkFile { data, options })
+    }
+
+    /// The options the blocks of this blk file are read with.
+    pub fn options(&self) -> ReadOptions {
+        self.options
+    }
+
+    /// Returns an iterator over the blocks within this blk file.
+    pub fn blocks<'a>(&'a self) -> BlockRefs<'a> {
+        BlockRefs {
+            bytes: match self.data {
+                BlkFileData::Empty => &[],
+                BlkFileData::Mapped(ref mmap) => &mmap[..],
+                BlkFileData::Deobfuscated(ref bytes) => &bytes[..],
+            },
+            options: self.options,
+            position: 0,
+            index_in_blk_file: 0,
+        }
+    }
+}
+
+/// Allows for iterating over the blocks within a `MappedBlkFile`.
+pub struct BlockRefs<'a> {
+    bytes: &'a [u8],
+    options: ReadOptions,
+    position: usize,
+    index_in_blk_file: usize,
+}
+
+impl<'a> BlockRefs<'a> {
+    /// Continues at the next magic number after the current position, at
+    /// which a block could not be parsed because of the given cause.
+    fn resync(&mut self, cause: ParseError) -> Option<io::Result<BlockRef<'a>>> {
+        let mut magic_number = [0u8; 4];
+        LittleEndian::write_u32(&mut magic_number, self.options.network.magic_number());
+
+        let offset = self.position;
+        let remaining_bytes = &self.bytes[offset..];
+        let next_offset = remaining_bytes
+            .windows(magic_number.len())
+            .skip(1)
+            .position(|candidate| candidate == magic_number)
+            .map(|i| offset + 1 + i);
+
+        let end_offset = match next_offset {
+            Some(next_offset) => next_offset,
+            None if remaining_bytes.iter().all(|&byte| byte == 0) => {
+                self.position = self.bytes.len();
+                return None;
+            }
+            None => self.bytes.len(),
+        };
+        self.position = end_offset;
+
+        Some(Err(ParseError::Skipped {
+            offset: offset as u64,
+            length: (end_offset - offset) as u64,
+            cause: Box::new(cause),
+        }
+        .into()))
+    }
+}
+
+impl<'a> Iterator for BlockRefs<'a> {
+    type Item = io::Result<BlockRef<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining_bytes = &self.bytes[self.position..];
+        match read_block_ref(
+            remaining_bytes,
+            self.index_in_blk_file,
+            self.position as u64,
+            self.options.network,
+        ) {
+            Ok((block, size_in_blk_file)) => {
+                self.position += size_in_blk_file;
+                self.index_in_blk_file += 1;
+                Some(Ok(block))
+            }
+            Err(error) => {
+                let error = shift_offset(error, self.position as u64);
+                if self.options.resync {
+                    if let Some(cause) = ParseError::from_io_error(&error).cloned() {
+                        return self.resync(cause);
+                    }
+                }
+                // Do not attempt to read any further blocks.
+                self.position = self.bytes.len();
+                if error.kind() == io::ErrorKind::UnexpectedEof {
+                    None
+                } else {
+                    Some(Err(error))
+                }
+            }
+        }
+    }
+}
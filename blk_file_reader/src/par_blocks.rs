@@ -0,0 +1,81 @@
+use domain::Block;
+use parse_error::ParseError;
+use rayon::iter::plumbing::UnindexedConsumer;
+use rayon::prelude::*;
+use read_options::ReadOptions;
+use std::io;
+use util::read_blocks_with_options;
+
+/// Allows for iterating over the blocks of multiple blk files in parallel.
+///
+/// Every item consists of the index of the blk file that contains the block,
+/// e.g. `1208` for `blk01208.dat`, and the block itself. Blocks are yielded in
+/// no particular order.
+///
+/// Each blk file is read sequentially by a single worker that holds at most one
+/// block at a time, so memory usage is bounded by the number of threads of the
+/// rayon thread pool the iterator is consumed in. To limit the parallelism,
+/// consume the iterator within `rayon::ThreadPool::install`.
+///
+/// Reading a blk file stops at its first erroneous block, except for byte
+/// ranges that have been skipped because `ReadOptions::resync` is set.
+pub struct ParBlocks {
+    blk_files: Vec<(usize, String)>,
+    options: ReadOptions,
+}
+
+impl ParBlocks {
+    /// Creates a parallel iterator over the given blk files, each given by its
+    /// index and path.
+    pub fn new(blk_files: Vec<(usize, String)>, options: ReadOptions) -> ParBlocks {
+        ParBlocks { blk_files, options }
+    }
+}
+
+impl ParallelIterator for ParBlocks {
+    type Item = (usize, io::Result<Block>);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let options = self.options;
+        self.blk_files
+            .into_par_iter()
+            .flat_map_iter(move |(blk_file_index, blk_file_path)| {
+                read_blk_file(blk_file_index, &blk_file_path, options)
+            })
+            .drive_unindexed(consumer)
+    }
+}
+
+/// Sequentially reads the blocks of the given blk file, up to and including the
+/// first error that is not a skipped byte range.
+fn read_blk_file(
+    blk_file_index: usize,
+    blk_file_path: &str,
+    options: ReadOptions,
+) -> impl Iterator<Item = (usize, io::Result<Block>)> {
+    let (blocks, open_error) = match read_blocks_with_options(blk_file_path, options) {
+        Ok(blocks) => (Some(blocks), None),
+        Err(error) => (None, Some(Err(error))),
+    };
+
+    open_error
+        .into_iter()
+        .chain(blocks.into_iter().flatten().scan(false, |failed, block| {
+            if *failed {
+                None
+            } else {
+                *failed = match block {
+                    Ok(_) => false,
+                    Err(ref error) => match ParseError::from_io_error(error) {
+                        Some(&ParseError::Skipped { .. }) => false,
+                        _ => true,
+                    },
+                };
+                Some(block)
+            }
+        }))
+        .map(move |block| (blk_file_index, block))
+}
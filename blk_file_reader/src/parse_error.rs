@@ -0,0 +1,272 @@
+use domain::Network;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// The maximum size of a serialized block, as enforced by Bitcoin Core.
+pub(crate) const MAX_BLOCK_SIZE: u32 = 4_000_000;
+
+/// The size of a block header, which every block starts with.
+pub(crate) const BLOCK_HEADER_SIZE: u32 = 80;
+
+/// Describes why the data of a blk file could not be parsed.
+///
+/// Parse errors are reported wrapped in an `io::Error`, see `from_io_error`.
+/// Every variant carries the offset within the blk file at which the problem
+/// has been encountered. Errors of blocks that have not been read from a blk
+/// file directly carry offsets relative to the start of the block instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The record at `offset` does not start with the magic number of the
+    /// network.
+    ///
+    /// A magic number of 0 usually marks the zero-padded end of a blk file,
+    /// which is why it is reported as `ErrorKind::UnexpectedEof`.
+    InvalidMagicNumber {
+        offset: u64,
+        magic_number: u32,
+        network: Network,
+    },
+    /// The size of the block at `offset` exceeds the maximum block size or
+    /// is too small to hold a block header.
+    InvalidBlockSize { offset: u64, size: u32 },
+    /// The blk file ends before all `size` bytes of the block at `offset`
+    /// could be read, e.g. because the block has only been written partially.
+    ///
+    /// Reported as `ErrorKind::UnexpectedEof`.
+    TruncatedBlock { offset: u64, size: u32 },
+    /// The transaction at `offset` extends beyond the end of its block.
+    TruncatedTransaction { offset: u64 },
+    /// The variable-length integer at `offset` is not canonically encoded or
+    /// exceeds the number of bytes remaining in its block.
+    InvalidVarInt { offset: u64 },
+    /// The `length` bytes at `offset` follow a block or transaction that has
+    /// been parsed from bytes that should contain nothing else, see
+    /// `Transaction::from_consensus_bytes`.
+    TrailingData { offset: u64, length: u64 },
+    /// The `length` bytes at `offset` have been skipped while resynchronizing
+    /// to the next magic number because of the given cause, see
+    /// `ReadOptions::resync`.
+    Skipped {
+        offset: u64,
+        length: u64,
+        cause: Box<ParseError>,
+    },
+}
+
+impl ParseError {
+    /// Returns the `ParseError` that is wrapped in the given `io::Error`, if
+    /// any.
+    pub fn from_io_error(error: &io::Error) -> Option<&ParseError> {
+        error
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<ParseError>())
+    }
+
+    /// The offset at which the problem has been encountered.
+    pub fn offset(&self) -> u64 {
+        match *self {
+            ParseError::InvalidMagicNumber { offset, .. }
+            | ParseError::InvalidBlockSize { offset, .. }
+            | ParseError::TruncatedBlock { offset, .. }
+            | ParseError::TruncatedTransaction { offset }
+            | ParseError::InvalidVarInt { offset }
+            | ParseError::TrailingData { offset, .. }
+            | ParseError::Skipped { offset, .. } => offset,
+        }
+    }
+
+    /// The kind of the `io::Error` this error is reported as.
+    pub fn kind(&self) -> io::ErrorKind {
+        match *self {
+            ParseError::InvalidMagicNumber {
+                magic_number: 0, ..
+            }
+            | ParseError::TruncatedBlock { .. } => io::ErrorKind::UnexpectedEof,
+            _ => io::ErrorKind::InvalidData,
+        }
+    }
+
+    fn shifted_by(self, base_offset: u64) -> ParseError {
+        match self {
+            ParseError::InvalidMagicNumber {
+                offset,
+                magic_number,
+                network,
+            } => ParseError::InvalidMagicNumber {
+                offset: base_offset + offset,
+                magic_number,
+                network,
+            },
+            ParseError::InvalidBlockSize { offset, size } => ParseError::InvalidBlockSize {
+                offset: base_offset + offset,
+                size,
+            },
+            ParseError::TruncatedBlock { offset, size } => ParseError::TruncatedBlock {
+                offset: base_offset + offset,
+                size,
+            },
+            ParseError::TruncatedTransaction { offset } => ParseError::TruncatedTransaction {
+                offset: base_offset + offset,
+            },
+            ParseError::InvalidVarInt { offset } => ParseError::InvalidVarInt {
+                offset: base_offset + offset,
+            },
+            ParseError::TrailingData { offset, length } => ParseError::TrailingData {
+                offset: base_offset + offset,
+                length,
+            },
+            ParseError::Skipped {
+                offset,
+                length,
+                cause,
+            } => ParseError::Skipped {
+                offset: base_offset + offset,
+                length,
+                cause: Box::new(cause.shifted_by(base_offset)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::InvalidMagicNumber {
+                offset,
+                magic_number,
+                network,
+            } => write!(
+                f,
+                "encountered invalid magic number {:X} for {} at offset {}",
+                magic_number, network, offset
+            ),
+            ParseError::InvalidBlockSize { offset, size } => {
+                write!(f, "invalid block size {} at offset {}", size, offset)
+            }
+            ParseError::TruncatedBlock { offset, size } => write!(
+                f,
+                "blk file ends within block of size {} at offset {}",
+                size, offset
+            ),
+            ParseError::TruncatedTransaction { offset } => {
+                write!(f, "transaction at offset {} exceeds its block", offset)
+            }
+            ParseError::InvalidVarInt { offset } => {
+                write!(f, "invalid variable-length integer at offset {}", offset)
+            }
+            ParseError::TrailingData { offset, length } => {
+                write!(
+                    f,
+                    "unexpected {} trailing bytes at offset {}",
+                    length, offset
+                )
+            }
+            ParseError::Skipped {
+                offset,
+                length,
+                ref cause,
+            } => write!(
+                f,
+                "skipped {} bytes at offset {} ({})",
+                length, offset, cause
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<ParseError> for io::Error {
+    fn from(error: ParseError) -> io::Error {
+        io::Error::new(error.kind(), error)
+    }
+}
+
+/// Whether the given error reports a byte range that has been skipped because
+/// `ReadOptions::resync` is set.
+pub fn is_skipped_range(error: &io::Error) -> bool {
+    match ParseError::from_io_error(error) {
+        Some(&ParseError::Skipped { .. }) => true,
+        _ => false,
+    }
+}
+
+/// Adds the given offset to the offsets of the `ParseError` wrapped in the
+/// given error, if any.
+///
+/// Used to turn offsets relative to a block into offsets within its blk file.
+pub(crate) fn shift_offset(error: io::Error, base_offset: u64) -> io::Error {
+    match ParseError::from_io_error(&error).cloned() {
+        Some(parse_error) => parse_error.shifted_by(base_offset).into(),
+        None => error,
+    }
+}
+
+/// Reports a plain `ErrorKind::UnexpectedEof` while reading the transaction at
+/// `offset` as `ParseError::TruncatedTransaction`.
+pub(crate) fn truncated_transaction_error(error: io::Error, offset: u64) -> io::Error {
+    if error.kind() == io::ErrorKind::UnexpectedEof && ParseError::from_io_error(&error).is_none() {
+        ParseError::TruncatedTransaction { offset }.into()
+    } else {
+        error
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn parse_error_can_be_recovered_from_io_error() {
+        // given
+        let parse_error = ParseError::InvalidVarInt { offset: 42 };
+
+        // when
+        let error = io::Error::from(parse_error.clone());
+
+        // then
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(ParseError::from_io_error(&error), Some(&parse_error));
+    }
+
+    #[test]
+    fn shift_offset_rebases_parse_errors_only() {
+        // given
+        let parse_error = io::Error::from(ParseError::TruncatedTransaction { offset: 10 });
+        let other_error = io::Error::new(io::ErrorKind::Other, "other");
+
+        // when
+        let parse_error = shift_offset(parse_error, 100);
+        let other_error = shift_offset(other_error, 100);
+
+        // then
+        assert_eq!(
+            ParseError::from_io_error(&parse_error).map(ParseError::offset),
+            Some(110)
+        );
+        assert_eq!(ParseError::from_io_error(&other_error), None);
+    }
+
+    #[test]
+    fn truncated_transaction_error_wraps_plain_eof_only() {
+        // given
+        let eof = io::Error::new(io::ErrorKind::UnexpectedEof, "eof");
+        let invalid_var_int = io::Error::from(ParseError::InvalidVarInt { offset: 7 });
+
+        // when
+        let eof = truncated_transaction_error(eof, 3);
+        let invalid_var_int = truncated_transaction_error(invalid_var_int, 3);
+
+        // then
+        assert_eq!(
+            ParseError::from_io_error(&eof),
+            Some(&ParseError::TruncatedTransaction { offset: 3 })
+        );
+        assert_eq!(
+            ParseError::from_io_error(&invalid_var_int),
+            Some(&ParseError::InvalidVarInt { offset: 7 })
+        );
+    }
+}
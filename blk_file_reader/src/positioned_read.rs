@@ -0,0 +1,71 @@
+use byteorder::{ByteOrder, LittleEndian};
+use domain::Network;
+use parse_error::shift_offset;
+use read::validate_magic_number;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use xor::{read_xor_key_for_file, xor};
+
+/// The length of the magic number and size that precede every record of a blk
+/// or rev file.
+const RECORD_HEADER_LENGTH: u64 = 8;
+
+/// Returns the offset of the record whose data starts at `data_offset`, e.g. as
+/// recorded by Bitcoin Core's block index, within the file at the given path.
+///
+/// Fails if `data_offset` leaves no room for the magic number and size of the
+/// record, i.e. if the block index entry is corrupt.
+pub(crate) fn record_offset(path_to_file: &str, data_offset: u32) -> io::Result<u64> {
+    u64::from(data_offset)
+        .checked_sub(RECORD_HEADER_LENGTH)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid data offset {} in {}", data_offset, path_to_file),
+            )
+        })
+}
+
+/// Reads the record of the blk or rev file at the given path which starts at
+/// `record_offset`, see `record_offset`.
+///
+/// Returns the de-obfuscated record, starting with its magic number and size,
+/// including `trailing_length` bytes that follow the data of the record. The
+/// size is checked with `validate_size` before reading the data, so a corrupt
+/// size does not cause an excessive allocation.
+pub(crate) fn read_record(
+    path_to_file: &str,
+    record_offset: u64,
+    network: Network,
+    trailing_length: usize,
+    validate_size: fn(u32) -> io::Result<()>,
+) -> io::Result<Box<[u8]>> {
+    let xor_key = read_xor_key_for_file(path_to_file)?;
+    let mut file = File::open(path_to_file)?;
+    file.seek(SeekFrom::Start(record_offset))?;
+
+    let mut header = [0u8; RECORD_HEADER_LENGTH as usize];
+    file.read_exact(&mut header)?;
+    if let Some(ref xor_key) = xor_key {
+        xor(&mut header, xor_key, record_offset);
+    }
+    validate_magic_number(LittleEndian::read_u32(&header[..4]), network)
+        .map_err(|error| shift_offset(error, record_offset))?;
+    let size = LittleEndian::read_u32(&header[4..]);
+    validate_size(size).map_err(|error| shift_offset(error, record_offset))?;
+    // TODO Fix possibly truncating cast.
+    let size = size as usize;
+
+    let mut record = vec![0u8; header.len() + size + trailing_length];
+    record[..header.len()].copy_from_slice(&header);
+    file.read_exact(&mut record[header.len()..])?;
+    if let Some(ref xor_key) = xor_key {
+        xor(
+            &mut record[header.len()..],
+            xor_key,
+            record_offset + RECORD_HEADER_LENGTH,
+        );
+    }
+
+    Ok(record.into_boxed_slice())
+}
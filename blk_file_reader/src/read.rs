@@ -1,28 +1,51 @@
+use base58check;
+use bech32;
 use byteorder::{LittleEndian, ReadBytesExt};
 use crypto::digest::Digest;
+use crypto::ripemd160::Ripemd160;
 use crypto::sha2::Sha256;
+use data_encoding::HEXLOWER_PERMISSIVE;
 use domain::*;
 use keys;
+use parse_error::{
+    shift_offset, truncated_transaction_error, ParseError, BLOCK_HEADER_SIZE, MAX_BLOCK_SIZE,
+};
+use read_options::ReadOptions;
 use script;
 use script::Script;
 use std::io::{Cursor, Error, ErrorKind, Read, Result};
-
-/// The magic number which identifies blocks in bitcoin's main network.
-const MAIN_NET_MAGIC_NUMBER: u32 = 0xD9B4BEF9;
+use validation::validate_block;
 
 /// This trait allows for reading `Block`s from blk files.
 pub trait ReadBlock: Read {
     /// Read a `Block` from the underlying blk file.
     ///
+    /// Malformed blocks are reported as `ParseError`s whose offsets are
+    /// relative to the start of the block, i.e. its magic number.
+    ///
+    /// The offset of the block within its blk file is unknown and recorded as
+    /// 0, use `read_block_at` if it is known.
+    ///
     /// For more information on the structure of blocks within a blk file refer
     /// to the [according wiki entry](https://en.bitcoin.it/wiki/Block).
-    fn read_block(&mut self, index_in_blk_file: usize) -> Result<Block>;
+    fn read_block(&mut self, index_in_blk_file: usize, options: ReadOptions) -> Result<Block> {
+        self.read_block_at(index_in_blk_file, 0, options)
+    }
+
+    /// Read a `Block` from the underlying blk file, whose magic number is
+    /// located at `offset_in_blk_file`, see `read_block`.
+    fn read_block_at(
+        &mut self,
+        index_in_blk_file: usize,
+        offset_in_blk_file: u64,
+        options: ReadOptions,
+    ) -> Result<Block>;
 }
 
 /// Internal helper trait.
-trait ReadBlockInternals: Read {
+pub(crate) trait ReadBlockInternals: Read {
     /// Read `Transactions` of a `Block` from the underlying blk file.
-    fn read_transactions(&mut self) -> Result<Box<[Transaction]>>;
+    fn read_transactions(&mut self, options: ReadOptions) -> Result<Box<[Transaction]>>;
 
     /// Read a `Transaction` from the underlying blk file.
     ///
@@ -30,7 +53,7 @@ trait ReadBlockInternals: Read {
     /// refer to the [according wiki entry](https://en.bitcoin.it/wiki/Transaction)
     /// For SegWit specifics refer to [BIP 141](https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki)
     /// and [BIP 144](https://github.com/bitcoin/bips/blob/master/bip-0144.mediawiki).
-    fn read_transaction(&mut self) -> Result<Transaction>;
+    fn read_transaction(&mut self, options: ReadOptions) -> Result<Transaction>;
 
     /// Read `Inputs` of a `Transaction` from the underlying blk file.
     fn read_inputs(&mut self, input_count: u32) -> Result<Box<[Input]>>;
@@ -42,13 +65,13 @@ trait ReadBlockInternals: Read {
     fn read_input(&mut self) -> Result<Input>;
 
     /// Read `Outputs` of a `Transaction` from the underlying blk file.
-    fn read_outputs(&mut self, output_count: u32) -> Result<Box<[Output]>>;
+    fn read_outputs(&mut self, output_count: u32, options: ReadOptions) -> Result<Box<[Output]>>;
 
     /// Read an `Output` from the underlying blk file.
     ///
     /// For more information on the structure of transactions within a blk file
     /// refer to the [according wiki entry](https://en.bitcoin.it/wiki/Transaction#General_format_.28inside_a_block.29_of_each_output_of_a_transaction_-_Txout).
-    fn read_output(&mut self, index: u32) -> Result<Output>;
+    fn read_output(&mut self, index: u32, options: ReadOptions) -> Result<Output>;
 
     /// Read a 256-bit `Hash` from the underyling blk file.
     fn read_hash(&mut self) -> Result<Hash>;
@@ -59,83 +82,214 @@ trait ReadBlockInternals: Read {
     /// blk file refer to the [according wiki entry](https://en.bitcoin.it/wiki/Protocol_documentation#Variable_length_integer).
     fn read_var_int(&mut self) -> Result<u64>;
 
+    /// Read a variable-length integer that counts the items or bytes that
+    /// follow it within the underlying blk file.
+    fn read_length(&mut self) -> Result<u64>;
+
     /// Read a bitcoin script from the underlying blk file.
     fn read_script(&mut self) -> Result<Box<[u8]>>;
 }
 
 /// Implement `ReadBlock` for all types that implement `Read`.
 impl<R: Read + ?Sized> ReadBlock for R {
-    fn read_block(&mut self, index_in_blk_file: usize) -> Result<Block> {
+    fn read_block_at(
+        &mut self,
+        index_in_blk_file: usize,
+        offset_in_blk_file: u64,
+        options: ReadOptions,
+    ) -> Result<Block> {
         let magic_number = self.read_u32::<LittleEndian>()?;
-        validate_magic_number(magic_number)?;
+        validate_magic_number(magic_number, options.network)?;
 
         let block_size = self.read_u32::<LittleEndian>()?;
+        validate_block_size(block_size)?;
 
         // TODO Fix possibly truncating cast.
         let mut block_content = Box::<[u8]>::from(vec![0u8; block_size as usize]);
-        self.read_exact(&mut block_content)?;
+        self.read_exact(&mut block_content).map_err(|error| {
+            if error.kind() == ErrorKind::UnexpectedEof {
+                ParseError::TruncatedBlock {
+                    offset: 0,
+                    size: block_size,
+                }
+                .into()
+            } else {
+                error
+            }
+        })?;
 
+        // Offsets within the block content are relative to the end of the
+        // magic number and size.
         let mut block_content_reader = Cursor::new(block_content);
+        read_block_content(
+            &mut block_content_reader,
+            index_in_blk_file,
+            offset_in_blk_file,
+            options,
+        )
+        .map_err(|error| shift_offset(error, 8))
+    }
+}
 
-        let mut block_header = [0u8; 80];
-        block_content_reader.read_exact(&mut block_header)?;
+/// Read a `Block` from the given block content, i.e. the consensus encoding of
+/// the block without magic number and size.
+///
+/// Bytes that follow the transactions of the block are left unread.
+fn read_block_content<B: AsRef<[u8]>>(
+    block_content_reader: &mut Cursor<B>,
+    index_in_blk_file: usize,
+    offset_in_blk_file: u64,
+    options: ReadOptions,
+) -> Result<Block> {
+    let size_in_bytes = block_content_reader.get_ref().as_ref().len() as u32;
+
+    let mut block_header = [0u8; 80];
+    block_content_reader.read_exact(&mut block_header)?;
+
+    let hash = calculate_hash(&block_header)?;
+
+    let mut block_header_reader = Cursor::new(&block_header[..]);
+    let version = block_header_reader.read_u32::<LittleEndian>()?;
+    let previous_block_hash = block_header_reader.read_hash()?;
+    let merkle_root = block_header_reader.read_hash()?;
+    let creation_time = block_header_reader.read_u32::<LittleEndian>()?;
+    let bits = block_header_reader.read_u32::<LittleEndian>()?;
+    let nonce = block_header_reader.read_u32::<LittleEndian>()?;
+
+    let transactions = block_content_reader.read_transactions(options)?;
+
+    let block = Block {
+        creation_time,
+        hash,
+        merkle_root,
+        bits,
+        nonce,
+        previous_block_hash,
+        version,
+        transactions,
+        index_in_blk_file,
+        offset_in_blk_file,
+        size_in_bytes,
+    };
 
-        let hash = calculate_hash(&block_header)?;
+    if options.validate_blocks {
+        validate_block(&block).map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    }
 
-        let mut block_header_reader = Cursor::new(&block_header[..]);
-        let version = block_header_reader.read_u32::<LittleEndian>()?;
-        let previous_block_hash = block_header_reader.read_hash()?;
-        let merkle_root = block_header_reader.read_hash()?;
-        let creation_time = block_header_reader.read_u32::<LittleEndian>()?;
-        let bits = block_header_reader.read_u32::<LittleEndian>()?;
-        let nonce = block_header_reader.read_u32::<LittleEndian>()?;
+    Ok(block)
+}
 
-        let transactions = block_content_reader.read_transactions()?;
+/// Reports the bytes that follow the position of `reader` as
+/// `ParseError::TrailingData`.
+fn ensure_no_trailing_data(reader: &Cursor<&[u8]>) -> Result<()> {
+    let length = reader.get_ref().len() as u64 - reader.position();
+    if length == 0 {
+        Ok(())
+    } else {
+        Err(ParseError::TrailingData {
+            offset: reader.position(),
+            length,
+        }
+        .into())
+    }
+}
 
-        let block = Block {
-            creation_time,
-            hash,
-            merkle_root,
-            bits,
-            nonce,
-            previous_block_hash,
-            version,
-            transactions,
-            index_in_blk_file,
-        };
+/// Decodes the given hex string, ignoring surrounding whitespace.
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    HEXLOWER_PERMISSIVE
+        .decode(hex.trim().as_bytes())
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))
+}
 
+impl Block {
+    /// Parses a block from its consensus encoding, see `to_consensus_bytes`.
+    ///
+    /// As the block has not been read from a blk file, its index and offset
+    /// within the blk file are 0 and the offsets of `ParseError`s are relative
+    /// to the start of `bytes`.
+    pub fn from_consensus_bytes(bytes: &[u8], options: ReadOptions) -> Result<Block> {
+        let mut reader = Cursor::new(bytes);
+        let block = read_block_content(&mut reader, 0, 0, options)?;
+        ensure_no_trailing_data(&reader)?;
         Ok(block)
     }
+
+    /// Parses a block from the hex string of its consensus encoding, see
+    /// `from_consensus_bytes`.
+    pub fn from_hex(hex: &str, options: ReadOptions) -> Result<Block> {
+        Block::from_consensus_bytes(&decode_hex(hex)?, options)
+    }
+}
+
+impl Transaction {
+    /// Parses a transaction from its consensus encoding, see
+    /// `to_consensus_bytes`.
+    ///
+    /// The offsets of `ParseError`s are relative to the start of `bytes`.
+    pub fn from_consensus_bytes(bytes: &[u8], options: ReadOptions) -> Result<Transaction> {
+        let mut reader = Cursor::new(bytes);
+        let transaction = reader
+            .read_transaction(options)
+            .map_err(|error| truncated_transaction_error(error, 0))?;
+        ensure_no_trailing_data(&reader)?;
+        Ok(transaction)
+    }
+
+    /// Parses a transaction from the hex string of its consensus encoding,
+    /// e.g. as returned by the `getrawtransaction` RPC of Bitcoin Core.
+    pub fn from_hex(hex: &str, options: ReadOptions) -> Result<Transaction> {
+        Transaction::from_consensus_bytes(&decode_hex(hex)?, options)
+    }
+}
+
+/// Checks that the given magic number belongs to the given network.
+///
+/// The offset of the reported `ParseError` is 0 and has to be shifted to the
+/// position of the magic number by the caller.
+pub(crate) fn validate_magic_number(magic_number: u32, network: Network) -> Result<()> {
+    if magic_number == network.magic_number() {
+        Ok(())
+    } else {
+        Err(ParseError::InvalidMagicNumber {
+            offset: 0,
+            magic_number,
+            network,
+        }
+        .into())
+    }
 }
 
-fn validate_magic_number(magic_number: u32) -> Result<()> {
-    match magic_number {
-        MAIN_NET_MAGIC_NUMBER => Ok(()),
-        0 => Err(Error::new(
-            ErrorKind::UnexpectedEof,
-            "encountered magic number 0",
-        )),
-        _ => Err(Error::new(
-            ErrorKind::Other,
-            format!("encountered invalid magic number {:X}", magic_number),
-        )),
+/// Checks that a block of the given size can hold a header without exceeding
+/// the maximum block size.
+pub(crate) fn validate_block_size(block_size: u32) -> Result<()> {
+    if block_size < BLOCK_HEADER_SIZE || block_size > MAX_BLOCK_SIZE {
+        Err(ParseError::InvalidBlockSize {
+            offset: 0,
+            size: block_size,
+        }
+        .into())
+    } else {
+        Ok(())
     }
 }
 
 /// Implement `ReadBlockInternals` for `Cursor`s over byte arrays.
 impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
-    fn read_transactions(&mut self) -> Result<Box<[Transaction]>> {
-        let transaction_count = self.read_var_int()?;
+    fn read_transactions(&mut self, options: ReadOptions) -> Result<Box<[Transaction]>> {
+        let transaction_count = self.read_length()?;
         // TODO Fix possibly truncating cast.
         let mut transactions = Vec::with_capacity(transaction_count as usize);
         for _ in 0..transaction_count {
-            let transaction = self.read_transaction()?;
+            let tx_start_position = self.position();
+            let transaction = self
+                .read_transaction(options)
+                .map_err(|error| truncated_transaction_error(error, tx_start_position))?;
             transactions.push(transaction);
         }
         Ok(transactions.into_boxed_slice())
     }
 
-    fn read_transaction(&mut self) -> Result<Transaction> {
+    fn read_transaction(&mut self, options: ReadOptions) -> Result<Transaction> {
         let tx_start_position = self.position();
 
         let version = self.read_u32::<LittleEndian>()?;
@@ -150,14 +304,14 @@ impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
 
         // Read transaction inputs.
         let input_start_position = self.position();
-        let input_count = self.read_var_int()? as u32;
+        let input_count = self.read_length()? as u32;
         let inputs = self.read_inputs(input_count)?;
         let input_end_position = self.position();
 
         // Read transaction outputs.
         let output_start_position = self.position();
-        let output_count = self.read_var_int()? as u32;
-        let outputs = self.read_outputs(output_count)?;
+        let output_count = self.read_length()? as u32;
+        let outputs = self.read_outputs(output_count, options)?;
         let output_end_position = self.position();
 
         // Read segregated witnesses.
@@ -165,11 +319,11 @@ impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
         let witness_start_position = self.position();
         if is_segwit_tx && flag == 0x01 {
             for _ in 0..input_count {
-                let item_count = self.read_var_int()?;
+                let item_count = self.read_length()?;
                 // TODO Fix possibly truncating cast.
                 let mut items = Vec::with_capacity(item_count as usize);
                 for _ in 0..item_count {
-                    let item_length = self.read_var_int()?;
+                    let item_length = self.read_length()?;
                     // TODO Fix possibly truncating cast.
                     let mut item = vec![0u8; item_length as usize];
                     self.read_exact(&mut item)?;
@@ -231,7 +385,7 @@ impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
             witness_hash.clone()
         };
 
-        let weight = if is_segwit_tx {
+        let (stripped_size, weight) = if is_segwit_tx {
             let size_of_version = 4u32;
             let size_of_marker_byte = 1u32;
             let size_of_flag_byte = 1u32;
@@ -243,11 +397,14 @@ impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
             let size_of_outputs = (output_end_position - output_start_position) as u32;
             let size_of_lock_time = 4u32;
 
-            (size_of_marker_byte + size_of_flag_byte + size_of_witness)
-                + (size_of_version + size_of_inputs + size_of_outputs + size_of_lock_time) * 4
+            let stripped_size =
+                size_of_version + size_of_inputs + size_of_outputs + size_of_lock_time;
+            let weight =
+                (size_of_marker_byte + size_of_flag_byte + size_of_witness) + stripped_size * 4;
+            (stripped_size, weight)
         } else {
             // TODO Fix possibly truncating cast.
-            tx_length as u32 * 4
+            (tx_length as u32, tx_length as u32 * 4)
         };
 
         let transaction = Transaction {
@@ -260,6 +417,7 @@ impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
             script_witnesses: script_witnesses.into_boxed_slice(),
             // TODO Fix possibly truncating cast.
             size_in_bytes: tx_length as u32,
+            stripped_size_in_bytes: stripped_size,
             weight,
         };
 
@@ -292,28 +450,20 @@ impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
         Ok(input)
     }
 
-    fn read_outputs(&mut self, output_count: u32) -> Result<Box<[Output]>> {
+    fn read_outputs(&mut self, output_count: u32, options: ReadOptions) -> Result<Box<[Output]>> {
         // TODO Fix possibly truncating cast.
         let mut outputs = Vec::with_capacity(output_count as usize);
         for output_index in 0..output_count {
-            let output = self.read_output(output_index)?;
+            let output = self.read_output(output_index, options)?;
             outputs.push(output);
         }
         Ok(outputs.into_boxed_slice())
     }
 
-    fn read_output(&mut self, index: u32) -> Result<Output> {
+    fn read_output(&mut self, index: u32, options: ReadOptions) -> Result<Output> {
         let value = self.read_u64::<LittleEndian>()?;
         let script = self.read_script()?;
-        // TODO Avoid copy.
-        let address = read_output_address(script.to_vec());
-
-        let output = Output {
-            index,
-            value,
-            address,
-            script,
-        };
+        let output = output_from_script(index, value, script, options);
 
         Ok(output)
     }
@@ -326,6 +476,7 @@ impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
     }
 
     fn read_var_int(&mut self) -> Result<u64> {
+        let var_int_position = self.position();
         let mut control_byte: [u8; 1] = [0];
         self.read_exact(&mut control_byte)?;
 
@@ -339,11 +490,45 @@ impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
             self.read_u64::<LittleEndian>()?
         };
 
+        // Bitcoin Core rejects variable-length integers that could have been
+        // encoded with fewer bytes.
+        let is_canonical = match control_byte[0] {
+            0xFD => var_int >= 0xFD,
+            0xFE => var_int > 0xFFFF,
+            0xFF => var_int > 0xFFFF_FFFF,
+            _ => true,
+        };
+        if !is_canonical {
+            return Err(ParseError::InvalidVarInt {
+                offset: var_int_position,
+            }
+            .into());
+        }
+
         Ok(var_int)
     }
 
+    fn read_length(&mut self) -> Result<u64> {
+        let length_position = self.position();
+        let length = self.read_var_int()?;
+
+        // Every counted item occupies at least one byte, which is why larger
+        // lengths can only stem from corrupt data. Rejecting them also avoids
+        // allocating for them.
+        let remaining_length =
+            (self.get_ref().as_ref().len() as u64).saturating_sub(self.position());
+        if length > remaining_length {
+            return Err(ParseError::InvalidVarInt {
+                offset: length_position,
+            }
+            .into());
+        }
+
+        Ok(length)
+    }
+
     fn read_script(&mut self) -> Result<Box<[u8]>> {
-        let script_length = self.read_var_int()?;
+        let script_length = self.read_length()?;
         // TODO Fix possibly truncating cast.
         let mut script = Box::<[u8]>::from(vec![0u8; script_length as usize]);
         self.read_exact(&mut script)?;
@@ -351,27 +536,109 @@ impl<B: AsRef<[u8]>> ReadBlockInternals for Cursor<B> {
     }
 }
 
+/// Creates an `Output` from the given script, determining its script type,
+/// address and multisig participants.
+pub(crate) fn output_from_script(
+    index: u32,
+    value: u64,
+    script: Box<[u8]>,
+    options: ReadOptions,
+) -> Output {
+    let script_type = ScriptType::from_script(&script);
+    let (address, address_derivation) = if script_type == ScriptType::P2PK {
+        if options.derive_p2pk_addresses {
+            let address = derive_p2pk_address(&script, options.network);
+            (Some(address), Some(AddressDerivation::FromPublicKey))
+        } else {
+            (None, None)
+        }
+    } else {
+        // TODO Avoid copy.
+        match read_output_address(script.to_vec(), options.network) {
+            Some(address) => (Some(address), Some(AddressDerivation::Direct)),
+            None => (None, None),
+        }
+    };
+    let multisig = if script_type == ScriptType::Multisig {
+        read_output_multisig(&script, options.network)
+    } else {
+        None
+    };
+
+    Output {
+        index,
+        value,
+        address,
+        address_derivation,
+        script,
+        script_type,
+        multisig,
+    }
+}
+
 /// Read the receiver address that is contained in the given output script.
 ///
 /// Returns the address on success or `None` if the structure of the script does
 /// not conform to any known "standard" script-type.
-fn read_output_address(script: Vec<u8>) -> Option<Address> {
+fn read_output_address(script: Vec<u8>, network: Network) -> Option<Address> {
+    if let Some((witness_version, witness_program)) = read_witness_program(&script) {
+        return Some(witness_program_address(
+            witness_version,
+            witness_program,
+            network,
+        ));
+    }
+
     let script = Script::from(script);
     // TODO Return a meaningful error instead of panicking.
     let script_addresses = script.extract_destinations().expect("Invalid addresses");
 
     if script_addresses.len() == 1 {
         let script_address = &script_addresses[0];
-        let base58check = base58check_encode(script_address);
+        let encoded = base58check_encode(script_address, network);
         let hash = script_address.hash.clone().take();
-        let address = Address { hash, base58check };
+        let payload = match script_address.kind {
+            keys::Type::P2PKH => AddressPayload::PubKeyHash(hash),
+            keys::Type::P2SH => AddressPayload::ScriptHash(hash),
+        };
+        let address = Address {
+            payload,
+            encoded,
+            encoding: AddressEncoding::Base58Check,
+        };
         Some(address)
     } else {
         None
     }
 }
 
-fn calculate_hash(bytes: &[u8]) -> Result<Hash> {
+/// Derive the P2PKH address of the public key of the given P2PK script.
+fn derive_p2pk_address(script: &[u8], network: Network) -> Address {
+    let public_key = &script[1..script.len() - 1];
+    p2pkh_address(public_key, network)
+}
+
+/// Read the threshold and the participants of the given bare multisig script.
+///
+/// Each participant is represented by its public key as well as by the P2PKH
+/// address of that key.
+fn read_output_multisig(script: &[u8], network: Network) -> Option<Multisig> {
+    let (required_signatures, public_keys) = read_multisig(script)?;
+    let participants = public_keys
+        .into_iter()
+        .map(|public_key| MultisigParticipant {
+            public_key: Box::from(public_key),
+            address: p2pkh_address(public_key, network),
+        })
+        .collect::<Vec<_>>();
+
+    Some(Multisig {
+        required_signatures,
+        participants: participants.into_boxed_slice(),
+    })
+}
+
+pub(crate) fn calculate_hash(bytes: &[u8]) -> Result<Hash> {
     let mut sha = Sha256::new();
 
     // first hash round
@@ -390,18 +657,127 @@ fn calculate_hash(bytes: &[u8]) -> Result<Hash> {
     Ok(Hash(second_hash))
 }
 
-/// Transforms the given `ScriptAddress` to a `keys::Address`.
-///
-/// This is done by leveraging the `Format` trait implementation of
-/// `keys::Address` to retrieve it as base58check-encoded string.
-fn base58check_encode(address: &script::ScriptAddress) -> String {
-    let address = keys::Address {
-        kind: address.kind,
-        network: keys::Network::Mainnet,
-        hash: address.hash.clone(),
+/// Returns the segwit address of the given witness program.
+pub(crate) fn witness_program_address(
+    witness_version: u8,
+    witness_program: &[u8],
+    network: Network,
+) -> Address {
+    let encoded =
+        bech32::encode_segwit_address(network.bech32_hrp(), witness_version, witness_program);
+    let encoding = if witness_version == 0 {
+        AddressEncoding::Bech32
+    } else {
+        AddressEncoding::Bech32m
+    };
+    Address {
+        payload: AddressPayload::WitnessProgram {
+            version: witness_version,
+            program: WitnessProgram::from_bytes(witness_program),
+        },
+        encoded,
+        encoding,
+    }
+}
+
+/// Returns the P2SH address of the given redeem script.
+pub(crate) fn p2sh_address(redeem_script: &[u8], network: Network) -> Address {
+    let hash = calculate_hash160(redeem_script);
+    Address {
+        payload: AddressPayload::ScriptHash(hash),
+        encoded: base58check::encode(network.script_hash_address_version(), &hash),
+        encoding: AddressEncoding::Base58Check,
+    }
+}
+
+/// Returns the P2PKH address of the given public key.
+pub(crate) fn p2pkh_address(public_key: &[u8], network: Network) -> Address {
+    let hash = calculate_hash160(public_key);
+    Address {
+        payload: AddressPayload::PubKeyHash(hash),
+        encoded: base58check::encode(network.pub_key_hash_address_version(), &hash),
+        encoding: AddressEncoding::Base58Check,
+    }
+}
+
+/// Calculates the RIPEMD160 of the SHA256 of `bytes`, as used for public key
+/// hashes.
+pub(crate) fn calculate_hash160(bytes: &[u8]) -> [u8; 20] {
+    let mut sha = Sha256::new();
+    sha.input(bytes);
+    let mut sha_hash = [0u8; 32];
+    sha.result(&mut sha_hash);
+
+    let mut ripemd = Ripemd160::new();
+    ripemd.input(&sha_hash);
+    let mut hash = [0u8; 20];
+    ripemd.result(&mut hash);
+    hash
+}
+
+/// Encodes the given `ScriptAddress` as base58check string, using the address
+/// version bytes of the given `network`.
+fn base58check_encode(address: &script::ScriptAddress, network: Network) -> String {
+    let version = match address.kind {
+        keys::Type::P2PKH => network.pub_key_hash_address_version(),
+        keys::Type::P2SH => network.script_hash_address_version(),
     };
-    let base58check = format!("{}", address);
-    base58check
+    base58check::encode(version, &*address.hash)
+}
+
+#[cfg(test)]
+pub(crate) mod read_block_tests {
+    use super::*;
+    use byteorder::WriteBytesExt;
+    use data_encoding::HEXLOWER;
+
+    /// A SegWit transaction that spends the genesis coinbase output to a
+    /// P2WPKH output.
+    pub(crate) const SEG_WIT_TRANSACTION: &'static str = "020000000001014a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b0000000000feffffff0100e1f50500000000160014751e76e8199196d454941c45d1b3a323f1433bd602023045210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f8179800000000";
+
+    /// A block consisting of the genesis coinbase transaction and
+    /// `SEG_WIT_TRANSACTION`.
+    pub(crate) fn block_bytes() -> Vec<u8> {
+        let header = vec![0x01u8; 80];
+        let coinbase_transaction = HEXLOWER
+            .decode(b"01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000")
+            .unwrap();
+        let seg_wit_transaction = HEXLOWER.decode(SEG_WIT_TRANSACTION.as_bytes()).unwrap();
+
+        let mut block_content = header;
+        block_content.push(2);
+        block_content.extend_from_slice(&coinbase_transaction);
+        block_content.extend_from_slice(&seg_wit_transaction);
+
+        let mut bytes = vec![0xF9, 0xBE, 0xB4, 0xD9];
+        bytes
+            .write_u32::<LittleEndian>(block_content.len() as u32)
+            .unwrap();
+        bytes.extend_from_slice(&block_content);
+        bytes
+    }
+
+    #[test]
+    fn records_stripped_sizes_and_weights() {
+        // given
+        let bytes = block_bytes();
+        let options = ReadOptions::new(Network::Mainnet);
+
+        // when
+        let block = Cursor::new(&bytes).read_block(0, options).unwrap();
+
+        // then
+        let seg_wit_transaction = &block.transactions[1];
+        assert_eq!(seg_wit_transaction.size_in_bytes, 122);
+        assert_eq!(seg_wit_transaction.stripped_size_in_bytes, 82);
+        assert_eq!(seg_wit_transaction.weight, 368);
+        assert_eq!(seg_wit_transaction.virtual_size(), 92);
+        assert_eq!(block.transactions[0].stripped_size_in_bytes, 204);
+        assert_eq!(block.size_in_bytes, 407);
+        assert_eq!(block.stripped_size_in_bytes(), 367);
+        assert_eq!(block.weight(), 1508);
+        assert_eq!(block.number_of_segwit_transactions(), 1);
+    }
 }
 
 #[cfg(test)]
@@ -435,3 +811,323 @@ mod read_script_tests {
         assert_eq!(expected_script, actual_script);
     }
 }
+
+#[cfg(test)]
+mod read_output_address_tests {
+    use super::*;
+    use data_encoding::HEXLOWER;
+
+    #[test]
+    fn when_script_is_p2wpkh_then_returns_bech32_address() {
+        // given
+        let script = HEXLOWER
+            .decode(b"0014751e76e8199196d454941c45d1b3a323f1433bd6")
+            .unwrap();
+
+        // when
+        let address = read_output_address(script, Network::Mainnet).unwrap();
+
+        // then
+        assert_eq!(
+            address.encoded,
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(address.encoding, AddressEncoding::Bech32);
+        assert_eq!(address.payload.witness_version(), Some(0));
+        assert_eq!(address.payload.as_bytes().len(), 20);
+    }
+
+    #[test]
+    fn when_script_is_p2wsh_then_returns_bech32_address() {
+        // given
+        let script = HEXLOWER
+            .decode(b"00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262")
+            .unwrap();
+
+        // when
+        let address = read_output_address(script, Network::Regtest).unwrap();
+
+        // then
+        assert!(address.encoded.starts_with("bcrt1q"));
+        assert_eq!(address.encoding, AddressEncoding::Bech32);
+        match address.payload {
+            AddressPayload::WitnessProgram {
+                version: 0,
+                program: WitnessProgram::Program32(_),
+            } => (),
+            payload => panic!("unexpected payload {:?}", payload),
+        }
+    }
+
+    #[test]
+    fn when_script_is_p2tr_then_returns_bech32m_address() {
+        // given
+        let script = HEXLOWER
+            .decode(b"512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+            .unwrap();
+
+        // when
+        let address = read_output_address(script, Network::Mainnet).unwrap();
+
+        // then
+        assert_eq!(
+            address.encoded,
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0"
+        );
+        assert_eq!(address.encoding, AddressEncoding::Bech32m);
+        assert_eq!(address.payload.witness_version(), Some(1));
+        assert_eq!(address.payload.as_bytes().len(), 32);
+    }
+
+    #[test]
+    fn when_version_0_witness_program_has_invalid_length_then_is_not_a_witness_program() {
+        // given
+        let script = HEXLOWER
+            .decode(b"0010751e76e8199196d454941c45d1b3")
+            .unwrap();
+
+        // when
+        let witness_program = read_witness_program(&script);
+
+        // then
+        assert!(witness_program.is_none());
+    }
+}
+
+#[cfg(test)]
+mod read_output_tests {
+    use super::*;
+    use data_encoding::HEXLOWER;
+
+    fn p2pk_output_bytes() -> Vec<u8> {
+        HEXLOWER
+            .decode(b"00f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac")
+            .unwrap()
+    }
+
+    #[test]
+    fn when_p2pk_address_derivation_is_enabled_then_returns_p2pkh_address() {
+        // given
+        let mut cursor = Cursor::new(p2pk_output_bytes());
+        let options = ReadOptions::new(Network::Mainnet);
+
+        // when
+        let output = cursor.read_output(0, options).unwrap();
+
+        // then
+        assert_eq!(
+            output.address.unwrap().encoded,
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+        );
+        assert_eq!(
+            output.address_derivation,
+            Some(AddressDerivation::FromPublicKey)
+        );
+    }
+
+    #[test]
+    fn when_p2pk_address_derivation_is_disabled_then_returns_no_address() {
+        // given
+        let mut cursor = Cursor::new(p2pk_output_bytes());
+        let options = ReadOptions {
+            derive_p2pk_addresses: false,
+            ..ReadOptions::new(Network::Mainnet)
+        };
+
+        // when
+        let output = cursor.read_output(0, options).unwrap();
+
+        // then
+        assert_eq!(output.value, 5000000000);
+        assert!(output.address.is_none());
+        assert!(output.address_derivation.is_none());
+    }
+
+    #[test]
+    fn when_script_is_p2pkh_then_address_is_direct() {
+        // given
+        let bytes = HEXLOWER
+            .decode(b"00f2052a010000001976a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac")
+            .unwrap();
+        let mut cursor = Cursor::new(bytes);
+
+        // when
+        let output = cursor.read_output(0, ReadOptions::default()).unwrap();
+
+        // then
+        assert_eq!(
+            output.address.unwrap().encoded,
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+        );
+        assert_eq!(output.address_derivation, Some(AddressDerivation::Direct));
+    }
+}
+
+#[cfg(test)]
+mod read_output_multisig_tests {
+    use super::*;
+    use data_encoding::HEXLOWER;
+
+    #[test]
+    fn when_script_is_bare_multisig_then_returns_all_participants() {
+        // given
+        let script = HEXLOWER
+            .decode(b"514104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5f21022afc20bf379bc96a2f4e9e63ffceb8652b2b6a097f63fbee6ecec2a49a48010e52ae")
+            .unwrap();
+
+        // when
+        let multisig = read_output_multisig(&script, Network::Mainnet).unwrap();
+
+        // then
+        assert_eq!(multisig.required_signatures, 1);
+        assert_eq!(multisig.participants.len(), 2);
+        assert_eq!(multisig.participants[0].public_key.len(), 65);
+        assert_eq!(
+            multisig.participants[0].address.encoded,
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+        );
+        assert_eq!(multisig.participants[1].public_key.len(), 33);
+        assert_eq!(
+            multisig.participants[1].address.encoding,
+            AddressEncoding::Base58Check
+        );
+    }
+
+    #[test]
+    fn when_script_is_not_bare_multisig_then_returns_none() {
+        // given
+        let script = HEXLOWER
+            .decode(b"76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac")
+            .unwrap();
+
+        // when
+        let multisig = read_output_multisig(&script, Network::Mainnet);
+
+        // then
+        assert!(multisig.is_none());
+    }
+}
+
+#[cfg(test)]
+mod validate_magic_number_tests {
+    use super::*;
+
+    #[test]
+    fn when_magic_number_matches_network_then_succeeds() {
+        // given
+        let magic_number = 0xDAB5BFFA;
+
+        // when
+        let result = validate_magic_number(magic_number, Network::Regtest);
+
+        // then
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn when_magic_number_belongs_to_other_network_then_fails() {
+        // given
+        let magic_number = 0xD9B4BEF9;
+
+        // when
+        let result = validate_magic_number(magic_number, Network::Testnet);
+
+        // then
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            ParseError::from_io_error(&error),
+            Some(&ParseError::InvalidMagicNumber {
+                offset: 0,
+                magic_number,
+                network: Network::Testnet,
+            })
+        );
+    }
+
+    #[test]
+    fn when_magic_number_is_zero_then_reports_eof() {
+        // when
+        let result = validate_magic_number(0, Network::Mainnet);
+
+        // then
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
+
+#[cfg(test)]
+mod from_consensus_bytes_tests {
+    use super::read_block_tests::SEG_WIT_TRANSACTION;
+    use super::*;
+
+    #[test]
+    fn when_hex_is_valid_then_parses_transaction() {
+        // given
+        let hex = format!("  {}\n", SEG_WIT_TRANSACTION.to_uppercase());
+        let options = ReadOptions::new(Network::Mainnet);
+
+        // when
+        let transaction = Transaction::from_hex(&hex, options).unwrap();
+
+        // then
+        assert_eq!(transaction.inputs.len(), 1);
+        assert_eq!(transaction.script_witnesses[0].items.len(), 2);
+        assert_eq!(
+            transaction.outputs[0].address.as_ref().unwrap().encoded,
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(transaction.to_hex(), SEG_WIT_TRANSACTION);
+    }
+
+    #[test]
+    fn when_bytes_follow_transaction_then_reports_trailing_data() {
+        // given
+        let mut bytes = HEXLOWER_PERMISSIVE
+            .decode(SEG_WIT_TRANSACTION.as_bytes())
+            .unwrap();
+        bytes.push(0x00);
+        let options = ReadOptions::new(Network::Mainnet);
+
+        // when
+        let error = Transaction::from_consensus_bytes(&bytes, options).unwrap_err();
+
+        // then
+        assert_eq!(
+            ParseError::from_io_error(&error),
+            Some(&ParseError::TrailingData {
+                offset: 122,
+                length: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn when_transaction_is_truncated_then_reports_truncated_transaction() {
+        // given
+        let bytes = HEXLOWER_PERMISSIVE
+            .decode(SEG_WIT_TRANSACTION.as_bytes())
+            .unwrap();
+        let options = ReadOptions::new(Network::Mainnet);
+
+        // when
+        let error = Transaction::from_consensus_bytes(&bytes[..53], options).unwrap_err();
+
+        // then
+        assert_eq!(
+            ParseError::from_io_error(&error),
+            Some(&ParseError::TruncatedTransaction { offset: 0 })
+        );
+    }
+
+    #[test]
+    fn when_hex_is_invalid_then_fails() {
+        // given
+        let options = ReadOptions::new(Network::Mainnet);
+
+        // when
+        let result = Transaction::from_hex("0x0200", options);
+
+        // then
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
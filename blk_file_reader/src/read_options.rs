@@ -0,0 +1,47 @@
+use domain::Network;
+
+/// Options that control how blocks are read from blk files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// The network the blk files belong to.
+    pub network: Network,
+
+    /// Whether to derive the P2PKH address of the public key of P2PK outputs.
+    ///
+    /// P2PK outputs do not contain an address, but block explorers commonly
+    /// show them under the P2PKH address of their public key. Derived
+    /// addresses are marked with `AddressDerivation::FromPublicKey`.
+    pub derive_p2pk_addresses: bool,
+
+    /// Whether to check the proof of work, merkle root and witness commitment
+    /// of every block that is read, see `validate_block`.
+    ///
+    /// Blocks that fail validation are reported as `ErrorKind::InvalidData`.
+    /// This applies to owned `Block`s only, not to `BlockRef`s.
+    pub validate_blocks: bool,
+
+    /// Whether to skip over corrupt data by scanning forward to the next
+    /// magic number instead of giving up on the rest of the blk file.
+    ///
+    /// Every skipped byte range is reported as `ParseError::Skipped`, after
+    /// which the iteration continues. Zero-padding at the end of a blk file
+    /// is skipped silently.
+    pub resync: bool,
+}
+
+impl ReadOptions {
+    pub fn new(network: Network) -> ReadOptions {
+        ReadOptions {
+            network,
+            derive_p2pk_addresses: true,
+            validate_blocks: false,
+            resync: false,
+        }
+    }
+}
+
+impl Default for ReadOptions {
+    fn default() -> ReadOptions {
+        ReadOptions::new(Network::default())
+    }
+}
@@ -0,0 +1,296 @@
+use byteorder::{LittleEndian, ReadBytesExt};
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use domain::*;
+use parse_error::{shift_offset, truncated_transaction_error, ParseError};
+use read::{calculate_hash, validate_block_size, validate_magic_number, ReadBlockInternals};
+use std::io::{Cursor, Error, ErrorKind, Result};
+
+/// Read a `BlockRef` from the start of the given blk file data, which starts at
+/// `offset_in_blk_file` within its blk file.
+///
+/// Returns the block together with the number of bytes it occupies within the
+/// blk file, including its magic number and size. Malformed blocks are reported
+/// as `ParseError`s whose offsets are relative to the start of `bytes`.
+pub(crate) fn read_block_ref<'a>(
+    bytes: &'a [u8],
+    index_in_blk_file: usize,
+    offset_in_blk_file: u64,
+    network: Network,
+) -> Result<(BlockRef<'a>, usize)> {
+    let mut cursor = Cursor::new(bytes);
+    let magic_number = cursor.read_u32::<LittleEndian>()?;
+    validate_magic_number(magic_number, network)?;
+
+    let block_size = cursor.read_u32::<LittleEndian>()?;
+    validate_block_size(block_size)?;
+    let block_content = read_slice(&mut cursor, u64::from(block_size)).map_err(|_| {
+        Error::from(ParseError::TruncatedBlock {
+            offset: 0,
+            size: block_size,
+        })
+    })?;
+    let mut block_content_reader = Cursor::new(block_content);
+
+    let block_header = read_slice(&mut block_content_reader, 80)?;
+    let hash = calculate_hash(block_header)?;
+
+    let mut block_header_reader = Cursor::new(block_header);
+    let version = block_header_reader.read_u32::<LittleEndian>()?;
+    let previous_block_hash = block_header_reader.read_hash()?;
+    let merkle_root = block_header_reader.read_hash()?;
+    let creation_time = block_header_reader.read_u32::<LittleEndian>()?;
+    let bits = block_header_reader.read_u32::<LittleEndian>()?;
+    let nonce = block_header_reader.read_u32::<LittleEndian>()?;
+
+    // Offsets within the block content are relative to the end of the magic
+    // number and size.
+    let transactions =
+        read_transaction_refs(&mut block_content_reader).map_err(|error| shift_offset(error, 8))?;
+
+    let block = BlockRef {
+        hash,
+        version,
+        previous_block_hash,
+        merkle_root,
+        creation_time,
+        bits,
+        nonce,
+        transactions,
+        index_in_blk_file,
+        offset_in_blk_file,
+        size_in_bytes: block_size,
+    };
+
+    Ok((block, 8 + block_size as usize))
+}
+
+fn read_transaction_refs<'a>(cursor: &mut Cursor<&'a [u8]>) -> Result<Box<[TransactionRef<'a>]>> {
+    let transaction_count = cursor.read_length()?;
+    // TODO Fix possibly truncating cast.
+    let mut transactions = Vec::with_capacity(transaction_count as usize);
+    for _ in 0..transaction_count {
+        let tx_start_position = cursor.position();
+        let transaction = read_transaction_ref(cursor)
+            .map_err(|error| truncated_transaction_error(error, tx_start_position))?;
+        transactions.push(transaction);
+    }
+    Ok(transactions.into_boxed_slice())
+}
+
+/// Read a `TransactionRef`, see `ReadBlockInternals::read_transaction` for its
+/// owned counterpart.
+fn read_transaction_ref<'a>(cursor: &mut Cursor<&'a [u8]>) -> Result<TransactionRef<'a>> {
+    let bytes: &'a [u8] = *cursor.get_ref();
+    let tx_start_position = cursor.position() as usize;
+
+    let version = cursor.read_u32::<LittleEndian>()?;
+    let marker_position = cursor.position();
+    let marker = cursor.read_u8()?;
+    let flag = cursor.read_u8()?;
+
+    let is_segwit_tx = marker == 0x00 && flag >= 0x01;
+    if !is_segwit_tx {
+        cursor.set_position(marker_position);
+    }
+
+    // Read transaction inputs.
+    let input_start_position = cursor.position() as usize;
+    let input_count = cursor.read_length()?;
+    // TODO Fix possibly truncating cast.
+    let mut inputs = Vec::with_capacity(input_count as usize);
+    for _ in 0..input_count {
+        inputs.push(read_input_ref(cursor)?);
+    }
+
+    // Read transaction outputs.
+    let output_count = cursor.read_length()?;
+    // TODO Fix possibly truncating cast.
+    let mut outputs = Vec::with_capacity(output_count as usize);
+    for index in 0..output_count {
+        // TODO Fix possibly truncating cast.
+        outputs.push(read_output_ref(cursor, index as u32)?);
+    }
+    let output_end_position = cursor.position() as usize;
+
+    // Read segregated witnesses.
+    let mut script_witnesses = vec![];
+    if is_segwit_tx && flag == 0x01 {
+        script_witnesses.reserve(inputs.len());
+        for _ in 0..input_count {
+            let item_count = cursor.read_length()?;
+            let items_start_position = cursor.position() as usize;
+            for _ in 0..item_count {
+                let item_length = cursor.read_length()?;
+                read_slice(cursor, item_length)?;
+            }
+            let items = &bytes[items_start_position..cursor.position() as usize];
+            // TODO Fix possibly truncating cast.
+            script_witnesses.push(ScriptWitnessRef::new(item_count as usize, items));
+        }
+    }
+    let witness_size = cursor.position() as usize - output_end_position;
+
+    let lock_time_start_position = cursor.position() as usize;
+    let lock_time = cursor.read_u32::<LittleEndian>()?;
+    let tx_end_position = cursor.position() as usize;
+    let tx_length = tx_end_position - tx_start_position;
+
+    let witness_hash = calculate_hash(&bytes[tx_start_position..tx_end_position])?;
+
+    let (tx_hash, stripped_size) = if is_segwit_tx {
+        // The transaction hash excludes marker, flag and witnesses, which
+        // separate the version from the inputs and the outputs from the lock
+        // time.
+        let tx_hash = calculate_hash_of_parts(&[
+            &bytes[tx_start_position..tx_start_position + 4],
+            &bytes[input_start_position..output_end_position],
+            &bytes[lock_time_start_position..tx_end_position],
+        ]);
+        (tx_hash, tx_length - 2 - witness_size)
+    } else {
+        (witness_hash.clone(), tx_length)
+    };
+
+    let transaction = TransactionRef {
+        tx_hash,
+        witness_hash,
+        version,
+        lock_time,
+        inputs: inputs.into_boxed_slice(),
+        outputs: outputs.into_boxed_slice(),
+        script_witnesses: script_witnesses.into_boxed_slice(),
+        // TODO Fix possibly truncating cast.
+        size_in_bytes: tx_length as u32,
+        // TODO Fix possibly truncating cast.
+        stripped_size_in_bytes: stripped_size as u32,
+        // TODO Fix possibly truncating cast.
+        weight: (stripped_size * 3 + tx_length) as u32,
+    };
+
+    Ok(transaction)
+}
+
+fn read_input_ref<'a>(cursor: &mut Cursor<&'a [u8]>) -> Result<InputRef<'a>> {
+    let previous_tx_hash = cursor.read_hash()?;
+    let previous_tx_output_index = cursor.read_u32::<LittleEndian>()?;
+    let script_length = cursor.read_length()?;
+    let script = read_slice(cursor, script_length)?;
+    let sequence_number = cursor.read_u32::<LittleEndian>()?;
+
+    let input = InputRef {
+        sequence_number,
+        previous_tx_hash,
+        previous_tx_output_index,
+        script,
+    };
+
+    Ok(input)
+}
+
+fn read_output_ref<'a>(cursor: &mut Cursor<&'a [u8]>, index: u32) -> Result<OutputRef<'a>> {
+    let value = cursor.read_u64::<LittleEndian>()?;
+    let script_length = cursor.read_length()?;
+    let script = read_slice(cursor, script_length)?;
+
+    let output = OutputRef {
+        index,
+        value,
+        script,
+    };
+
+    Ok(output)
+}
+
+/// Read the next `length` bytes as slice of the data underlying the cursor.
+fn read_slice<'a>(cursor: &mut Cursor<&'a [u8]>, length: u64) -> Result<&'a [u8]> {
+    let bytes: &'a [u8] = *cursor.get_ref();
+    let start = cursor.position() as usize;
+    let end = start.saturating_add(length as usize);
+    if end > bytes.len() {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "failed to fill whole buffer",
+        ));
+    }
+    cursor.set_position(end as u64);
+    Ok(&bytes[start..end])
+}
+
+/// Calculates the double-SHA256 of the concatenation of the given parts.
+pub(crate) fn calculate_hash_of_parts(parts: &[&[u8]]) -> Hash {
+    let mut sha = Sha256::new();
+
+    // first hash round
+    for part in parts {
+        sha.input(part);
+    }
+    let mut first_hash = [0u8; 32];
+    sha.result(&mut first_hash);
+
+    // second hash round
+    sha.reset();
+    sha.input(&first_hash);
+    let mut second_hash = [0u8; 32];
+    sha.result(&mut second_hash);
+
+    second_hash.reverse();
+
+    Hash(second_hash)
+}
+
+#[cfg(test)]
+mod read_block_ref_tests {
+    use super::*;
+    use read::read_block_tests::block_bytes;
+    use read::ReadBlock;
+    use read_options::ReadOptions;
+
+    #[test]
+    fn block_ref_equals_block_read_by_copying() {
+        // given
+        let bytes = block_bytes();
+        let options = ReadOptions::new(Network::Mainnet);
+
+        // when
+        let (block_ref, size_in_blk_file) = read_block_ref(&bytes, 0, 0, Network::Mainnet).unwrap();
+
+        // then
+        let block = Cursor::new(&bytes).read_block(0, options).unwrap();
+        assert_eq!(size_in_blk_file, bytes.len());
+        assert_eq!(block_ref.to_block(options), block);
+    }
+
+    #[test]
+    fn scripts_and_witnesses_borrow_from_blk_file_data() {
+        // given
+        let bytes = block_bytes();
+        let bytes_range = bytes.as_ptr() as usize..bytes.as_ptr() as usize + bytes.len();
+
+        // when
+        let (block_ref, _) = read_block_ref(&bytes, 0, 0, Network::Mainnet).unwrap();
+
+        // then
+        let transaction = &block_ref.transactions[1];
+        let output_script = transaction.outputs[0].script;
+        assert!(bytes_range.contains(&(output_script.as_ptr() as usize)));
+        let witness_items: Vec<&[u8]> = transaction.script_witnesses[0].items().collect();
+        assert_eq!(witness_items.len(), 2);
+        assert_eq!(witness_items[0], &[0x30, 0x45][..]);
+        assert_eq!(witness_items[1].len(), 33);
+        assert!(bytes_range.contains(&(witness_items[1].as_ptr() as usize)));
+    }
+
+    #[test]
+    fn when_block_is_truncated_then_reports_eof() {
+        // given
+        let mut bytes = block_bytes();
+        bytes.truncate(100);
+
+        // when
+        let result = read_block_ref(&bytes, 0, 0, Network::Mainnet);
+
+        // then
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,885 @@
+use blk_file_reader::{
+    Block, BlockLocation, ChainStats, Distribution, Hash, Input, InputAddress, Network, Output,
+    ScriptType, ScriptWitness, SpendType, Transaction, TransactionLocation,
+};
+use csv;
+use data_encoding::HEXLOWER;
+use serde::Serialize;
+use serde_json;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// The machine-readable formats records can be printed in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// A single JSON array of records.
+    Json,
+    /// One JSON record per line.
+    Ndjson,
+    /// Comma-separated values with a header row, which only allows for flat
+    /// records.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Format, String> {
+        match format {
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format {}", format)),
+        }
+    }
+}
+
+/// The kind of records blocks are printed as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Records {
+    Blocks,
+    Transactions,
+    Outputs,
+}
+
+impl FromStr for Records {
+    type Err = String;
+
+    fn from_str(records: &str) -> Result<Records, String> {
+        match records {
+            "blocks" => Ok(Records::Blocks),
+            "transactions" => Ok(Records::Transactions),
+            "outputs" => Ok(Records::Outputs),
+            _ => Err(format!("unknown records {}", records)),
+        }
+    }
+}
+
+/// Prints blocks and transactions to stdout, or another writer, as records of
+/// the given format.
+///
+/// Since CSV does not allow for nesting, blocks and transactions are printed
+/// without their transactions, inputs and outputs in that format. Printing is
+/// synchronized, so blocks can be printed from multiple threads.
+pub struct RecordPrinter<W: Write = io::Stdout> {
+    format: Format,
+    records: Records,
+    network: Network,
+    state: Mutex<PrinterState<W>>,
+}
+
+struct PrinterState<W: Write> {
+    number_of_records: usize,
+    writer: RecordWriter<W>,
+}
+
+enum RecordWriter<W: Write> {
+    Json(W),
+    Ndjson(W),
+    Csv(csv::Writer<W>),
+}
+
+impl RecordPrinter {
+    pub fn new(format: Format, records: Records, network: Network) -> RecordPrinter {
+        RecordPrinter::with_writer(format, records, network, io::stdout())
+    }
+}
+
+impl<W: Write> RecordPrinter<W> {
+    pub fn with_writer(
+        format: Format,
+        records: Records,
+        network: Network,
+        writer: W,
+    ) -> RecordPrinter<W> {
+        let writer = match format {
+            Format::Json => RecordWriter::Json(writer),
+            Format::Ndjson => RecordWriter::Ndjson(writer),
+            Format::Csv => RecordWriter::Csv(csv::Writer::from_writer(writer)),
+        };
+        RecordPrinter {
+            format,
+            records,
+            network,
+            state: Mutex::new(PrinterState {
+                number_of_records: 0,
+                writer,
+            }),
+        }
+    }
+
+    pub fn print_block(&self, block: &Block) -> io::Result<()> {
+        self.print_located_block(block, None)
+    }
+
+    /// Prints the given block, which has been found at the given location,
+    /// with the name of its blk file.
+    pub fn print_found_block(&self, block: &Block, location: &BlockLocation) -> io::Result<()> {
+        self.print_located_block(block, Some(location))
+    }
+
+    /// Prints the given transaction, or its outputs, of the block with the
+    /// given hash, if known.
+    pub fn print_transaction(
+        &self,
+        transaction: &Transaction,
+        block_hash: Option<&Hash>,
+    ) -> io::Result<()> {
+        self.print_located_transaction(transaction, block_hash, None)
+    }
+
+    /// Prints the given transaction, or its outputs, which has been found at
+    /// the given location within the block with the given hash.
+    pub fn print_found_transaction(
+        &self,
+        transaction: &Transaction,
+        block_hash: &Hash,
+        location: &TransactionLocation,
+    ) -> io::Result<()> {
+        self.print_located_transaction(transaction, Some(block_hash), Some(location))
+    }
+
+    /// Prints the given statistics of the blocks with the given name, e.g. of
+    /// a blk file, which are incomplete if not all blocks could be read.
+    pub fn print_stats(&self, name: &str, stats: &ChainStats, is_complete: bool) -> io::Result<()> {
+        match self.format {
+            Format::Csv => self.print_record(&StatsRow::new(name, stats, is_complete)),
+            _ => self.print_record(&StatsRecord::new(name, stats, is_complete)),
+        }
+    }
+
+    /// Terminates the printed records, e.g. by closing the JSON array.
+    pub fn finish(&self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let number_of_records = state.number_of_records;
+        match state.writer {
+            RecordWriter::Json(ref mut writer) => {
+                let terminator = if number_of_records == 0 {
+                    "[]\n"
+                } else {
+                    "\n]\n"
+                };
+                writer.write_all(terminator.as_bytes())?;
+                writer.flush()
+            }
+            RecordWriter::Ndjson(ref mut writer) => writer.flush(),
+            RecordWriter::Csv(ref mut writer) => writer.flush(),
+        }
+    }
+
+    fn print_located_block(
+        &self,
+        block: &Block,
+        location: Option<&BlockLocation>,
+    ) -> io::Result<()> {
+        match self.records {
+            Records::Blocks => match self.format {
+                Format::Csv => self.print_record(&BlockRow::new(block, location)),
+                _ => self.print_record(&BlockRecord::new(block, location, self.network)),
+            },
+            Records::Transactions | Records::Outputs => {
+                for (index_in_block, transaction) in block.transactions.iter().enumerate() {
+                    let transaction_location = location.map(|&location| TransactionLocation {
+                        block: location,
+                        index_in_block,
+                    });
+                    self.print_located_transaction(
+                        transaction,
+                        Some(&block.hash),
+                        transaction_location.as_ref(),
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn print_located_transaction(
+        &self,
+        transaction: &Transaction,
+        block_hash: Option<&Hash>,
+        location: Option<&TransactionLocation>,
+    ) -> io::Result<()> {
+        match (self.records, self.format) {
+            (Records::Outputs, _) => {
+                for output in transaction.outputs.iter() {
+                    self.print_record(&OutputRow::new(output, transaction, block_hash, location))?;
+                }
+                Ok(())
+            }
+            (_, Format::Csv) => {
+                self.print_record(&TransactionRow::new(transaction, block_hash, location))
+            }
+            _ => self.print_record(&TransactionRecord::new(
+                transaction,
+                block_hash,
+                location,
+                self.network,
+            )),
+        }
+    }
+
+    fn print_record<T: Serialize>(&self, record: &T) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let number_of_records = state.number_of_records;
+        match state.writer {
+            RecordWriter::Json(ref mut writer) => {
+                let separator = if number_of_records == 0 { "[\n" } else { ",\n" };
+                writer.write_all(separator.as_bytes())?;
+                serde_json::to_writer(&mut *writer, record)?;
+            }
+            RecordWriter::Ndjson(ref mut writer) => {
+                serde_json::to_writer(&mut *writer, record)?;
+                writer.write_all(b"\n")?;
+            }
+            RecordWriter::Csv(ref mut writer) => writer.serialize(record)?,
+        }
+        state.number_of_records += 1;
+        Ok(())
+    }
+}
+
+fn hex(hash: &Hash) -> String {
+    HEXLOWER.encode(&hash.0)
+}
+
+#[derive(Serialize)]
+struct BlockRecord {
+    hash: String,
+    version: u32,
+    previous_block_hash: String,
+    merkle_root: String,
+    creation_time: u32,
+    bits: u32,
+    nonce: u32,
+    bip34_height: Option<u32>,
+    coinbase_tag: Option<String>,
+    size_in_bytes: u32,
+    stripped_size_in_bytes: u32,
+    weight: u32,
+    /// The blk file the block has been found in, see `print_found_block`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blk_file_name: Option<String>,
+    index_in_blk_file: usize,
+    offset_in_blk_file: u64,
+    transactions: Vec<TransactionRecord>,
+}
+
+impl BlockRecord {
+    fn new(block: &Block, location: Option<&BlockLocation>, network: Network) -> BlockRecord {
+        BlockRecord {
+            hash: hex(&block.hash),
+            version: block.version,
+            previous_block_hash: hex(&block.previous_block_hash),
+            merkle_root: hex(&block.merkle_root),
+            creation_time: block.creation_time,
+            bits: block.bits,
+            nonce: block.nonce,
+            bip34_height: block.bip34_height(),
+            coinbase_tag: block.coinbase_tag(),
+            size_in_bytes: block.size_in_bytes,
+            stripped_size_in_bytes: block.stripped_size_in_bytes(),
+            weight: block.weight(),
+            blk_file_name: location.map(BlockLocation::blk_file_name),
+            index_in_blk_file: block.index_in_blk_file,
+            offset_in_blk_file: block.offset_in_blk_file,
+            transactions: block
+                .transactions
+                .iter()
+                .map(|transaction| TransactionRecord::new(transaction, None, None, network))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BlockRow {
+    hash: String,
+    version: u32,
+    previous_block_hash: String,
+    merkle_root: String,
+    creation_time: u32,
+    bits: u32,
+    nonce: u32,
+    bip34_height: Option<u32>,
+    coinbase_tag: Option<String>,
+    size_in_bytes: u32,
+    stripped_size_in_bytes: u32,
+    weight: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blk_file_name: Option<String>,
+    index_in_blk_file: usize,
+    offset_in_blk_file: u64,
+    number_of_transactions: usize,
+    number_of_segwit_transactions: usize,
+}
+
+impl BlockRow {
+    fn new(block: &Block, location: Option<&BlockLocation>) -> BlockRow {
+        BlockRow {
+            hash: hex(&block.hash),
+            version: block.version,
+            previous_block_hash: hex(&block.previous_block_hash),
+            merkle_root: hex(&block.merkle_root),
+            creation_time: block.creation_time,
+            bits: block.bits,
+            nonce: block.nonce,
+            bip34_height: block.bip34_height(),
+            coinbase_tag: block.coinbase_tag(),
+            size_in_bytes: block.size_in_bytes,
+            stripped_size_in_bytes: block.stripped_size_in_bytes(),
+            weight: block.weight(),
+            blk_file_name: location.map(BlockLocation::blk_file_name),
+            index_in_blk_file: block.index_in_blk_file,
+            offset_in_blk_file: block.offset_in_blk_file,
+            number_of_transactions: block.transactions.len(),
+            number_of_segwit_transactions: block.number_of_segwit_transactions(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TransactionRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_hash: Option<String>,
+    /// The blk file the transaction has been found in, see
+    /// `print_found_transaction`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blk_file_name: Option<String>,
+    /// The offset of the transaction's block within its blk file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset_in_blk_file: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index_in_block: Option<usize>,
+    tx_hash: String,
+    witness_hash: String,
+    version: u32,
+    lock_time: u32,
+    is_coinbase: bool,
+    size_in_bytes: u32,
+    stripped_size_in_bytes: u32,
+    virtual_size: u32,
+    weight: u32,
+    inputs: Vec<InputRecord>,
+    outputs: Vec<OutputRecord>,
+}
+
+impl TransactionRecord {
+    fn new(
+        transaction: &Transaction,
+        block_hash: Option<&Hash>,
+        location: Option<&TransactionLocation>,
+        network: Network,
+    ) -> TransactionRecord {
+        let spend_types = transaction.input_spend_types();
+        let addresses = transaction.input_addresses(network);
+        let inputs = transaction
+            .inputs
+            .iter()
+            .zip(spend_types.into_iter().zip(addresses))
+            .enumerate()
+            .map(|(input_index, (input, (spend_type, address)))| {
+                let witness = transaction.script_witnesses.get(input_index);
+                InputRecord::new(input, witness, spend_type, address)
+            })
+            .collect();
+        TransactionRecord {
+            block_hash: block_hash.map(hex),
+            blk_file_name: location.map(|location| location.block.blk_file_name()),
+            offset_in_blk_file: location.map(|location| location.block.offset_in_blk_file),
+            index_in_block: location.map(|location| location.index_in_block),
+            tx_hash: hex(&transaction.tx_hash),
+            witness_hash: hex(&transaction.witness_hash),
+            version: transaction.version,
+            lock_time: transaction.lock_time,
+            is_coinbase: transaction.is_coinbase(),
+            size_in_bytes: transaction.size_in_bytes,
+            stripped_size_in_bytes: transaction.stripped_size_in_bytes,
+            virtual_size: transaction.virtual_size(),
+            weight: transaction.weight,
+            inputs,
+            outputs: transaction.outputs.iter().map(OutputRecord::new).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TransactionRow {
+    block_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blk_file_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset_in_blk_file: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index_in_block: Option<usize>,
+    tx_hash: String,
+    witness_hash: String,
+    version: u32,
+    lock_time: u32,
+    is_coinbase: bool,
+    size_in_bytes: u32,
+    stripped_size_in_bytes: u32,
+    virtual_size: u32,
+    weight: u32,
+    number_of_inputs: usize,
+    number_of_outputs: usize,
+}
+
+impl TransactionRow {
+    fn new(
+        transaction: &Transaction,
+        block_hash: Option<&Hash>,
+        location: Option<&TransactionLocation>,
+    ) -> TransactionRow {
+        TransactionRow {
+            block_hash: block_hash.map(hex),
+            blk_file_name: location.map(|location| location.block.blk_file_name()),
+            offset_in_blk_file: location.map(|location| location.block.offset_in_blk_file),
+            index_in_block: location.map(|location| location.index_in_block),
+            tx_hash: hex(&transaction.tx_hash),
+            witness_hash: hex(&transaction.witness_hash),
+            version: transaction.version,
+            lock_time: transaction.lock_time,
+            is_coinbase: transaction.is_coinbase(),
+            size_in_bytes: transaction.size_in_bytes,
+            stripped_size_in_bytes: transaction.stripped_size_in_bytes,
+            virtual_size: transaction.virtual_size(),
+            weight: transaction.weight,
+            number_of_inputs: transaction.inputs.len(),
+            number_of_outputs: transaction.outputs.len(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct InputRecord {
+    previous_tx_hash: String,
+    previous_tx_output_index: u32,
+    sequence_number: u32,
+    script: String,
+    script_asm: String,
+    witness: Vec<String>,
+    spend_type: &'static str,
+    /// The address derived from the input script and witness.
+    address: Option<String>,
+}
+
+impl InputRecord {
+    fn new(
+        input: &Input,
+        witness: Option<&ScriptWitness>,
+        spend_type: SpendType,
+        address: Option<InputAddress>,
+    ) -> InputRecord {
+        InputRecord {
+            previous_tx_hash: hex(&input.previous_tx_hash),
+            previous_tx_output_index: input.previous_tx_output_index,
+            sequence_number: input.sequence_number,
+            script: HEXLOWER.encode(&input.script),
+            script_asm: input.script_asm(),
+            witness: witness
+                .map(|witness| {
+                    witness
+                        .items
+                        .iter()
+                        .map(|item| HEXLOWER.encode(item))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            spend_type: spend_type.name(),
+            address: address.map(|input_address| input_address.address.encoded),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OutputRecord {
+    index: u32,
+    value: u64,
+    script: String,
+    script_asm: String,
+    script_type: &'static str,
+    address: Option<String>,
+}
+
+impl OutputRecord {
+    fn new(output: &Output) -> OutputRecord {
+        OutputRecord {
+            index: output.index,
+            value: output.value,
+            script: HEXLOWER.encode(&output.script),
+            script_asm: output.script_asm(),
+            script_type: output.script_type.name(),
+            address: output
+                .address
+                .as_ref()
+                .map(|address| address.encoded.clone()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OutputRow {
+    block_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blk_file_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset_in_blk_file: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index_in_block: Option<usize>,
+    tx_hash: String,
+    index: u32,
+    value: u64,
+    script_type: &'static str,
+    address: Option<String>,
+    script_asm: String,
+}
+
+impl OutputRow {
+    fn new(
+        output: &Output,
+        transaction: &Transaction,
+        block_hash: Option<&Hash>,
+        location: Option<&TransactionLocation>,
+    ) -> OutputRow {
+        OutputRow {
+            block_hash: block_hash.map(hex),
+            blk_file_name: location.map(|location| location.block.blk_file_name()),
+            offset_in_blk_file: location.map(|location| location.block.offset_in_blk_file),
+            index_in_block: location.map(|location| location.index_in_block),
+            tx_hash: hex(&transaction.tx_hash),
+            index: output.index,
+            value: output.value,
+            script_type: output.script_type.name(),
+            address: output
+                .address
+                .as_ref()
+                .map(|address| address.encoded.clone()),
+            script_asm: output.script_asm(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct StatsRecord<'a> {
+    name: &'a str,
+    is_complete: bool,
+    number_of_blocks: u64,
+    number_of_transactions: u64,
+    number_of_segwit_transactions: u64,
+    segwit_share: Option<f64>,
+    number_of_inputs: u64,
+    number_of_outputs: u64,
+    number_of_op_return_outputs: u64,
+    first_creation_time: Option<u32>,
+    last_creation_time: Option<u32>,
+    block_sizes: DistributionRecord,
+    block_weights: DistributionRecord,
+    transaction_sizes: DistributionRecord,
+    transaction_weights: DistributionRecord,
+    /// The number of outputs by script type name.
+    script_types: BTreeMap<&'static str, u64>,
+}
+
+impl<'a> StatsRecord<'a> {
+    fn new(name: &'a str, stats: &ChainStats, is_complete: bool) -> StatsRecord<'a> {
+        StatsRecord {
+            name,
+            is_complete,
+            number_of_blocks: stats.number_of_blocks,
+            number_of_transactions: stats.number_of_transactions,
+            number_of_segwit_transactions: stats.number_of_segwit_transactions,
+            segwit_share: stats.segwit_share(),
+            number_of_inputs: stats.number_of_inputs,
+            number_of_outputs: stats.number_of_outputs,
+            number_of_op_return_outputs: stats.number_of_op_return_outputs(),
+            first_creation_time: stats.first_creation_time,
+            last_creation_time: stats.last_creation_time,
+            block_sizes: DistributionRecord::new(&stats.block_sizes),
+            block_weights: DistributionRecord::new(&stats.block_weights),
+            transaction_sizes: DistributionRecord::new(&stats.transaction_sizes),
+            transaction_weights: DistributionRecord::new(&stats.transaction_weights),
+            script_types: stats
+                .script_types
+                .iter()
+                .map(|(script_type, &count)| (script_type.name(), count))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DistributionRecord {
+    min: Option<u32>,
+    mean: Option<f64>,
+    max: Option<u32>,
+    buckets: Vec<BucketRecord>,
+}
+
+#[derive(Serialize)]
+struct BucketRecord {
+    min: u32,
+    max: u32,
+    count: u64,
+}
+
+impl DistributionRecord {
+    fn new(distribution: &Distribution) -> DistributionRecord {
+        DistributionRecord {
+            min: distribution.min(),
+            mean: distribution.mean(),
+            max: distribution.max(),
+            buckets: distribution
+                .buckets()
+                .into_iter()
+                .map(|(min, max, count)| BucketRecord { min, max, count })
+                .collect(),
+        }
+    }
+}
+
+/// The flat variant of `StatsRecord`, which leaves out the buckets of the
+/// distributions. `OP_RETURN` outputs are counted as `nulldata_outputs`.
+#[derive(Serialize)]
+struct StatsRow<'a> {
+    name: &'a str,
+    is_complete: bool,
+    number_of_blocks: u64,
+    number_of_transactions: u64,
+    number_of_segwit_transactions: u64,
+    segwit_share: Option<f64>,
+    number_of_inputs: u64,
+    number_of_outputs: u64,
+    first_creation_time: Option<u32>,
+    last_creation_time: Option<u32>,
+    min_block_size: Option<u32>,
+    mean_block_size: Option<f64>,
+    max_block_size: Option<u32>,
+    min_block_weight: Option<u32>,
+    mean_block_weight: Option<f64>,
+    max_block_weight: Option<u32>,
+    min_transaction_size: Option<u32>,
+    mean_transaction_size: Option<f64>,
+    max_transaction_size: Option<u32>,
+    min_transaction_weight: Option<u32>,
+    mean_transaction_weight: Option<f64>,
+    max_transaction_weight: Option<u32>,
+    pubkey_outputs: u64,
+    pubkeyhash_outputs: u64,
+    scripthash_outputs: u64,
+    witness_v0_keyhash_outputs: u64,
+    witness_v0_scripthash_outputs: u64,
+    witness_v1_taproot_outputs: u64,
+    multisig_outputs: u64,
+    nulldata_outputs: u64,
+    witness_unknown_outputs: u64,
+    nonstandard_outputs: u64,
+}
+
+impl<'a> StatsRow<'a> {
+    fn new(name: &'a str, stats: &ChainStats, is_complete: bool) -> StatsRow<'a> {
+        StatsRow {
+            name,
+            is_complete,
+            number_of_blocks: stats.number_of_blocks,
+            number_of_transactions: stats.number_of_transactions,
+            number_of_segwit_transactions: stats.number_of_segwit_transactions,
+            segwit_share: stats.segwit_share(),
+            number_of_inputs: stats.number_of_inputs,
+            number_of_outputs: stats.number_of_outputs,
+            first_creation_time: stats.first_creation_time,
+            last_creation_time: stats.last_creation_time,
+            min_block_size: stats.block_sizes.min(),
+            mean_block_size: stats.block_sizes.mean(),
+            max_block_size: stats.block_sizes.max(),
+            min_block_weight: stats.block_weights.min(),
+            mean_block_weight: stats.block_weights.mean(),
+            max_block_weight: stats.block_weights.max(),
+            min_transaction_size: stats.transaction_sizes.min(),
+            mean_transaction_size: stats.transaction_sizes.mean(),
+            max_transaction_size: stats.transaction_sizes.max(),
+            min_transaction_weight: stats.transaction_weights.min(),
+            mean_transaction_weight: stats.transaction_weights.mean(),
+            max_transaction_weight: stats.transaction_weights.max(),
+            pubkey_outputs: stats.number_of_outputs_of_type(ScriptType::P2PK),
+            pubkeyhash_outputs: stats.number_of_outputs_of_type(ScriptType::P2PKH),
+            scripthash_outputs: stats.number_of_outputs_of_type(ScriptType::P2SH),
+            witness_v0_keyhash_outputs: stats.number_of_outputs_of_type(ScriptType::P2WPKH),
+            witness_v0_scripthash_outputs: stats.number_of_outputs_of_type(ScriptType::P2WSH),
+            witness_v1_taproot_outputs: stats.number_of_outputs_of_type(ScriptType::P2TR),
+            multisig_outputs: stats.number_of_outputs_of_type(ScriptType::Multisig),
+            nulldata_outputs: stats.number_of_outputs_of_type(ScriptType::OpReturn),
+            witness_unknown_outputs: stats.number_of_outputs_of_type(ScriptType::WitnessUnknown),
+            nonstandard_outputs: stats.number_of_outputs_of_type(ScriptType::NonStandard),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use blk_file_reader::read_blocks;
+    use serde_json::Value;
+
+    const PATH_TO_GENESIS_BLK_FILE: &'static str = "tests/fixtures/zero_xor_key/blk00000.dat";
+    const GENESIS_BLOCK_HASH: &'static str =
+        "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+    const GENESIS_TX_HASH: &'static str =
+        "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+    const GENESIS_ADDRESS: &'static str = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+
+    /// Prints the genesis block the given number of times and returns the
+    /// printed records.
+    fn print_genesis_block(format: Format, records: Records, times: usize) -> String {
+        let block = read_blocks(PATH_TO_GENESIS_BLK_FILE, Network::Mainnet)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let mut output = Vec::new();
+        {
+            let printer =
+                RecordPrinter::with_writer(format, records, Network::Mainnet, &mut output);
+            for _ in 0..times {
+                printer.print_block(&block).unwrap();
+            }
+            printer.finish().unwrap();
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn prints_blocks_as_json_array() {
+        // when
+        let output = print_genesis_block(Format::Json, Records::Blocks, 2);
+
+        // then
+        let blocks: Value = serde_json::from_str(&output).unwrap();
+        let blocks = blocks.as_array().unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0]["hash"], GENESIS_BLOCK_HASH);
+        assert_eq!(blocks[0]["merkle_root"], GENESIS_TX_HASH);
+        assert_eq!(
+            blocks[0]["previous_block_hash"],
+            "0000000000000000000000000000000000000000000000000000000000000000"
+        );
+        let output = &blocks[0]["transactions"][0]["outputs"][0];
+        assert_eq!(output["value"], 5_000_000_000u64);
+        assert_eq!(output["script_type"], "pubkey");
+        assert_eq!(output["address"], GENESIS_ADDRESS);
+    }
+
+    #[test]
+    fn prints_empty_json_array_without_records() {
+        // when
+        let output = print_genesis_block(Format::Json, Records::Blocks, 0);
+
+        // then
+        assert_eq!(output, "[]\n");
+    }
+
+    #[test]
+    fn prints_one_transaction_per_line_as_ndjson() {
+        // when
+        let output = print_genesis_block(Format::Ndjson, Records::Transactions, 2);
+
+        // then
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let transaction: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(transaction["block_hash"], GENESIS_BLOCK_HASH);
+        assert_eq!(transaction["tx_hash"], GENESIS_TX_HASH);
+        assert_eq!(transaction["is_coinbase"], true);
+    }
+
+    #[test]
+    fn prints_blocks_without_transactions_as_csv() {
+        // when
+        let output = print_genesis_block(Format::Csv, Records::Blocks, 1);
+
+        // then
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "hash,version,previous_block_hash,merkle_root,creation_time,bits,nonce,\
+             bip34_height,coinbase_tag,size_in_bytes,stripped_size_in_bytes,weight,\
+             index_in_blk_file,offset_in_blk_file,number_of_transactions,\
+             number_of_segwit_transactions"
+        );
+        assert!(lines[1].starts_with(&format!("{},1,", GENESIS_BLOCK_HASH)));
+        assert!(lines[1].ends_with(",1,0"));
+    }
+
+    #[test]
+    fn prints_outputs_as_csv() {
+        // when
+        let output = print_genesis_block(Format::Csv, Records::Outputs, 1);
+
+        // then
+        let mut reader = csv::Reader::from_reader(output.as_bytes());
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec![
+                "block_hash",
+                "tx_hash",
+                "index",
+                "value",
+                "script_type",
+                "address",
+                "script_asm",
+            ]
+        );
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(&rows[0][0], GENESIS_BLOCK_HASH);
+        assert_eq!(&rows[0][1], GENESIS_TX_HASH);
+        assert_eq!(&rows[0][3], "5000000000");
+        assert_eq!(&rows[0][4], "pubkey");
+        assert_eq!(&rows[0][5], GENESIS_ADDRESS);
+        assert!(rows[0][6].ends_with(" OP_CHECKSIG"));
+    }
+
+    #[test]
+    fn prints_location_of_found_transaction() {
+        // given
+        let block = read_blocks(PATH_TO_GENESIS_BLK_FILE, Network::Mainnet)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let location = TransactionLocation {
+            block: BlockLocation {
+                blk_file_index: 0,
+                index_in_blk_file: 0,
+                offset_in_blk_file: 0,
+            },
+            index_in_block: 0,
+        };
+        let mut output = Vec::new();
+
+        // when
+        {
+            let printer = RecordPrinter::with_writer(
+                Format::Ndjson,
+                Records::Transactions,
+                Network::Mainnet,
+                &mut output,
+            );
+            printer
+                .print_found_transaction(&block.transactions[0], &block.hash, &location)
+                .unwrap();
+            printer.finish().unwrap();
+        }
+
+        // then
+        let transaction: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(transaction["blk_file_name"], "blk00000.dat");
+        assert_eq!(transaction["offset_in_blk_file"], 0);
+        assert_eq!(transaction["index_in_block"], 0);
+        assert_eq!(transaction["tx_hash"], GENESIS_TX_HASH);
+    }
+}
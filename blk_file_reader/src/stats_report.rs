@@ -0,0 +1,142 @@
+use blk_file_reader::{format_unix_time, ChainStats, Distribution};
+use std::io::{self, Write};
+
+/// The statistics of the blocks within a single blk file.
+pub struct BlkFileStats {
+    pub name: String,
+    pub stats: ChainStats,
+    /// Whether all blocks of the blk file have been read, i.e. reading did not
+    /// stop at an error.
+    pub is_complete: bool,
+}
+
+/// Prints a table of the given statistics of blk files, followed by the
+/// detailed total statistics.
+///
+/// Blk files that have not been read completely are marked as incomplete.
+pub fn print_stats_report(blk_file_stats: &[BlkFileStats], total: &ChainStats) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    writeln!(
+        out,
+        "{:<14} {:>8} {:>12} {:>8} {:>10}  {:<19}  {}",
+        "File",
+        "Blocks",
+        "Transactions",
+        "SegWit",
+        "OP_RETURN",
+        "First block time",
+        "Last block time"
+    )?;
+    for blk_file_stats in blk_file_stats.iter() {
+        let stats = &blk_file_stats.stats;
+        let row = format!(
+            "{:<14} {:>8} {:>12} {:>8} {:>10}  {:<19}  {}",
+            blk_file_stats.name,
+            stats.number_of_blocks,
+            stats.number_of_transactions,
+            format_share(stats.segwit_share()),
+            stats.number_of_op_return_outputs(),
+            format_time(stats.first_creation_time),
+            format_time(stats.last_creation_time)
+        );
+        if blk_file_stats.is_complete {
+            writeln!(out, "{}", row)?;
+        } else {
+            writeln!(out, "{:<98}  incomplete", row)?;
+        }
+    }
+
+    writeln!(out)?;
+    let number_of_incomplete_blk_files = blk_file_stats
+        .iter()
+        .filter(|blk_file_stats| !blk_file_stats.is_complete)
+        .count();
+    if number_of_incomplete_blk_files == 0 {
+        writeln!(out, "Total of {} blk files", blk_file_stats.len())?;
+    } else {
+        writeln!(
+            out,
+            "Total of {} blk files, {} of them incomplete",
+            blk_file_stats.len(),
+            number_of_incomplete_blk_files
+        )?;
+    }
+    writeln!(out, "  Blocks:               {}", total.number_of_blocks)?;
+    writeln!(
+        out,
+        "  Transactions:         {} ({} SegWit)",
+        total.number_of_transactions,
+        format_share(total.segwit_share())
+    )?;
+    writeln!(out, "  Inputs:               {}", total.number_of_inputs)?;
+    writeln!(
+        out,
+        "  Outputs:              {} ({} OP_RETURN)",
+        total.number_of_outputs,
+        total.number_of_op_return_outputs()
+    )?;
+    writeln!(
+        out,
+        "  Block times (UTC):    {} to {}",
+        format_time(total.first_creation_time),
+        format_time(total.last_creation_time)
+    )?;
+    print_distribution(&mut out, "Block sizes", &total.block_sizes)?;
+    print_distribution(&mut out, "Block weights", &total.block_weights)?;
+    print_distribution(&mut out, "Transaction sizes", &total.transaction_sizes)?;
+    print_distribution(&mut out, "Transaction weights", &total.transaction_weights)?;
+
+    writeln!(out, "  Output script types:")?;
+    let mut script_types: Vec<_> = total.script_types.iter().collect();
+    script_types.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.name().cmp(b.0.name())));
+    for (script_type, &count) in script_types {
+        writeln!(
+            out,
+            "    {:<22} {:>12} ({})",
+            script_type.name(),
+            count,
+            format_share(Some(count as f64 / total.number_of_outputs as f64))
+        )?;
+    }
+    Ok(())
+}
+
+fn print_distribution<W: Write>(
+    out: &mut W,
+    title: &str,
+    distribution: &Distribution,
+) -> io::Result<()> {
+    match (distribution.min(), distribution.mean(), distribution.max()) {
+        (Some(min), Some(mean), Some(max)) => {
+            writeln!(
+                out,
+                "  {:<21} min {}, mean {:.1}, max {}",
+                format!("{}:", title),
+                min,
+                mean,
+                max
+            )?;
+            for (lower_bound, upper_bound, count) in distribution.buckets() {
+                writeln!(
+                    out,
+                    "    {:>10} - {:<10} {:>12}",
+                    lower_bound, upper_bound, count
+                )?;
+            }
+        }
+        _ => writeln!(out, "  {:<21} -", format!("{}:", title))?,
+    }
+    Ok(())
+}
+
+fn format_share(share: Option<f64>) -> String {
+    share.map_or_else(
+        || String::from("-"),
+        |share| format!("{:.2}%", share * 100.0),
+    )
+}
+
+fn format_time(unix_time: Option<u32>) -> String {
+    unix_time.map_or_else(|| String::from("-"), format_unix_time)
+}
@@ -0,0 +1,308 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Not, Sub};
+
+/// An unsigned 256-bit integer, as needed for targets, chain work and the
+/// decompression of public keys.
+///
+/// Only the operations that are required for these calculations are
+/// implemented. Arithmetic wraps around on overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct U256 {
+    /// Little-endian 64-bit limbs.
+    limbs: [u64; 4],
+}
+
+impl U256 {
+    pub fn zero() -> U256 {
+        U256 { limbs: [0; 4] }
+    }
+
+    pub fn from_u64(value: u64) -> U256 {
+        U256 {
+            limbs: [value, 0, 0, 0],
+        }
+    }
+
+    /// Decodes the given little-endian bytes, e.g. a block hash in internal
+    /// byte order.
+    pub fn from_le_bytes(bytes: &[u8; 32]) -> U256 {
+        let mut limbs = [0u64; 4];
+        for (i, byte) in bytes.iter().enumerate() {
+            limbs[i / 8] |= u64::from(*byte) << (8 * (i % 8));
+        }
+        U256 { limbs }
+    }
+
+    /// Decodes the given big-endian bytes.
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> U256 {
+        let mut little_endian_bytes = *bytes;
+        little_endian_bytes.reverse();
+        U256::from_le_bytes(&little_endian_bytes)
+    }
+
+    /// Encodes this integer as big-endian bytes.
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (self.limbs[3 - i / 8] >> (8 * (7 - i % 8))) as u8;
+        }
+        bytes
+    }
+
+    /// Decodes the compact representation of a target as used by the `bits`
+    /// field of block headers.
+    ///
+    /// Returns `None` if the compact representation is negative or overflows
+    /// 256 bits, which is never the case for valid blocks.
+    pub fn from_compact(bits: u32) -> Option<U256> {
+        let size = bits >> 24;
+        let word = bits & 0x007F_FFFF;
+        let is_negative = word != 0 && (bits & 0x0080_0000) != 0;
+        let is_overflow =
+            word != 0 && (size > 34 || (word > 0xFF && size > 33) || (word > 0xFFFF && size > 32));
+        if is_negative || is_overflow {
+            return None;
+        }
+
+        if size <= 3 {
+            Some(U256::from_u64(u64::from(word >> (8 * (3 - size)))))
+        } else {
+            Some(U256::from_u64(u64::from(word)).shl(8 * (size - 3)))
+        }
+    }
+
+    /// Returns whether the least significant bit is set.
+    pub fn is_odd(&self) -> bool {
+        self.bit(0)
+    }
+
+    /// Calculates `(self + other) % modulus`, where both summands must be less
+    /// than `modulus`.
+    pub fn add_mod(&self, other: &U256, modulus: &U256) -> U256 {
+        let (sum, carry) = self.overflowing_add(other);
+        if carry || sum >= *modulus {
+            sum - *modulus
+        } else {
+            sum
+        }
+    }
+
+    /// Calculates `(self * other) % modulus`, where both factors must be less
+    /// than `modulus`.
+    pub fn mul_mod(&self, other: &U256, modulus: &U256) -> U256 {
+        let mut result = U256::zero();
+        for i in (0..256).rev() {
+            result = result.add_mod(&result, modulus);
+            if other.bit(i) {
+                result = result.add_mod(self, modulus);
+            }
+        }
+        result
+    }
+
+    /// Calculates `(self ^ exponent) % modulus`, where `self` must be less than
+    /// `modulus`.
+    pub fn pow_mod(&self, exponent: &U256, modulus: &U256) -> U256 {
+        let mut result = U256::from_u64(1);
+        for i in (0..256).rev() {
+            result = result.mul_mod(&result, modulus);
+            if exponent.bit(i) {
+                result = result.mul_mod(self, modulus);
+            }
+        }
+        result
+    }
+
+    fn overflowing_add(&self, other: &U256) -> (U256, bool) {
+        let mut result = U256::zero();
+        let mut carry = false;
+        for i in 0..4 {
+            let (sum, overflow_1) = self.limbs[i].overflowing_add(other.limbs[i]);
+            let (sum, overflow_2) = sum.overflowing_add(carry as u64);
+            result.limbs[i] = sum;
+            carry = overflow_1 || overflow_2;
+        }
+        (result, carry)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&limb| limb == 0)
+    }
+
+    fn bit(&self, index: u32) -> bool {
+        (self.limbs[(index / 64) as usize] >> (index % 64)) & 1 == 1
+    }
+
+    fn set_bit(&mut self, index: u32) {
+        self.limbs[(index / 64) as usize] |= 1 << (index % 64);
+    }
+
+    /// Shifts this integer to the left by the given number of bits.
+    pub fn shl(&self, shift: u32) -> U256 {
+        let mut result = U256::zero();
+        if shift >= 256 {
+            return result;
+        }
+        let limb_shift = (shift / 64) as usize;
+        let bit_shift = shift % 64;
+        for i in (limb_shift..4).rev() {
+            let source = i - limb_shift;
+            result.limbs[i] = self.limbs[source] << bit_shift;
+            if bit_shift > 0 && source > 0 {
+                result.limbs[i] |= self.limbs[source - 1] >> (64 - bit_shift);
+            }
+        }
+        result
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &U256) -> Ordering {
+        for i in (0..4).rev() {
+            match self.limbs[i].cmp(&other.limbs[i]) {
+                Ordering::Equal => continue,
+                ordering => return ordering,
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &U256) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for U256 {
+    type Output = U256;
+
+    fn add(self, other: U256) -> U256 {
+        self.overflowing_add(&other).0
+    }
+}
+
+impl Sub for U256 {
+    type Output = U256;
+
+    fn sub(self, other: U256) -> U256 {
+        let mut result = U256::zero();
+        let mut borrow = false;
+        for i in 0..4 {
+            let (difference, overflow_1) = self.limbs[i].overflowing_sub(other.limbs[i]);
+            let (difference, overflow_2) = difference.overflowing_sub(borrow as u64);
+            result.limbs[i] = difference;
+            borrow = overflow_1 || overflow_2;
+        }
+        result
+    }
+}
+
+impl Not for U256 {
+    type Output = U256;
+
+    fn not(self) -> U256 {
+        U256 {
+            limbs: [
+                !self.limbs[0],
+                !self.limbs[1],
+                !self.limbs[2],
+                !self.limbs[3],
+            ],
+        }
+    }
+}
+
+impl Div for U256 {
+    type Output = U256;
+
+    /// Long division, panics if `divisor` is zero.
+    fn div(self, divisor: U256) -> U256 {
+        assert!(!divisor.is_zero(), "division by zero");
+        let mut quotient = U256::zero();
+        let mut remainder = U256::zero();
+        for i in (0..256).rev() {
+            remainder = remainder.shl(1);
+            if self.bit(i) {
+                remainder.limbs[0] |= 1;
+            }
+            if remainder >= divisor {
+                remainder = remainder - divisor;
+                quotient.set_bit(i);
+            }
+        }
+        quotient
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn decodes_compact_target_of_genesis_block() {
+        // given
+        let bits = 0x1d00ffff;
+
+        // when
+        let target = U256::from_compact(bits).unwrap();
+
+        // then
+        let mut expected = [0u8; 32];
+        expected[4] = 0xFF;
+        expected[5] = 0xFF;
+        assert_eq!(target.to_be_bytes(), expected);
+    }
+
+    #[test]
+    fn rejects_negative_compact_target() {
+        assert!(U256::from_compact(0x04923456).is_none());
+    }
+
+    #[test]
+    fn divides() {
+        let dividend = U256::from_u64(1).shl(200) + U256::from_u64(5);
+        let divisor = U256::from_u64(1).shl(100);
+        assert_eq!(dividend / divisor, U256::from_u64(1).shl(100));
+        assert_eq!(U256::from_u64(100) / U256::from_u64(7), U256::from_u64(14));
+    }
+
+    #[test]
+    fn converts_between_byte_orders() {
+        // given
+        let mut bytes = [0u8; 32];
+        bytes[0] = 0x01;
+        bytes[31] = 0x02;
+
+        // when
+        let value = U256::from_le_bytes(&bytes);
+
+        // then
+        let mut big_endian_bytes = bytes;
+        big_endian_bytes.reverse();
+        assert_eq!(value.to_be_bytes(), big_endian_bytes);
+        assert_eq!(U256::from_be_bytes(&big_endian_bytes), value);
+    }
+
+    #[test]
+    fn calculates_modular_arithmetic() {
+        // given
+        let modulus = !U256::zero() - U256::from_u64(4);
+        let large = modulus - U256::from_u64(1);
+
+        // then
+        assert_eq!(large.add_mod(&large, &modulus), modulus - U256::from_u64(2));
+        assert_eq!(large.mul_mod(&large, &modulus), U256::from_u64(1));
+        assert_eq!(
+            U256::from_u64(3).pow_mod(&U256::from_u64(5), &U256::from_u64(7)),
+            U256::from_u64(5)
+        );
+        assert_eq!(
+            U256::from_u64(2).pow_mod(&U256::from_u64(300), &modulus),
+            U256::from_u64(1)
+                .shl(300 - 256)
+                .mul_mod(&U256::from_u64(5), &modulus)
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use byteorder::{ByteOrder, LittleEndian};
+use compression::read_compressed_output;
+use core_var_int::read_core_var_int;
+use domain::{BlockUndo, Hash, SpentOutput, TransactionUndo};
+use read::{calculate_hash, output_from_script, ReadBlockInternals};
+use read_options::ReadOptions;
+use std::io::{Cursor, Error, ErrorKind, Result};
+
+/// The length of the checksum that follows the undo data of a block.
+pub(crate) const UNDO_CHECKSUM_LENGTH: usize = 32;
+
+/// The maximum size of the undo data of a block, which is the maximum size of
+/// any object that Bitcoin Core deserializes, see `MAX_SIZE`.
+const MAX_UNDO_SIZE: u32 = 0x0200_0000;
+
+/// Checks that undo data of the given size does not exceed `MAX_UNDO_SIZE`.
+pub(crate) fn validate_undo_size(undo_size: u32) -> Result<()> {
+    if undo_size > MAX_UNDO_SIZE {
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("undo data of {} bytes exceeds the maximum size", undo_size),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Read the undo data of a block from a record of a rev file, see
+/// `positioned_read::read_record`, which includes the trailing checksum.
+///
+/// The checksum covers the hash of the previous block, so a successful read
+/// also ensures that the undo data belongs to a child of `previous_block_hash`.
+pub(crate) fn read_block_undo(
+    record: &[u8],
+    previous_block_hash: &Hash,
+    options: ReadOptions,
+) -> Result<BlockUndo> {
+    if record.len() < 8 + UNDO_CHECKSUM_LENGTH {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "undo record is truncated",
+        ));
+    }
+    // The magic number has already been validated when reading the record.
+    // TODO Fix possibly truncating cast.
+    let size = LittleEndian::read_u32(&record[4..8]) as usize;
+    let data = &record[8..record.len() - UNDO_CHECKSUM_LENGTH];
+    if data.len() != size {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "undo record is truncated",
+        ));
+    }
+
+    validate_checksum(
+        data,
+        &record[record.len() - UNDO_CHECKSUM_LENGTH..],
+        previous_block_hash,
+    )?;
+
+    let mut reader = Cursor::new(data);
+    let transaction_count = reader.read_length()?;
+    // TODO Fix possibly truncating cast.
+    let mut transactions = Vec::with_capacity(transaction_count as usize);
+    for _ in 0..transaction_count {
+        let spent_output_count = reader.read_length()?;
+        // TODO Fix possibly truncating cast.
+        let mut spent_outputs = Vec::with_capacity(spent_output_count as usize);
+        for _ in 0..spent_output_count {
+            spent_outputs.push(read_spent_output(&mut reader, options)?);
+        }
+        transactions.push(TransactionUndo {
+            spent_outputs: spent_outputs.into_boxed_slice(),
+        });
+    }
+
+    Ok(BlockUndo {
+        transactions: transactions.into_boxed_slice(),
+    })
+}
+
+/// Read a spent output, which Bitcoin Core serializes as `Coin` along with a
+/// dummy version for compatibility with older undo files.
+fn read_spent_output(reader: &mut Cursor<&[u8]>, options: ReadOptions) -> Result<SpentOutput> {
+    let code = read_core_var_int(reader)?;
+    // TODO Fix possibly truncating cast.
+    let height = (code >> 1) as u32;
+    let is_coinbase = code & 1 == 1;
+    if height > 0 {
+        let _version = read_core_var_int(reader)?;
+    }
+    let (value, script) = read_compressed_output(reader)?;
+    let output = output_from_script(0, value, script, options);
+
+    Ok(SpentOutput {
+        height,
+        is_coinbase,
+        value: output.value,
+        address: output.address,
+        address_derivation: output.address_derivation,
+        script: output.script,
+        script_type: output.script_type,
+    })
+}
+
+fn validate_checksum(data: &[u8], checksum: &[u8], previous_block_hash: &Hash) -> Result<()> {
+    let mut checksummed_bytes = Vec::with_capacity(32 + data.len());
+    checksummed_bytes.extend(previous_block_hash.0.iter().rev());
+    checksummed_bytes.extend_from_slice(data);
+    let expected_checksum = calculate_hash(&checksummed_bytes)?;
+    if expected_checksum.0.iter().rev().eq(checksum.iter()) {
+        Ok(())
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            "checksum of undo data does not match",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use domain::{AddressDerivation, Network, ScriptType};
+
+    fn record(data: &[u8], previous_block_hash: &Hash) -> Vec<u8> {
+        let mut record = vec![0xF9, 0xBE, 0xB4, 0xD9];
+        record.extend_from_slice(&[data.len() as u8, 0, 0, 0]);
+        record.extend_from_slice(data);
+        let mut checksummed_bytes: Vec<u8> = previous_block_hash.0.iter().rev().cloned().collect();
+        checksummed_bytes.extend_from_slice(data);
+        let checksum = calculate_hash(&checksummed_bytes).unwrap();
+        record.extend(checksum.0.iter().rev());
+        record
+    }
+
+    #[test]
+    fn reads_spent_outputs() {
+        // given
+        let previous_block_hash = Hash([0x11; 32]);
+        // One transaction spending two outputs: a P2PKH coinbase output of 50
+        // BTC from height 1 and a P2SH output of 1 satoshi from height 2.
+        let mut data = vec![0x01, 0x02];
+        data.extend_from_slice(&[0x03, 0x00, 0x32, 0x00]);
+        data.extend_from_slice(&[0xAB; 20]);
+        data.extend_from_slice(&[0x04, 0x00, 0x01, 0x01]);
+        data.extend_from_slice(&[0xCD; 20]);
+        let record = record(&data, &previous_block_hash);
+
+        // when
+        let block_undo = read_block_undo(
+            &record,
+            &previous_block_hash,
+            ReadOptions::new(Network::Mainnet),
+        )
+        .unwrap();
+
+        // then
+        assert_eq!(block_undo.transactions.len(), 1);
+        let spent_outputs = &block_undo.transactions[0].spent_outputs;
+        assert_eq!(spent_outputs.len(), 2);
+
+        assert_eq!(spent_outputs[0].height, 1);
+        assert!(spent_outputs[0].is_coinbase);
+        assert_eq!(spent_outputs[0].value, 5_000_000_000);
+        assert_eq!(spent_outputs[0].script_type, ScriptType::P2PKH);
+        assert_eq!(
+            spent_outputs[0].address_derivation,
+            Some(AddressDerivation::Direct)
+        );
+
+        assert_eq!(spent_outputs[1].height, 2);
+        assert!(!spent_outputs[1].is_coinbase);
+        assert_eq!(spent_outputs[1].value, 1);
+        assert_eq!(spent_outputs[1].script_type, ScriptType::P2SH);
+    }
+
+    #[test]
+    fn when_previous_block_hash_does_not_match_then_fails() {
+        // given
+        let record = record(&[0x00], &Hash([0x11; 32]));
+
+        // when
+        let result = read_block_undo(&record, &Hash([0x22; 32]), ReadOptions::default());
+
+        // then
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -1,6 +1,13 @@
-use super::Blocks;
-use std::io::{self, BufReader};
+use super::{
+    Block, BlockIndexEntry, BlockLocation, BlockUndo, Blocks, Network, ParBlocks, ReadOptions,
+};
+use parse_error::shift_offset;
+use positioned_read::{read_record, record_offset};
+use read::{validate_block_size, ReadBlock};
+use std::io::{self, BufReader, Cursor};
 use std::path::Path;
+use undo::{read_block_undo, validate_undo_size, UNDO_CHECKSUM_LENGTH};
+use xor::read_xor_key_for_file;
 
 /// Reads all blk files at the given path.
 ///
@@ -26,9 +33,164 @@ fn is_blk_file(file_name: &str) -> bool {
     return file_name.starts_with("blk") && file_name.ends_with(".dat");
 }
 
+/// Returns the index of the blk file at the given path, e.g. `1208` for
+/// `blk01208.dat`.
+///
+/// Returns `None` if the file name does not follow the `blk?????.dat` pattern.
+pub fn blk_file_index(path_to_blk_file: &str) -> Option<usize> {
+    let file_name = Path::new(path_to_blk_file).file_name()?.to_str()?;
+    if !is_blk_file(file_name) {
+        return None;
+    }
+    file_name[3..file_name.len() - 4].parse::<usize>().ok()
+}
+
 /// Reads the blocks of the blk file at the given path.
-pub fn read_blocks(path_to_blk_file: &str) -> io::Result<Blocks> {
+///
+/// The given `network` determines the expected magic number of the blocks as
+/// well as the encoding of the addresses within the blocks.
+pub fn read_blocks(path_to_blk_file: &str, network: Network) -> io::Result<Blocks> {
+    read_blocks_with_options(path_to_blk_file, ReadOptions::new(network))
+}
+
+/// Reads the blocks of the blk file at the given path using the given `options`.
+///
+/// If the directory of the blk file contains an `xor.dat` file, the blk file is
+/// de-obfuscated with the key stored therein.
+pub fn read_blocks_with_options(
+    path_to_blk_file: &str,
+    options: ReadOptions,
+) -> io::Result<Blocks> {
+    let xor_key = read_xor_key_for_file(path_to_blk_file)?;
     let file = std::fs::File::open(path_to_blk_file)?;
     let buf_reader = BufReader::new(file);
-    Ok(Blocks::new(buf_reader))
+    match xor_key {
+        Some(xor_key) => Ok(Blocks::with_xor_key(buf_reader, options, xor_key)),
+        None => Ok(Blocks::new(buf_reader, options)),
+    }
+}
+
+/// Reads the blocks of all blk files at the given path in parallel.
+///
+/// See `ParBlocks` for the order in which the blocks are yielded and how to
+/// limit the parallelism.
+pub fn par_read_blocks(path_to_blk_files: &str, options: ReadOptions) -> io::Result<ParBlocks> {
+    let blk_files = read_indexed_blk_files(path_to_blk_files)?;
+    Ok(ParBlocks::new(blk_files, options))
+}
+
+/// Reads all blk files at the given path, see `read_blk_files`, together with
+/// their index.
+pub(crate) fn read_indexed_blk_files(path_to_blk_files: &str) -> io::Result<Vec<(usize, String)>> {
+    let mut blk_files = Vec::new();
+    for blk_file_path in read_blk_files(path_to_blk_files)? {
+        let index = blk_file_index(&blk_file_path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is not named like a blk file", blk_file_path),
+            )
+        })?;
+        blk_files.push((index, blk_file_path));
+    }
+    Ok(blk_files)
+}
+
+/// Reads the block of the given block index entry from the blk files at the
+/// given path, see `BlockIndex`.
+///
+/// Since the block index does not record the position of a block among the
+/// blocks of its blk file, `index_in_blk_file` of the returned block is zero.
+pub fn read_indexed_block(
+    path_to_blk_files: &str,
+    entry: &BlockIndexEntry,
+    options: ReadOptions,
+) -> io::Result<Block> {
+    let (file_number, data_offset) = match (entry.file_number, entry.data_offset) {
+        (Some(file_number), Some(data_offset)) => (file_number, data_offset),
+        _ => return Err(not_stored_error(entry, "block")),
+    };
+    let path_to_blk_file = format!("{}/blk{:05}.dat", path_to_blk_files, file_number);
+    let record_offset = record_offset(&path_to_blk_file, data_offset)?;
+    let record = read_record(
+        &path_to_blk_file,
+        record_offset,
+        options.network,
+        0,
+        validate_block_size,
+    )?;
+    Cursor::new(record)
+        .read_block_at(0, record_offset, options)
+        .map_err(|error| shift_offset(error, record_offset))
+}
+
+/// Reads the block at the given location from the blk files at the given path,
+/// e.g. as found by `find_block` or looked up in a `HashIndex`.
+pub fn read_located_block(
+    path_to_blk_files: &str,
+    location: &BlockLocation,
+    options: ReadOptions,
+) -> io::Result<Block> {
+    let path_to_blk_file = format!("{}/{}", path_to_blk_files, location.blk_file_name());
+    let record_offset = location.offset_in_blk_file;
+    let record = read_record(
+        &path_to_blk_file,
+        record_offset,
+        options.network,
+        0,
+        validate_block_size,
+    )?;
+    Cursor::new(record)
+        .read_block_at(location.index_in_blk_file, record_offset, options)
+        .map_err(|error| shift_offset(error, record_offset))
+}
+
+/// Reads the serialized block at the given offset of the blk file at the given
+/// path, see `Block::offset_in_blk_file`.
+///
+/// Returns the de-obfuscated bytes of the block, excluding its magic number and
+/// size, without parsing them.
+pub fn read_raw_block(
+    path_to_blk_file: &str,
+    offset_in_blk_file: u64,
+    network: Network,
+) -> io::Result<Box<[u8]>> {
+    let record = read_record(
+        path_to_blk_file,
+        offset_in_blk_file,
+        network,
+        0,
+        validate_block_size,
+    )?;
+    Ok(Box::from(&record[8..]))
+}
+
+/// Reads the undo data of the block of the given block index entry from the
+/// rev files at the given path, i.e. the outputs spent by the block.
+///
+/// The genesis block has no undo data.
+pub fn read_indexed_block_undo(
+    path_to_blk_files: &str,
+    entry: &BlockIndexEntry,
+    options: ReadOptions,
+) -> io::Result<BlockUndo> {
+    let (file_number, undo_offset) = match (entry.file_number, entry.undo_offset) {
+        (Some(file_number), Some(undo_offset)) => (file_number, undo_offset),
+        _ => return Err(not_stored_error(entry, "undo data")),
+    };
+    let path_to_rev_file = format!("{}/rev{:05}.dat", path_to_blk_files, file_number);
+    let record = read_record(
+        &path_to_rev_file,
+        record_offset(&path_to_rev_file, undo_offset)?,
+        options.network,
+        UNDO_CHECKSUM_LENGTH,
+        validate_undo_size,
+    )?;
+    read_block_undo(&record, &entry.previous_block_hash, options)
+}
+
+fn not_stored_error(entry: &BlockIndexEntry, what: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} of block {:?} is not stored", what, entry.hash),
+    )
 }
@@ -0,0 +1,384 @@
+use domain::{Block, Hash};
+use read_ref::calculate_hash_of_parts;
+use std::error::Error;
+use std::fmt;
+use u256::U256;
+
+/// The prefix of the coinbase output that commits to the witnesses of a block,
+/// i.e. `OP_RETURN` followed by a push of 36 bytes starting with `0xaa21a9ed`.
+const WITNESS_COMMITMENT_PREFIX: [u8; 6] = [0x6A, 0x24, 0xAA, 0x21, 0xA9, 0xED];
+
+/// The length of a witness commitment output script.
+const WITNESS_COMMITMENT_LENGTH: usize = 38;
+
+/// Checks the integrity of the given block.
+///
+/// Verifies that the block hash meets the target encoded in `bits`, that the
+/// merkle root matches the hashes of the transactions and that the witness
+/// commitment of the coinbase transaction, if any, matches the witness hashes
+/// as specified in [BIP 141](https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki#commitment-structure).
+///
+/// This does not check whether the transactions are valid, nor whether the
+/// target is the one required by the difficulty adjustment.
+pub fn validate_block(block: &Block) -> Result<(), ValidationError> {
+    validate_proof_of_work(block)?;
+    validate_merkle_root(block)?;
+    validate_witness_commitment(block)
+}
+
+fn validate_proof_of_work(block: &Block) -> Result<(), ValidationError> {
+    let target = match U256::from_compact(block.bits) {
+        Some(target) if !target.is_zero() => target,
+        _ => return Err(ValidationError::InvalidTarget { bits: block.bits }),
+    };
+    if U256::from_be_bytes(&block.hash.0) <= target {
+        Ok(())
+    } else {
+        Err(ValidationError::InsufficientProofOfWork {
+            hash: block.hash.clone(),
+            bits: block.bits,
+        })
+    }
+}
+
+fn validate_merkle_root(block: &Block) -> Result<(), ValidationError> {
+    let tx_hashes: Vec<[u8; 32]> = block
+        .transactions
+        .iter()
+        .map(|transaction| to_internal_byte_order(&transaction.tx_hash))
+        .collect();
+    let merkle_root = calculate_merkle_root(tx_hashes).ok_or(ValidationError::NoTransactions)?;
+    if merkle_root == to_internal_byte_order(&block.merkle_root) {
+        Ok(())
+    } else {
+        Err(ValidationError::MerkleRootMismatch {
+            merkle_root: block.merkle_root.clone(),
+            calculated_merkle_root: to_display_byte_order(merkle_root),
+        })
+    }
+}
+
+fn validate_witness_commitment(block: &Block) -> Result<(), ValidationError> {
+    let coinbase = match block.transactions.first() {
+        Some(coinbase) => coinbase,
+        None => return Err(ValidationError::NoTransactions),
+    };
+
+    // If there are multiple commitments, the last one counts.
+    let commitment = coinbase
+        .outputs
+        .iter()
+        .rev()
+        .map(|output| &output.script)
+        .find(|script| {
+            script.len() >= WITNESS_COMMITMENT_LENGTH
+                && script.starts_with(&WITNESS_COMMITMENT_PREFIX)
+        })
+        .map(|script| &script[WITNESS_COMMITMENT_PREFIX.len()..WITNESS_COMMITMENT_LENGTH]);
+
+    let commitment = match commitment {
+        Some(commitment) => commitment,
+        None => {
+            let has_witnesses = block.transactions.iter().any(|transaction| {
+                transaction
+                    .script_witnesses
+                    .iter()
+                    .any(|script_witness| !script_witness.items.is_empty())
+            });
+            return if has_witnesses {
+                Err(ValidationError::MissingWitnessCommitment)
+            } else {
+                Ok(())
+            };
+        }
+    };
+
+    // The coinbase witness must consist of a single 32-byte reserved value.
+    let reserved_value = coinbase
+        .script_witnesses
+        .first()
+        .filter(|script_witness| script_witness.items.len() == 1)
+        .map(|script_witness| &script_witness.items[0])
+        .filter(|item| item.len() == 32)
+        .ok_or(ValidationError::InvalidWitnessReservedValue)?;
+
+    // The witness hash of the coinbase transaction is replaced by zeros.
+    let witness_hashes: Vec<[u8; 32]> = Some([0u8; 32])
+        .into_iter()
+        .chain(
+            block.transactions[1..]
+                .iter()
+                .map(|transaction| to_internal_byte_order(&transaction.witness_hash)),
+        )
+        .collect();
+    let witness_root =
+        calculate_merkle_root(witness_hashes).ok_or(ValidationError::NoTransactions)?;
+    let calculated_commitment =
+        to_internal_byte_order(&calculate_hash_of_parts(&[&witness_root, reserved_value]));
+
+    if &calculated_commitment[..] == commitment {
+        Ok(())
+    } else {
+        Err(ValidationError::WitnessCommitmentMismatch)
+    }
+}
+
+/// Calculates the merkle root of the given hashes, all in internal byte order.
+///
+/// Returns `None` if there are no hashes.
+fn calculate_merkle_root(mut hashes: Vec<[u8; 32]>) -> Option<[u8; 32]> {
+    if hashes.is_empty() {
+        return None;
+    }
+    while hashes.len() > 1 {
+        // If the number of hashes is odd, the last one is paired with itself.
+        if hashes.len() % 2 == 1 {
+            let last = hashes[hashes.len() - 1];
+            hashes.push(last);
+        }
+        hashes = hashes
+            .chunks(2)
+            .map(|pair| to_internal_byte_order(&calculate_hash_of_parts(&[&pair[0], &pair[1]])))
+            .collect();
+    }
+    Some(hashes[0])
+}
+
+fn to_internal_byte_order(hash: &Hash) -> [u8; 32] {
+    let mut bytes = hash.0;
+    bytes.reverse();
+    bytes
+}
+
+fn to_display_byte_order(mut bytes: [u8; 32]) -> Hash {
+    bytes.reverse();
+    Hash(bytes)
+}
+
+/// Returned by `validate_block` if a block fails one of its checks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// The block does not contain any transactions.
+    NoTransactions,
+    /// `bits` does not encode a valid target.
+    InvalidTarget { bits: u32 },
+    /// The block hash exceeds the target encoded in `bits`.
+    InsufficientProofOfWork { hash: Hash, bits: u32 },
+    /// The merkle root of the header does not match the transactions.
+    MerkleRootMismatch {
+        merkle_root: Hash,
+        calculated_merkle_root: Hash,
+    },
+    /// The block contains witnesses but no witness commitment.
+    MissingWitnessCommitment,
+    /// The witness of the coinbase transaction is not a single 32-byte value.
+    InvalidWitnessReservedValue,
+    /// The witness commitment does not match the witnesses of the block.
+    WitnessCommitmentMismatch,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValidationError::NoTransactions => write!(f, "block contains no transactions"),
+            ValidationError::InvalidTarget { bits } => write!(f, "invalid target {:08x}", bits),
+            ValidationError::InsufficientProofOfWork { ref hash, bits } => {
+                write!(f, "block hash {:?} does not meet target {:08x}", hash, bits)
+            }
+            ValidationError::MerkleRootMismatch {
+                ref merkle_root,
+                ref calculated_merkle_root,
+            } => write!(
+                f,
+                "merkle root {:?} does not match calculated merkle root {:?}",
+                merkle_root, calculated_merkle_root
+            ),
+            ValidationError::MissingWitnessCommitment => {
+                write!(f, "block contains witnesses but no witness commitment")
+            }
+            ValidationError::InvalidWitnessReservedValue => {
+                write!(f, "coinbase witness is not a single 32-byte reserved value")
+            }
+            ValidationError::WitnessCommitmentMismatch => {
+                write!(f, "witness commitment does not match witnesses")
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use domain::{Network, ScriptWitness};
+    use read::{output_from_script, ReadBlock};
+    use read_options::ReadOptions;
+    use std::io::Cursor;
+    use std::io::ErrorKind;
+
+    fn genesis_block() -> Block {
+        let bytes: &[u8] = include_bytes!("../tests/fixtures/zero_xor_key/blk00000.dat");
+        Cursor::new(bytes)
+            .read_block(0, ReadOptions::new(Network::Mainnet))
+            .unwrap()
+    }
+
+    /// Adds a copy of the coinbase transaction that has a witness to the
+    /// genesis block, along with a witness commitment.
+    fn seg_wit_block() -> Block {
+        let mut block = genesis_block();
+        let mut transaction = block.transactions[0].clone();
+        transaction.witness_hash = Hash([0x22; 32]);
+        transaction.script_witnesses = vec![ScriptWitness {
+            items: vec![vec![0x01]],
+        }]
+        .into_boxed_slice();
+
+        let reserved_value = [0x33u8; 32];
+        let witness_root = calculate_merkle_root(vec![
+            [0u8; 32],
+            to_internal_byte_order(&transaction.witness_hash),
+        ])
+        .unwrap();
+        let commitment =
+            to_internal_byte_order(&calculate_hash_of_parts(&[&witness_root, &reserved_value]));
+        let mut commitment_script = WITNESS_COMMITMENT_PREFIX.to_vec();
+        commitment_script.extend_from_slice(&commitment);
+
+        let mut coinbase = block.transactions[0].clone();
+        coinbase.script_witnesses = vec![ScriptWitness {
+            items: vec![reserved_value.to_vec()],
+        }]
+        .into_boxed_slice();
+        let mut outputs = coinbase.outputs.to_vec();
+        outputs.push(output_from_script(
+            1,
+            0,
+            commitment_script.into_boxed_slice(),
+            ReadOptions::new(Network::Mainnet),
+        ));
+        coinbase.outputs = outputs.into_boxed_slice();
+
+        block.transactions = vec![coinbase, transaction].into_boxed_slice();
+        block
+    }
+
+    #[test]
+    fn genesis_block_is_valid() {
+        assert_eq!(validate_block(&genesis_block()), Ok(()));
+    }
+
+    #[test]
+    fn when_validation_is_enabled_then_read_block_reports_invalid_blocks() {
+        // given
+        let mut bytes = include_bytes!("../tests/fixtures/zero_xor_key/blk00000.dat").to_vec();
+        // Corrupt the merkle root.
+        bytes[8 + 36] ^= 0xFF;
+        let options = ReadOptions {
+            validate_blocks: true,
+            ..ReadOptions::new(Network::Mainnet)
+        };
+
+        // when
+        let result = Cursor::new(bytes).read_block(0, options);
+
+        // then
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn when_merkle_root_does_not_match_then_fails() {
+        // given
+        let mut block = genesis_block();
+        block.merkle_root = Hash([0u8; 32]);
+
+        // when
+        let result = validate_block(&block);
+
+        // then
+        match result {
+            Err(ValidationError::MerkleRootMismatch {
+                calculated_merkle_root,
+                ..
+            }) => assert_eq!(calculated_merkle_root, genesis_block().merkle_root),
+            _ => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn when_hash_exceeds_target_then_fails() {
+        // given
+        let mut block = genesis_block();
+        block.bits = 0x1b00ffff;
+
+        // when
+        let result = validate_block(&block);
+
+        // then
+        assert_eq!(
+            result,
+            Err(ValidationError::InsufficientProofOfWork {
+                hash: block.hash.clone(),
+                bits: 0x1b00ffff,
+            })
+        );
+    }
+
+    #[test]
+    fn when_bits_encode_negative_target_then_fails() {
+        // given
+        let mut block = genesis_block();
+        block.bits = 0x1d80ffff;
+
+        // when
+        let result = validate_block(&block);
+
+        // then
+        assert_eq!(
+            result,
+            Err(ValidationError::InvalidTarget { bits: 0x1d80ffff })
+        );
+    }
+
+    #[test]
+    fn witness_commitment_matches_witness_hashes() {
+        // given
+        let block = seg_wit_block();
+
+        // when
+        let result = validate_witness_commitment(&block);
+
+        // then
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn when_witness_hash_does_not_match_commitment_then_fails() {
+        // given
+        let mut block = seg_wit_block();
+        block.transactions[1].witness_hash = Hash([0x44; 32]);
+
+        // when
+        let result = validate_witness_commitment(&block);
+
+        // then
+        assert_eq!(result, Err(ValidationError::WitnessCommitmentMismatch));
+    }
+
+    #[test]
+    fn when_block_has_witnesses_but_no_commitment_then_fails() {
+        // given
+        let mut block = seg_wit_block();
+        let mut coinbase = block.transactions[0].clone();
+        coinbase.outputs = coinbase.outputs[..1].to_vec().into_boxed_slice();
+        block.transactions[0] = coinbase;
+
+        // when
+        let result = validate_witness_commitment(&block);
+
+        // then
+        assert_eq!(result, Err(ValidationError::MissingWitnessCommitment));
+    }
+}
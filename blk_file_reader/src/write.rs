@@ -0,0 +1,271 @@
+use byteorder::{LittleEndian, WriteBytesExt};
+use data_encoding::HEXLOWER;
+use domain::*;
+use std::io::{Result, Write};
+
+/// This trait allows for writing `Block`s to blk files.
+pub trait WriteBlock: Write {
+    /// Write a `Block` to the underlying blk file, preceded by the magic number
+    /// of the given `network` and the size of the block.
+    fn write_block(&mut self, block: &Block, network: Network) -> Result<()>;
+
+    /// Write a `Block` in consensus encoding, i.e. without the magic number and
+    /// size that precede it in blk files.
+    ///
+    /// For more information on the structure of blocks refer to the
+    /// [according wiki entry](https://en.bitcoin.it/wiki/Block).
+    fn write_block_content(&mut self, block: &Block) -> Result<()>;
+
+    /// Write a `Transaction` in consensus encoding.
+    ///
+    /// Transactions with witnesses are written in the extended format of
+    /// [BIP 144](https://github.com/bitcoin/bips/blob/master/bip-0144.mediawiki).
+    fn write_transaction(&mut self, transaction: &Transaction) -> Result<()>;
+}
+
+/// Internal helper trait.
+pub(crate) trait WriteBlockInternals: Write {
+    /// Write an `Input` in consensus encoding.
+    fn write_input(&mut self, input: &Input) -> Result<()>;
+
+    /// Write an `Output` in consensus encoding.
+    fn write_output(&mut self, output: &Output) -> Result<()>;
+
+    /// Write the items of a `ScriptWitness` in consensus encoding.
+    fn write_script_witness(&mut self, script_witness: &ScriptWitness) -> Result<()>;
+
+    /// Write a 256-bit `Hash` in internal byte order.
+    fn write_hash(&mut self, hash: &Hash) -> Result<()>;
+
+    /// Write a variable-length integer, see `ReadBlockInternals::read_var_int`.
+    fn write_var_int(&mut self, value: u64) -> Result<()>;
+
+    /// Write a bitcoin script preceded by its length.
+    fn write_script(&mut self, script: &[u8]) -> Result<()>;
+}
+
+/// Implement `WriteBlock` for all types that implement `Write`.
+impl<W: Write + ?Sized> WriteBlock for W {
+    fn write_block(&mut self, block: &Block, network: Network) -> Result<()> {
+        let mut block_content = Vec::new();
+        block_content.write_block_content(block)?;
+
+        self.write_u32::<LittleEndian>(network.magic_number())?;
+        // TODO Fix possibly truncating cast.
+        self.write_u32::<LittleEndian>(block_content.len() as u32)?;
+        self.write_all(&block_content)
+    }
+
+    fn write_block_content(&mut self, block: &Block) -> Result<()> {
+        self.write_u32::<LittleEndian>(block.version)?;
+        self.write_hash(&block.previous_block_hash)?;
+        self.write_hash(&block.merkle_root)?;
+        self.write_u32::<LittleEndian>(block.creation_time)?;
+        self.write_u32::<LittleEndian>(block.bits)?;
+        self.write_u32::<LittleEndian>(block.nonce)?;
+
+        self.write_var_int(block.transactions.len() as u64)?;
+        for transaction in block.transactions.iter() {
+            self.write_transaction(transaction)?;
+        }
+        Ok(())
+    }
+
+    fn write_transaction(&mut self, transaction: &Transaction) -> Result<()> {
+        let has_witnesses = transaction
+            .script_witnesses
+            .iter()
+            .any(|script_witness| !script_witness.items.is_empty());
+
+        self.write_u32::<LittleEndian>(transaction.version)?;
+        if has_witnesses {
+            // Marker and flag.
+            self.write_all(&[0x00, 0x01])?;
+        }
+
+        self.write_var_int(transaction.inputs.len() as u64)?;
+        for input in transaction.inputs.iter() {
+            self.write_input(input)?;
+        }
+
+        self.write_var_int(transaction.outputs.len() as u64)?;
+        for output in transaction.outputs.iter() {
+            self.write_output(output)?;
+        }
+
+        if has_witnesses {
+            // Every input has a witness, which may be empty.
+            let empty_script_witness = ScriptWitness { items: vec![] };
+            for index in 0..transaction.inputs.len() {
+                let script_witness = transaction
+                    .script_witnesses
+                    .get(index)
+                    .unwrap_or(&empty_script_witness);
+                self.write_script_witness(script_witness)?;
+            }
+        }
+
+        self.write_u32::<LittleEndian>(transaction.lock_time)
+    }
+}
+
+/// Implement `WriteBlockInternals` for all types that implement `Write`.
+impl<W: Write + ?Sized> WriteBlockInternals for W {
+    fn write_input(&mut self, input: &Input) -> Result<()> {
+        self.write_hash(&input.previous_tx_hash)?;
+        self.write_u32::<LittleEndian>(input.previous_tx_output_index)?;
+        self.write_script(&input.script)?;
+        self.write_u32::<LittleEndian>(input.sequence_number)
+    }
+
+    fn write_output(&mut self, output: &Output) -> Result<()> {
+        self.write_u64::<LittleEndian>(output.value)?;
+        self.write_script(&output.script)
+    }
+
+    fn write_script_witness(&mut self, script_witness: &ScriptWitness) -> Result<()> {
+        self.write_var_int(script_witness.items.len() as u64)?;
+        for item in script_witness.items.iter() {
+            self.write_script(item)?;
+        }
+        Ok(())
+    }
+
+    fn write_hash(&mut self, hash: &Hash) -> Result<()> {
+        let mut bytes = hash.0;
+        bytes.reverse();
+        self.write_all(&bytes)
+    }
+
+    fn write_var_int(&mut self, value: u64) -> Result<()> {
+        if value < 0xFD {
+            self.write_u8(value as u8)
+        } else if value <= 0xFFFF {
+            self.write_u8(0xFD)?;
+            self.write_u16::<LittleEndian>(value as u16)
+        } else if value <= 0xFFFF_FFFF {
+            self.write_u8(0xFE)?;
+            self.write_u32::<LittleEndian>(value as u32)
+        } else {
+            self.write_u8(0xFF)?;
+            self.write_u64::<LittleEndian>(value)
+        }
+    }
+
+    fn write_script(&mut self, script: &[u8]) -> Result<()> {
+        self.write_var_int(script.len() as u64)?;
+        self.write_all(script)
+    }
+}
+
+impl Block {
+    /// Returns the consensus encoding of this block, see
+    /// `WriteBlock::write_block_content`.
+    pub fn to_consensus_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes
+            .write_block_content(self)
+            .expect("writing to a vector does not fail");
+        bytes
+    }
+
+    /// Returns the consensus encoding of this block as hex string.
+    pub fn to_hex(&self) -> String {
+        HEXLOWER.encode(&self.to_consensus_bytes())
+    }
+}
+
+impl Transaction {
+    /// Returns the consensus encoding of this transaction, see
+    /// `WriteBlock::write_transaction`.
+    pub fn to_consensus_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes
+            .write_transaction(self)
+            .expect("writing to a vector does not fail");
+        bytes
+    }
+
+    /// Returns the consensus encoding of this transaction as hex string, e.g.
+    /// as expected by the `sendrawtransaction` RPC of Bitcoin Core.
+    pub fn to_hex(&self) -> String {
+        HEXLOWER.encode(&self.to_consensus_bytes())
+    }
+}
+
+#[cfg(test)]
+mod write_var_int_tests {
+
+    use super::*;
+    use read::ReadBlockInternals;
+    use std::io::Cursor;
+
+    #[test]
+    fn writes_var_ints_that_are_read_back() {
+        for &value in &[0, 0xFC, 0xFD, 0xFFFF, 0x10000, 0xFFFF_FFFF, 0x1_0000_0000] {
+            // given
+            let mut bytes = Vec::new();
+
+            // when
+            bytes.write_var_int(value).unwrap();
+
+            // then
+            assert_eq!(Cursor::new(bytes).read_var_int().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn writes_shortest_encoding() {
+        let mut bytes = Vec::new();
+        bytes.write_var_int(0xFD).unwrap();
+        assert_eq!(bytes, vec![0xFD, 0xFD, 0x00]);
+    }
+}
+
+#[cfg(test)]
+mod write_block_tests {
+
+    use super::*;
+    use read::read_block_tests::block_bytes;
+    use read::ReadBlock;
+    use read_options::ReadOptions;
+    use std::io::Cursor;
+
+    #[test]
+    fn writes_block_that_has_been_read() {
+        // given
+        let bytes = block_bytes();
+        let block = Cursor::new(&bytes)
+            .read_block(0, ReadOptions::new(Network::Mainnet))
+            .unwrap();
+
+        // when
+        let mut written_bytes = Vec::new();
+        written_bytes.write_block(&block, Network::Mainnet).unwrap();
+
+        // then
+        assert_eq!(written_bytes, bytes);
+        assert_eq!(block.to_consensus_bytes(), &bytes[8..]);
+    }
+
+    #[test]
+    fn writes_transactions_with_and_without_witnesses() {
+        // given
+        let bytes = block_bytes();
+        let block = Cursor::new(&bytes)
+            .read_block(0, ReadOptions::new(Network::Mainnet))
+            .unwrap();
+
+        // when
+        let coinbase_bytes = block.transactions[0].to_consensus_bytes();
+        let seg_wit_bytes = block.transactions[1].to_consensus_bytes();
+
+        // then
+        assert_eq!(coinbase_bytes.len(), 204);
+        assert_eq!(&seg_wit_bytes[4..6], &[0x00, 0x01]);
+        assert_eq!(
+            coinbase_bytes.len() + seg_wit_bytes.len(),
+            bytes.len() - 8 - 80 - 1
+        );
+    }
+}
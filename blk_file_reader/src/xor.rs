@@ -0,0 +1,162 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// The name of the file that contains the key which Bitcoin Core uses to
+/// obfuscate the blk and rev files within the same directory.
+pub const XOR_KEY_FILE_NAME: &'static str = "xor.dat";
+
+/// Reads the obfuscation key from the `xor.dat` file within the given
+/// directory.
+///
+/// Since version 28, Bitcoin Core XORs the content of blk and rev files with
+/// the key stored in `blocks/xor.dat`. Returns `None` if the directory does not
+/// contain an `xor.dat` file or if the key consists of zeros only, i.e. if the
+/// files are not obfuscated.
+pub fn read_xor_key(blk_file_dir: &Path) -> io::Result<Option<[u8; 8]>> {
+    let mut file = match File::open(blk_file_dir.join(XOR_KEY_FILE_NAME)) {
+        Ok(file) => file,
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    let mut xor_key = [0u8; 8];
+    file.read_exact(&mut xor_key)?;
+
+    if xor_key.iter().all(|&byte| byte == 0) {
+        Ok(None)
+    } else {
+        Ok(Some(xor_key))
+    }
+}
+
+/// Reads the obfuscation key for the blk or rev file at the given path, see
+/// `read_xor_key`.
+pub(crate) fn read_xor_key_for_file(path_to_file: &str) -> io::Result<Option<[u8; 8]>> {
+    let blk_file_dir = Path::new(path_to_file)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    read_xor_key(blk_file_dir)
+}
+
+/// De-obfuscates the given bytes, which are located at the given `offset`
+/// within their file.
+pub(crate) fn xor(bytes: &mut [u8], xor_key: &[u8; 8], offset: u64) {
+    let key_offset = (offset % 8) as usize;
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte ^= xor_key[(key_offset + i) % 8];
+    }
+}
+
+/// De-obfuscates the bytes read from an underlying reader that starts at the
+/// beginning of an obfuscated file.
+pub(crate) struct XorReader<R> {
+    inner: R,
+    xor_key: Option<[u8; 8]>,
+    position: u64,
+}
+
+impl<R: Read> XorReader<R> {
+    /// Creates a reader that de-obfuscates with the given key, or passes bytes
+    /// through unchanged if there is no key.
+    pub fn new(inner: R, xor_key: Option<[u8; 8]>) -> XorReader<R> {
+        XorReader {
+            inner,
+            xor_key,
+            position: 0,
+        }
+    }
+}
+
+impl<R> XorReader<R> {
+    /// The offset of the next byte to be read within the file.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<R: Read> Read for XorReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let number_of_bytes = self.inner.read(buf)?;
+        if let Some(ref xor_key) = self.xor_key {
+            xor(&mut buf[..number_of_bytes], xor_key, self.position);
+        }
+        self.position += number_of_bytes as u64;
+        Ok(number_of_bytes)
+    }
+}
+
+impl<R: Seek> Seek for XorReader<R> {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(position)?;
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn xor_applies_key_relative_to_offset() {
+        // given
+        let xor_key = [1, 2, 3, 4, 5, 6, 7, 8];
+        let mut bytes = [0u8; 4];
+
+        // when
+        xor(&mut bytes, &xor_key, 6);
+
+        // then
+        assert_eq!(bytes, [7, 8, 1, 2]);
+    }
+
+    #[test]
+    fn xor_reader_de_obfuscates_across_reads() {
+        // given
+        let xor_key = [0xFFu8; 8];
+        let obfuscated = [0xFEu8, 0xFD, 0xFC];
+        let mut reader = XorReader::new(&obfuscated[..], Some(xor_key));
+
+        // when
+        let mut first = [0u8; 1];
+        reader.read_exact(&mut first).unwrap();
+        let mut rest = [0u8; 2];
+        reader.read_exact(&mut rest).unwrap();
+
+        // then
+        assert_eq!(first, [1]);
+        assert_eq!(rest, [2, 3]);
+    }
+
+    #[test]
+    fn xor_reader_without_key_passes_bytes_through() {
+        // given
+        let bytes = [1u8, 2, 3];
+        let mut reader = XorReader::new(&bytes[..], None);
+
+        // when
+        let mut read_bytes = Vec::new();
+        reader.read_to_end(&mut read_bytes).unwrap();
+
+        // then
+        assert_eq!(read_bytes, bytes);
+    }
+
+    #[test]
+    fn xor_reader_de_obfuscates_relative_to_seeked_position() {
+        // given
+        let xor_key = [1, 2, 3, 4, 5, 6, 7, 8];
+        let obfuscated = [0u8; 8];
+        let mut reader = XorReader::new(io::Cursor::new(&obfuscated[..]), Some(xor_key));
+
+        // when
+        reader.seek(SeekFrom::Start(6)).unwrap();
+        let mut read_bytes = Vec::new();
+        reader.read_to_end(&mut read_bytes).unwrap();
+
+        // then
+        assert_eq!(read_bytes, [7, 8]);
+        assert_eq!(reader.position(), 8);
+    }
+}
@@ -0,0 +1,87 @@
+//! # Block Index Test
+//!
+//! Verifies that the best chain is read from a LevelDB block index that
+//! contains the genesis block, which is stored in the obfuscated blk file, and
+//! the header of block 1, whose data has not been downloaded.
+
+extern crate blk_file_reader;
+extern crate data_encoding;
+
+use blk_file_reader::{read_indexed_block, BlockIndex, Hash, ReadOptions};
+use data_encoding::HEXLOWER;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+const PATH_TO_OBFUSCATED_BLK_FILES: &'static str = "tests/fixtures/obfuscated";
+const PATH_TO_BLOCK_INDEX: &'static str = "tests/fixtures/obfuscated/index";
+const GENESIS_BLOCK_HASH: &'static str =
+    "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+const BLOCK_1_HASH: &'static str =
+    "00000000839a8e6886ab5951d76f411475428afc90947ee320161bbf18eb6048";
+
+fn hash(hex: &str) -> Hash {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&HEXLOWER.decode(hex.as_bytes()).unwrap());
+    Hash(hash)
+}
+
+/// Copies the block index fixture into a fresh directory, since LevelDB
+/// rewrites its files when opening a database.
+fn copy_block_index(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("blk_file_reader_{}_{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for file in fs::read_dir(PATH_TO_BLOCK_INDEX).unwrap() {
+        let file = file.unwrap();
+        fs::copy(file.path(), dir.join(file.file_name())).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn reads_all_entries_of_block_index() {
+    // given
+    let path_to_block_index = copy_block_index("reads_all_entries_of_block_index");
+
+    // when
+    let block_index = BlockIndex::open(&path_to_block_index).unwrap();
+
+    // then
+    assert_eq!(block_index.len(), 2);
+    let genesis_block_entry = block_index.get(&hash(GENESIS_BLOCK_HASH)).unwrap();
+    assert_eq!(genesis_block_entry.height, 0);
+    assert_eq!(genesis_block_entry.number_of_transactions, 1);
+    assert_eq!(genesis_block_entry.file_number, Some(0));
+    assert_eq!(genesis_block_entry.data_offset, Some(8));
+    let block_1_entry = block_index.get(&hash(BLOCK_1_HASH)).unwrap();
+    assert_eq!(block_1_entry.height, 1);
+    assert_eq!(block_1_entry.previous_block_hash, hash(GENESIS_BLOCK_HASH));
+    assert!(!block_1_entry.has_data());
+
+    fs::remove_dir_all(&path_to_block_index).unwrap();
+}
+
+#[test]
+fn best_chain_ends_in_last_block_with_data() {
+    // given
+    let path_to_block_index = copy_block_index("best_chain_ends_in_last_block_with_data");
+    let block_index = BlockIndex::open(&path_to_block_index).unwrap();
+
+    // when
+    let best_chain = block_index.best_chain();
+
+    // then
+    assert_eq!(best_chain.len(), 1);
+    assert_eq!(best_chain[0].hash, hash(GENESIS_BLOCK_HASH));
+    let block = read_indexed_block(
+        PATH_TO_OBFUSCATED_BLK_FILES,
+        best_chain[0],
+        ReadOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(block.hash, hash(GENESIS_BLOCK_HASH));
+
+    fs::remove_dir_all(&path_to_block_index).unwrap();
+}
@@ -0,0 +1,65 @@
+//! # Chain Stats Test
+//!
+//! Verifies that the statistics of blocks are collected and merged correctly.
+
+extern crate blk_file_reader;
+
+use blk_file_reader::{read_blocks, ChainStats, Network, ScriptType};
+
+const PATH_TO_BLK_FILE: &'static str = "tests/fixtures/zero_xor_key/blk00000.dat";
+
+#[test]
+fn collects_stats_of_genesis_block() {
+    // given
+    let genesis_block = read_blocks(PATH_TO_BLK_FILE, Network::Mainnet)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let mut stats = ChainStats::new();
+
+    // when
+    stats.add_block(&genesis_block);
+
+    // then
+    assert_eq!(stats.number_of_blocks, 1);
+    assert_eq!(stats.number_of_transactions, 1);
+    assert_eq!(stats.number_of_inputs, 1);
+    assert_eq!(stats.number_of_outputs, 1);
+    assert_eq!(stats.segwit_share(), Some(0.0));
+    assert_eq!(stats.number_of_outputs_of_type(ScriptType::P2PK), 1);
+    assert_eq!(stats.number_of_op_return_outputs(), 0);
+    assert_eq!(stats.block_sizes.max(), Some(285));
+    assert_eq!(stats.block_weights.max(), Some(1140));
+    assert_eq!(stats.transaction_sizes.max(), Some(204));
+    assert_eq!(stats.first_creation_time, Some(1231006505));
+    assert_eq!(stats.last_creation_time, Some(1231006505));
+}
+
+#[test]
+fn merged_stats_equal_stats_of_all_blocks() {
+    // given
+    let genesis_block = read_blocks(PATH_TO_BLK_FILE, Network::Mainnet)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let mut later_block = genesis_block.clone();
+    later_block.creation_time += 600;
+    let mut stats = ChainStats::new();
+    let mut other_stats = ChainStats::new();
+    let mut expected_stats = ChainStats::new();
+
+    // when
+    stats.add_block(&genesis_block);
+    other_stats.add_block(&later_block);
+    stats.merge(&other_stats);
+
+    // then
+    expected_stats.add_block(&genesis_block);
+    expected_stats.add_block(&later_block);
+    assert_eq!(stats, expected_stats);
+    assert_eq!(stats.number_of_blocks, 2);
+    assert_eq!(stats.last_creation_time, Some(1231007105));
+    assert_eq!(ChainStats::new().segwit_share(), None);
+}
@@ -0,0 +1,31 @@
+//! Helpers for the integration tests that write their own blk files.
+
+use std::env;
+use std::fs;
+use std::process;
+
+const PATH_TO_GENESIS_BLK_FILE: &'static str = "tests/fixtures/zero_xor_key/blk00000.dat";
+
+/// The length of the genesis block within its blk file, including its magic
+/// number and size.
+const GENESIS_RECORD_LENGTH: usize = 293;
+
+/// Reads the genesis block from its blk file, including its magic number and
+/// size.
+pub fn read_genesis_record() -> Vec<u8> {
+    let mut genesis_record = fs::read(PATH_TO_GENESIS_BLK_FILE).unwrap();
+    genesis_record.truncate(GENESIS_RECORD_LENGTH);
+    genesis_record
+}
+
+/// Writes a blk file that consists of the given bytes into a directory that is
+/// unique to the given name and the running test process.
+///
+/// Returns the path to the blk file.
+pub fn write_blk_file(name: &str, bytes: &[u8]) -> String {
+    let dir = env::temp_dir().join(format!("blk_file_reader_{}_{}", name, process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("blk00000.dat");
+    fs::write(&path, bytes).unwrap();
+    path.to_str().unwrap().to_owned()
+}
@@ -0,0 +1,243 @@
+//! # Find Test
+//!
+//! Verifies that blocks and transactions are found by hash, both by scanning
+//! the blk files and by looking them up in a hash index, and that the index
+//! notices blocks written to the indexed blk files.
+
+extern crate blk_file_reader;
+
+use blk_file_reader::{
+    find_block, find_transaction, read_blocks, read_located_block, Block, BlockLocation, Hash,
+    HashIndex, Network, ReadOptions, TransactionLocation, WriteBlock,
+};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::process;
+
+const PATH_TO_BLK_FILES: &'static str = "tests/fixtures/obfuscated";
+const PATH_TO_GENESIS_BLK_FILE: &'static str = "tests/fixtures/zero_xor_key/blk00000.dat";
+const GENESIS_BLOCK_HASH: &'static str =
+    "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+const GENESIS_TX_HASH: &'static str =
+    "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+const GENESIS_BLOCK_LOCATION: BlockLocation = BlockLocation {
+    blk_file_index: 0,
+    index_in_blk_file: 0,
+    offset_in_blk_file: 0,
+};
+
+/// The number of zeros Bitcoin Core's preallocation leaves after the blocks of
+/// the written blk files, in this test.
+const PADDING_LENGTH: usize = 4096;
+
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("blk_file_reader_{}_{}", name, process::id()))
+}
+
+/// Derives distinct blocks from the genesis block, each of them with a
+/// distinct coinbase transaction.
+fn derive_blocks(first_nonce: u32, number_of_blocks: u32) -> Vec<Block> {
+    let genesis_block = read_blocks(PATH_TO_GENESIS_BLK_FILE, Network::Mainnet)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    (first_nonce..first_nonce + number_of_blocks)
+        .map(|nonce| {
+            let mut block = genesis_block.clone();
+            block.nonce = nonce;
+            block.transactions[0].lock_time = nonce;
+            block
+        })
+        .collect()
+}
+
+/// Serializes the given blocks followed by zero padding.
+fn blk_file_bytes(blocks: &[Block]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for block in blocks {
+        bytes.write_block(block, Network::Mainnet).unwrap();
+    }
+    bytes.extend_from_slice(&[0u8; PADDING_LENGTH]);
+    bytes
+}
+
+/// Writes a directory of blk files with the given number of derived blocks
+/// each.
+fn write_blk_files(name: &str, number_of_blk_files: u32, blocks_per_blk_file: u32) -> PathBuf {
+    let dir = temp_path(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for blk_file_index in 0..number_of_blk_files {
+        let blocks = derive_blocks(blk_file_index * blocks_per_blk_file, blocks_per_blk_file);
+        let path = dir.join(format!("blk{:05}.dat", blk_file_index));
+        fs::write(path, blk_file_bytes(&blocks)).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn finds_block_by_hash() {
+    // given
+    let hash = Hash::from_hex(GENESIS_BLOCK_HASH).unwrap();
+    // when
+    let (location, block) =
+        find_block(PATH_TO_BLK_FILES, &hash, ReadOptions::new(Network::Mainnet))
+            .unwrap()
+            .unwrap();
+    // then
+    assert_eq!(location, GENESIS_BLOCK_LOCATION);
+    assert_eq!(location.blk_file_name(), "blk00000.dat");
+    assert_eq!(block.hash, hash);
+}
+
+#[test]
+fn finds_transaction_by_hash() {
+    // given
+    let tx_hash = Hash::from_hex(GENESIS_TX_HASH).unwrap();
+    // when
+    let (location, block) = find_transaction(
+        PATH_TO_BLK_FILES,
+        &tx_hash,
+        ReadOptions::new(Network::Mainnet),
+    )
+    .unwrap()
+    .unwrap();
+    // then
+    assert_eq!(
+        location,
+        TransactionLocation {
+            block: GENESIS_BLOCK_LOCATION,
+            index_in_block: 0,
+        }
+    );
+    assert_eq!(block.transactions[location.index_in_block].tx_hash, tx_hash);
+}
+
+#[test]
+fn unknown_hash_is_not_found() {
+    // given
+    let hash = Hash([0u8; 32]);
+    let options = ReadOptions::new(Network::Mainnet);
+    // when
+    let block = find_block(PATH_TO_BLK_FILES, &hash, options).unwrap();
+    let transaction = find_transaction(PATH_TO_BLK_FILES, &hash, options).unwrap();
+    // then
+    assert!(block.is_none());
+    assert!(transaction.is_none());
+}
+
+#[test]
+fn hash_index_locates_blocks_and_transactions() {
+    // given
+    let options = ReadOptions::new(Network::Mainnet);
+    let path_to_index = temp_path("hash_index_locates_blocks_and_transactions.idx");
+    let path_to_index = path_to_index.to_str().unwrap();
+    HashIndex::build(PATH_TO_BLK_FILES, options)
+        .unwrap()
+        .write(path_to_index)
+        .unwrap();
+    // when
+    let hash_index = HashIndex::open(path_to_index).unwrap();
+    let block_location = hash_index.block_location(&Hash::from_hex(GENESIS_BLOCK_HASH).unwrap());
+    let transaction_location =
+        hash_index.transaction_location(&Hash::from_hex(GENESIS_TX_HASH).unwrap());
+    // then
+    assert!(hash_index
+        .is_up_to_date(PATH_TO_BLK_FILES, options)
+        .unwrap());
+    assert_eq!(hash_index.number_of_blocks(), 1);
+    assert_eq!(hash_index.number_of_transactions(), 1);
+    assert_eq!(block_location, Some(GENESIS_BLOCK_LOCATION));
+    assert_eq!(
+        transaction_location.map(|location| location.index_in_block),
+        Some(0)
+    );
+    assert_eq!(hash_index.block_location(&Hash([0u8; 32])), None);
+    let block = read_located_block(PATH_TO_BLK_FILES, &GENESIS_BLOCK_LOCATION, options).unwrap();
+    assert_eq!(block.hash, Hash::from_hex(GENESIS_BLOCK_HASH).unwrap());
+
+    fs::remove_file(path_to_index).unwrap();
+}
+
+#[test]
+fn hash_index_locates_every_block_and_transaction_of_many() {
+    // given
+    let options = ReadOptions::new(Network::Mainnet);
+    let dir = write_blk_files(
+        "hash_index_locates_every_block_and_transaction_of_many",
+        3,
+        7,
+    );
+    let path_to_blk_files = dir.to_str().unwrap();
+
+    // when
+    let hash_index = HashIndex::build(path_to_blk_files, options).unwrap();
+
+    // then
+    assert_eq!(hash_index.number_of_blocks(), 21);
+    assert_eq!(hash_index.number_of_transactions(), 21);
+    for blk_file_index in 0..3 {
+        let path_to_blk_file = dir.join(format!("blk{:05}.dat", blk_file_index));
+        let blocks = read_blocks(path_to_blk_file.to_str().unwrap(), Network::Mainnet).unwrap();
+        for block in blocks {
+            let block = block.unwrap();
+            let location = BlockLocation {
+                blk_file_index,
+                index_in_blk_file: block.index_in_blk_file,
+                offset_in_blk_file: block.offset_in_blk_file,
+            };
+            assert_eq!(hash_index.block_location(&block.hash), Some(location));
+            assert_eq!(
+                hash_index.transaction_location(&block.transactions[0].tx_hash),
+                Some(TransactionLocation {
+                    block: location,
+                    index_in_block: 0,
+                })
+            );
+        }
+    }
+    assert_eq!(hash_index.block_location(&Hash([0u8; 32])), None);
+    assert_eq!(hash_index.transaction_location(&Hash([0xFF; 32])), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn hash_index_is_out_of_date_once_block_is_written_into_padding() {
+    // given
+    let options = ReadOptions::new(Network::Mainnet);
+    let dir = write_blk_files("hash_index_is_out_of_date_once_block_is_written", 2, 2);
+    let path_to_blk_files = dir.to_str().unwrap();
+    let hash_index = HashIndex::build(path_to_blk_files, options).unwrap();
+    assert!(hash_index
+        .is_up_to_date(path_to_blk_files, options)
+        .unwrap());
+
+    // when
+    let path_to_blk_file = dir.join("blk00001.dat");
+    let length = fs::metadata(&path_to_blk_file).unwrap().len();
+    let mut added_block = Vec::new();
+    added_block
+        .write_block(&derive_blocks(100, 1)[0], Network::Mainnet)
+        .unwrap();
+    let mut blk_file = OpenOptions::new()
+        .write(true)
+        .open(&path_to_blk_file)
+        .unwrap();
+    blk_file
+        .seek(SeekFrom::Start(length - PADDING_LENGTH as u64))
+        .unwrap();
+    blk_file.write_all(&added_block).unwrap();
+
+    // then
+    assert_eq!(fs::metadata(&path_to_blk_file).unwrap().len(), length);
+    assert!(!hash_index
+        .is_up_to_date(path_to_blk_files, options)
+        .unwrap());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
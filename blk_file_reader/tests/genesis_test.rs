@@ -5,7 +5,7 @@
 extern crate blk_file_reader;
 extern crate data_encoding;
 
-use blk_file_reader::read_blocks;
+use blk_file_reader::{read_blocks, AddressDerivation, AddressEncoding, Network, ScriptType};
 use data_encoding::HEXLOWER;
 
 const PATH_TO_BLK_FILE_0: &'static str = "../sample-blk-files/blk00000.dat";
@@ -14,7 +14,7 @@ const PATH_TO_BLK_FILE_0: &'static str = "../sample-blk-files/blk00000.dat";
 #[test]
 pub fn can_read_genesis_block() {
     // given
-    let mut blocks = read_blocks(PATH_TO_BLK_FILE_0).unwrap();
+    let mut blocks = read_blocks(PATH_TO_BLK_FILE_0, Network::Mainnet).unwrap();
     // when
     let block = blocks.next().unwrap().unwrap();
     // then
@@ -54,15 +54,26 @@ pub fn can_read_genesis_block() {
     assert_eq!(block.transactions[0].outputs.len(), 1);
     assert_eq!(block.transactions[0].outputs[0].index, 0);
     assert_eq!(block.transactions[0].outputs[0].value, 5_000_000_000);
+    assert_eq!(
+        block.transactions[0].outputs[0].script_type,
+        ScriptType::P2PK
+    );
     assert_eq!(block.transactions[0].weight, 816);
+    assert_eq!(block.weight(), 1140);
+    assert_eq!(block.number_of_segwit_transactions(), 0);
 
     let address = block.transactions[0].outputs[0].address.as_ref().unwrap();
     assert!(block.transactions[0].outputs[0].address.is_some());
     assert_eq!(
-        address.hash.to_vec(),
+        address.payload.as_bytes().to_vec(),
         HEXLOWER
             .decode(b"62e907b15cbf27d5425399ebf6f0fb50ebb88f18")
             .unwrap()
     );
-    assert_eq!(address.base58check, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+    assert_eq!(address.encoded, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+    assert_eq!(address.encoding, AddressEncoding::Base58Check);
+    assert_eq!(
+        block.transactions[0].outputs[0].address_derivation,
+        Some(AddressDerivation::FromPublicKey)
+    );
 }
@@ -0,0 +1,122 @@
+//! # Indexed Read Test
+//!
+//! Verifies that blocks are read from the position recorded in a block index
+//! entry, and that the size of a record is checked before it is read.
+
+extern crate blk_file_reader;
+extern crate data_encoding;
+
+use blk_file_reader::{
+    read_indexed_block, read_indexed_block_undo, read_raw_block, BlockIndexEntry, ChainWork, Hash,
+    Network, ParseError, ReadOptions,
+};
+use data_encoding::HEXLOWER;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::process;
+
+const PATH_TO_OBFUSCATED_BLK_FILES: &'static str = "tests/fixtures/obfuscated";
+const GENESIS_BLOCK_HASH: &'static str =
+    "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+
+fn genesis_block_entry(data_offset: Option<u32>) -> BlockIndexEntry {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&HEXLOWER.decode(GENESIS_BLOCK_HASH.as_bytes()).unwrap());
+    BlockIndexEntry {
+        hash: Hash(hash),
+        height: 0,
+        status: 8,
+        number_of_transactions: 1,
+        file_number: Some(0),
+        data_offset,
+        undo_offset: None,
+        version: 1,
+        previous_block_hash: Hash([0u8; 32]),
+        merkle_root: Hash([0u8; 32]),
+        creation_time: 1231006505,
+        bits: 0x1d00ffff,
+        nonce: 2083236893,
+        chain_work: ChainWork::from_bits(0x1d00ffff),
+    }
+}
+
+#[test]
+fn reads_block_at_data_offset_of_entry() {
+    // given
+    let entry = genesis_block_entry(Some(8));
+
+    // when
+    let block =
+        read_indexed_block(PATH_TO_OBFUSCATED_BLK_FILES, &entry, ReadOptions::default()).unwrap();
+
+    // then
+    assert_eq!(HEXLOWER.encode(&block.hash.0), GENESIS_BLOCK_HASH);
+    assert_eq!(block.transactions.len(), 1);
+}
+
+#[test]
+fn when_entry_has_no_data_then_fails() {
+    // given
+    let entry = genesis_block_entry(None);
+
+    // when
+    let result = read_indexed_block(PATH_TO_OBFUSCATED_BLK_FILES, &entry, ReadOptions::default());
+
+    // then
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::NotFound);
+}
+
+#[test]
+fn when_data_offset_of_entry_precedes_record_header_then_fails() {
+    // given
+    let entry = genesis_block_entry(Some(4));
+
+    // when
+    let result = read_indexed_block(PATH_TO_OBFUSCATED_BLK_FILES, &entry, ReadOptions::default());
+
+    // then
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn when_entry_has_no_undo_data_then_fails() {
+    // given
+    let entry = genesis_block_entry(Some(8));
+
+    // when
+    let result =
+        read_indexed_block_undo(PATH_TO_OBFUSCATED_BLK_FILES, &entry, ReadOptions::default());
+
+    // then
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::NotFound);
+}
+
+#[test]
+fn when_record_size_exceeds_maximum_block_size_then_fails() {
+    // given
+    let dir = env::temp_dir().join(format!(
+        "blk_file_reader_oversized_record_{}",
+        process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("blk00000.dat");
+    let mut bytes = vec![0xF9, 0xBE, 0xB4, 0xD9, 0xFF, 0xFF, 0xFF, 0xFF];
+    bytes.extend_from_slice(&[0u8; 64]);
+    fs::write(&path, bytes).unwrap();
+
+    // when
+    let result = read_raw_block(path.to_str().unwrap(), 0, Network::Mainnet);
+
+    // then
+    let error = result.unwrap_err();
+    assert_eq!(
+        ParseError::from_io_error(&error),
+        Some(&ParseError::InvalidBlockSize {
+            offset: 0,
+            size: 0xFFFF_FFFF,
+        })
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
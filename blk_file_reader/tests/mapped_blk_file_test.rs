@@ -0,0 +1,37 @@
+//! # Mapped Blk File Test
+//!
+//! Verifies that memory-mapped blk files yield the same blocks as the
+//! copying reader.
+
+extern crate blk_file_reader;
+
+use blk_file_reader::{read_blocks, MappedBlkFile, Network, ReadOptions};
+
+const PATH_TO_BLK_FILE_0: &'static str = "../sample-blk-files/blk00000.dat";
+const PATH_TO_SEG_WIT_BLK_FILE: &'static str = "../sample-blk-files/blk01208.dat";
+
+fn assert_mapped_blocks_equal_read_blocks(path: &str, number_of_blocks: usize) {
+    let options = ReadOptions::new(Network::Mainnet);
+    let blk_file = MappedBlkFile::open(path, options).unwrap();
+    let mapped_blocks = blk_file.blocks().take(number_of_blocks);
+    let blocks = read_blocks(path, Network::Mainnet)
+        .unwrap()
+        .take(number_of_blocks);
+
+    let mut block_counter = 0;
+    for (mapped_block, block) in mapped_blocks.zip(blocks) {
+        assert_eq!(mapped_block.unwrap().to_block(options), block.unwrap());
+        block_counter += 1;
+    }
+    assert_eq!(block_counter, number_of_blocks);
+}
+
+#[test]
+fn mapped_blocks_equal_read_blocks() {
+    assert_mapped_blocks_equal_read_blocks(PATH_TO_BLK_FILE_0, 100);
+}
+
+#[test]
+fn mapped_seg_wit_blocks_equal_read_blocks() {
+    assert_mapped_blocks_equal_read_blocks(PATH_TO_SEG_WIT_BLK_FILE, 10);
+}
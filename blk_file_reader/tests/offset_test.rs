@@ -0,0 +1,92 @@
+//! # Offset Test
+//!
+//! Verifies that the offsets of blocks within their blk file are recorded and
+//! can be used to resume reading or to fetch a single block.
+
+extern crate blk_file_reader;
+
+mod common;
+
+use blk_file_reader::{read_blocks, read_raw_block, MappedBlkFile, Network, ReadOptions};
+use common::{read_genesis_record, write_blk_file};
+
+/// Writes a blk file that contains the genesis block twice.
+fn write_genesis_blk_file(name: &str) -> String {
+    let genesis_record = read_genesis_record();
+    let mut bytes = genesis_record.clone();
+    bytes.extend_from_slice(&genesis_record);
+    write_blk_file(name, &bytes)
+}
+
+#[test]
+fn records_offset_and_size_of_blocks() {
+    // given
+    let path = write_genesis_blk_file("offsets");
+
+    // when
+    let blocks: Vec<_> = read_blocks(&path, Network::Mainnet)
+        .unwrap()
+        .map(|block| block.unwrap())
+        .collect();
+
+    // then
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].offset_in_blk_file, 0);
+    assert_eq!(blocks[0].size_in_bytes, 285);
+    assert_eq!(blocks[1].offset_in_blk_file, 293);
+    assert_eq!(blocks[0].end_offset_in_blk_file(), 293);
+}
+
+#[test]
+fn mapped_blocks_record_the_same_offsets() {
+    // given
+    let path = write_genesis_blk_file("mapped_offsets");
+    let options = ReadOptions::new(Network::Mainnet);
+    let blk_file = MappedBlkFile::open(&path, options).unwrap();
+
+    // when
+    let offsets: Vec<_> = blk_file
+        .blocks()
+        .map(|block| {
+            let block = block.unwrap();
+            (block.offset_in_blk_file, block.size_in_bytes)
+        })
+        .collect();
+
+    // then
+    assert_eq!(offsets, vec![(0, 285), (293, 285)]);
+}
+
+#[test]
+fn seek_to_offset_resumes_at_block() {
+    // given
+    let path = write_genesis_blk_file("seek");
+    let mut blocks = read_blocks(&path, Network::Mainnet).unwrap();
+    let second_block = blocks.nth(1).unwrap().unwrap();
+
+    // when
+    let mut blocks = read_blocks(&path, Network::Mainnet).unwrap();
+    blocks.seek_to_offset(293, 1).unwrap();
+
+    // then
+    assert_eq!(blocks.next().unwrap().unwrap(), second_block);
+    assert!(blocks.next().is_none());
+}
+
+#[test]
+fn reads_raw_block_at_offset() {
+    // given
+    let path = write_genesis_blk_file("raw");
+    let block = read_blocks(&path, Network::Mainnet)
+        .unwrap()
+        .nth(1)
+        .unwrap()
+        .unwrap();
+
+    // when
+    let raw_block = read_raw_block(&path, block.offset_in_blk_file, Network::Mainnet).unwrap();
+
+    // then
+    assert_eq!(raw_block.len(), block.size_in_bytes as usize);
+    assert_eq!(&raw_block[..], &block.to_consensus_bytes()[..]);
+}
@@ -0,0 +1,66 @@
+//! Tests for the `par_read_blocks` function.
+
+extern crate blk_file_reader;
+extern crate rayon;
+
+use blk_file_reader::{blk_file_index, par_read_blocks, read_blocks, Network, ReadOptions};
+use rayon::prelude::*;
+
+const PATH_TO_BLK_FILES: &'static str = "../sample-blk-files";
+
+#[test]
+fn reads_same_blocks_as_sequential_reader() {
+    // Given
+    let blocks = par_read_blocks(PATH_TO_BLK_FILES, ReadOptions::new(Network::Mainnet)).unwrap();
+    // When
+    let mut block_hashes: Vec<(usize, Vec<u8>)> = blocks
+        .map(|(blk_file_index, block)| (blk_file_index, block.unwrap().hash.0.to_vec()))
+        .collect();
+    // Then
+    let mut expected_block_hashes = Vec::new();
+    for blk_file in blk_file_reader::read_blk_files(PATH_TO_BLK_FILES).unwrap() {
+        let index = blk_file_index(&blk_file).unwrap();
+        for block in read_blocks(&blk_file, Network::Mainnet).unwrap() {
+            expected_block_hashes.push((index, block.unwrap().hash.0.to_vec()));
+        }
+    }
+    block_hashes.sort();
+    expected_block_hashes.sort();
+    assert_eq!(block_hashes, expected_block_hashes);
+}
+
+#[test]
+fn can_limit_parallelism() {
+    // Given
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    let blocks = par_read_blocks(PATH_TO_BLK_FILES, ReadOptions::new(Network::Mainnet)).unwrap();
+    // When
+    let number_of_genesis_blocks = thread_pool.install(|| {
+        blocks
+            .filter(|&(_, ref block)| {
+                block
+                    .as_ref()
+                    .unwrap()
+                    .previous_block_hash
+                    .0
+                    .iter()
+                    .all(|&b| b == 0)
+            })
+            .count()
+    });
+    // Then
+    assert_eq!(number_of_genesis_blocks, 1);
+}
+
+#[test]
+fn determines_blk_file_index_from_file_name() {
+    assert_eq!(
+        blk_file_index("../sample-blk-files/blk01208.dat"),
+        Some(1208)
+    );
+    assert_eq!(blk_file_index("blk00000.dat"), Some(0));
+    assert_eq!(blk_file_index("rev00000.dat"), None);
+}
@@ -0,0 +1,108 @@
+//! # Resync Test
+//!
+//! Verifies that corrupt data within blk files is reported as `ParseError`s
+//! and skipped when resynchronizing to the next magic number.
+
+extern crate blk_file_reader;
+
+mod common;
+
+use blk_file_reader::{read_blocks_with_options, MappedBlkFile, Network, ParseError, ReadOptions};
+use common::{read_genesis_record, write_blk_file};
+use std::io;
+
+const MAINNET_MAGIC_NUMBER: [u8; 4] = [0xF9, 0xBE, 0xB4, 0xD9];
+
+/// Writes a blk file that starts with garbage, followed by the genesis block,
+/// a block of invalid size, the genesis block again and zero-padding.
+fn write_corrupt_blk_file(name: &str) -> String {
+    let genesis_record = read_genesis_record();
+
+    let mut bytes = vec![0xAB; 5];
+    bytes.extend_from_slice(&genesis_record);
+    bytes.extend_from_slice(&MAINNET_MAGIC_NUMBER);
+    bytes.extend_from_slice(&[0x40, 0x4B, 0x4C, 0x00]); // 5,000,000 bytes
+    bytes.extend_from_slice(&genesis_record);
+    bytes.extend_from_slice(&[0u8; 64]);
+    write_blk_file(name, &bytes)
+}
+
+fn resync_options() -> ReadOptions {
+    ReadOptions {
+        resync: true,
+        ..ReadOptions::new(Network::Mainnet)
+    }
+}
+
+fn skipped_range(error: &io::Error) -> (u64, u64) {
+    match ParseError::from_io_error(error) {
+        Some(&ParseError::Skipped { offset, length, .. }) => (offset, length),
+        other => panic!("expected skipped range, got {:?}", other),
+    }
+}
+
+#[test]
+fn reports_parse_error_with_offset_without_resync() {
+    // given
+    let path = write_corrupt_blk_file("without_resync");
+    let mut blocks = read_blocks_with_options(&path, ReadOptions::new(Network::Mainnet)).unwrap();
+
+    // when
+    let error = blocks.next().unwrap().unwrap_err();
+
+    // then
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        ParseError::from_io_error(&error),
+        Some(&ParseError::InvalidMagicNumber {
+            offset: 0,
+            magic_number: 0xABABABAB,
+            network: Network::Mainnet,
+        })
+    );
+}
+
+#[test]
+fn resync_skips_corrupt_byte_ranges() {
+    // given
+    let path = write_corrupt_blk_file("resync");
+    let blocks = read_blocks_with_options(&path, resync_options()).unwrap();
+
+    // when
+    let items: Vec<io::Result<_>> = blocks.collect();
+
+    // then
+    assert_eq!(items.len(), 4);
+    assert_eq!(skipped_range(items[0].as_ref().unwrap_err()), (0, 5));
+    assert_eq!(items[1].as_ref().unwrap().index_in_blk_file, 0);
+    let second_skipped = items[2].as_ref().unwrap_err();
+    assert_eq!(skipped_range(second_skipped), (298, 8));
+    match ParseError::from_io_error(second_skipped) {
+        Some(&ParseError::Skipped { ref cause, .. }) => assert_eq!(
+            **cause,
+            ParseError::InvalidBlockSize {
+                offset: 298,
+                size: 5_000_000,
+            }
+        ),
+        other => panic!("expected skipped range, got {:?}", other),
+    }
+    assert_eq!(items[3].as_ref().unwrap().index_in_blk_file, 1);
+}
+
+#[test]
+fn mapped_resync_skips_corrupt_byte_ranges() {
+    // given
+    let path = write_corrupt_blk_file("mapped_resync");
+    let blk_file = MappedBlkFile::open(&path, resync_options()).unwrap();
+
+    // when
+    let items: Vec<io::Result<_>> = blk_file.blocks().collect();
+
+    // then
+    assert_eq!(items.len(), 4);
+    assert_eq!(skipped_range(items[0].as_ref().unwrap_err()), (0, 5));
+    assert!(items[1].is_ok());
+    assert_eq!(skipped_range(items[2].as_ref().unwrap_err()), (298, 8));
+    assert!(items[3].is_ok());
+}
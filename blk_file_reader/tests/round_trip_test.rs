@@ -0,0 +1,51 @@
+//! # Round Trip Test
+//!
+//! Verifies that blocks which are written by `WriteBlock` are identical to the
+//! blocks in the blk file they have been read from, and that blocks can be
+//! parsed from their consensus encoding.
+
+extern crate blk_file_reader;
+
+use blk_file_reader::{read_blocks, Block, Network, ReadOptions, WriteBlock};
+use std::fs;
+
+const PATH_TO_BLK_FILE: &'static str = "tests/fixtures/zero_xor_key/blk00000.dat";
+
+#[test]
+fn written_blk_file_equals_original_blk_file() {
+    // given
+    let original_bytes = fs::read(PATH_TO_BLK_FILE).unwrap();
+    let blocks = read_blocks(PATH_TO_BLK_FILE, Network::Mainnet).unwrap();
+
+    // when
+    let mut written_bytes = Vec::new();
+    for block in blocks {
+        written_bytes
+            .write_block(&block.unwrap(), Network::Mainnet)
+            .unwrap();
+    }
+
+    // then
+    // The original blk file is padded with zeros after its last block.
+    assert_eq!(&original_bytes[..written_bytes.len()], &written_bytes[..]);
+    assert!(original_bytes[written_bytes.len()..]
+        .iter()
+        .all(|&byte| byte == 0));
+}
+
+#[test]
+fn block_parsed_from_hex_equals_block_read_from_blk_file() {
+    // given
+    let block = read_blocks(PATH_TO_BLK_FILE, Network::Mainnet)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+
+    // when
+    let parsed_block =
+        Block::from_hex(&block.to_hex(), ReadOptions::new(Network::Mainnet)).unwrap();
+
+    // then
+    assert_eq!(parsed_block, block);
+}
@@ -5,7 +5,7 @@
 extern crate blk_file_reader;
 extern crate data_encoding;
 
-use blk_file_reader::read_blocks;
+use blk_file_reader::{read_blocks, Network};
 use data_encoding::HEXLOWER;
 
 const PATH_TO_SEG_WIT_BLK_FILE: &'static str = "../sample-blk-files/blk01208.dat";
@@ -14,7 +14,7 @@ const PATH_TO_SEG_WIT_BLK_FILE: &'static str = "../sample-blk-files/blk01208.dat
 #[test]
 pub fn can_read_seg_wit_block() {
     // given
-    let mut blocks = read_blocks(PATH_TO_SEG_WIT_BLK_FILE).unwrap();
+    let mut blocks = read_blocks(PATH_TO_SEG_WIT_BLK_FILE, Network::Mainnet).unwrap();
     // when
     let block = blocks.next().unwrap().unwrap();
     // then
@@ -44,4 +44,5 @@ pub fn can_read_seg_wit_block() {
         "697abd7cd319e30a83e887aad86a91ce06eab915025ea83d694cbab708aded31"
     );
     assert_eq!(block.transactions[19].weight, 766);
+    assert_eq!(block.transactions[19].virtual_size(), 192);
 }
@@ -1,7 +1,7 @@
 extern crate blk_file_reader;
 extern crate data_encoding;
 
-use blk_file_reader::read_blocks;
+use blk_file_reader::{read_blocks, Network};
 use data_encoding::HEXLOWER;
 
 const PATH_TO_BLK_FILE_0: &'static str = "../sample-blk-files/blk00000.dat";
@@ -9,7 +9,7 @@ const PATH_TO_BLK_FILE_0: &'static str = "../sample-blk-files/blk00000.dat";
 #[test]
 fn skip_0_skips_no_blocks() {
     // given
-    let blocks = read_blocks(PATH_TO_BLK_FILE_0).unwrap();
+    let blocks = read_blocks(PATH_TO_BLK_FILE_0, Network::Mainnet).unwrap();
     // when skip is called with `0`
     let mut blocks = blocks.skip(0);
     // then `read()` returns the genesis block
@@ -23,7 +23,7 @@ fn skip_0_skips_no_blocks() {
 #[test]
 fn skip_1_skips_one_block() {
     // given
-    let blocks = read_blocks(PATH_TO_BLK_FILE_0).unwrap();
+    let blocks = read_blocks(PATH_TO_BLK_FILE_0, Network::Mainnet).unwrap();
     // when skip is called with `1`
     let mut blocks = blocks.skip(1);
     // then `read()` returns the successor of the genesis block
@@ -0,0 +1,84 @@
+//! # XOR Test
+//!
+//! Verifies that blk files which have been obfuscated by Bitcoin Core with the
+//! key stored in `xor.dat` are read correctly.
+
+extern crate blk_file_reader;
+extern crate data_encoding;
+
+use blk_file_reader::{read_blocks, read_xor_key, MappedBlkFile, Network, ReadOptions};
+use data_encoding::HEXLOWER;
+use std::path::Path;
+
+const PATH_TO_OBFUSCATED_BLK_FILE: &'static str = "tests/fixtures/obfuscated/blk00000.dat";
+const PATH_TO_ZERO_XOR_KEY_BLK_FILE: &'static str = "tests/fixtures/zero_xor_key/blk00000.dat";
+const GENESIS_BLOCK_HASH: &'static str =
+    "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+
+#[test]
+fn reads_xor_key() {
+    // given
+    let path = Path::new("tests/fixtures/obfuscated");
+    // when
+    let xor_key = read_xor_key(path).unwrap();
+    // then
+    assert_eq!(
+        xor_key,
+        Some([0x3c, 0x7a, 0x1f, 0x5e, 0x9b, 0x02, 0xd8, 0x64])
+    );
+}
+
+#[test]
+fn zero_xor_key_is_ignored() {
+    // given
+    let path = Path::new("tests/fixtures/zero_xor_key");
+    // when
+    let xor_key = read_xor_key(path).unwrap();
+    // then
+    assert!(xor_key.is_none());
+}
+
+#[test]
+fn can_read_obfuscated_blk_file() {
+    // given
+    let blocks = read_blocks(PATH_TO_OBFUSCATED_BLK_FILE, Network::Mainnet).unwrap();
+    // when
+    let blocks: Vec<_> = blocks.collect();
+    // then
+    assert_eq!(blocks.len(), 1);
+    let block = blocks[0].as_ref().unwrap();
+    assert_eq!(HEXLOWER.encode(&block.hash.0), GENESIS_BLOCK_HASH);
+    assert_eq!(
+        block.transactions[0].outputs[0]
+            .address
+            .as_ref()
+            .unwrap()
+            .encoded,
+        "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+    );
+}
+
+#[test]
+fn can_read_obfuscated_blk_file_via_memory_map() {
+    // given
+    let options = ReadOptions::new(Network::Mainnet);
+    let blk_file = MappedBlkFile::open(PATH_TO_OBFUSCATED_BLK_FILE, options).unwrap();
+    // when
+    let blocks: Vec<_> = blk_file.blocks().collect();
+    // then
+    assert_eq!(blocks.len(), 1);
+    let block = blocks[0].as_ref().unwrap();
+    assert_eq!(HEXLOWER.encode(&block.hash.0), GENESIS_BLOCK_HASH);
+}
+
+#[test]
+fn can_read_blk_file_with_zero_xor_key() {
+    // given
+    let blocks = read_blocks(PATH_TO_ZERO_XOR_KEY_BLK_FILE, Network::Mainnet).unwrap();
+    // when
+    let blocks: Vec<_> = blocks.collect();
+    // then
+    assert_eq!(blocks.len(), 1);
+    let block = blocks[0].as_ref().unwrap();
+    assert_eq!(HEXLOWER.encode(&block.hash.0), GENESIS_BLOCK_HASH);
+}
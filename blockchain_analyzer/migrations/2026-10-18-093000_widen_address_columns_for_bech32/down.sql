@@ -0,0 +1,41 @@
+-- Fails if Bech32 or Bech32m addresses have already been imported.
+DROP VIEW resolved_senders;
+DROP VIEW resolved_receivers;
+DROP VIEW resolved_inputs;
+
+ALTER TABLE output_addresses ALTER COLUMN base58check TYPE VARCHAR(36);
+ALTER TABLE addresses ALTER COLUMN base58check TYPE VARCHAR(36);
+
+CREATE VIEW resolved_inputs AS
+    SELECT oa.base58check,
+        o.value,
+        i.id,
+        i.sequence_number,
+        i.previous_tx_hash,
+        i.previous_tx_output_index,
+        i.script,
+        i.transaction_id
+   FROM inputs i
+        JOIN transactions tx ON i.previous_tx_hash = tx.hash
+        JOIN outputs o ON o.transaction_id = tx.id AND o.output_index = i.previous_tx_output_index
+        JOIN output_addresses oa ON oa.output_id = o.id;
+
+CREATE VIEW resolved_receivers AS
+    SELECT r.base58check AS sender_base58check,
+        oa.base58check AS receiver_base58check,
+        o.value AS sent_value,
+        r.transaction_id
+    FROM resolved_inputs r
+         JOIN transactions t ON t.id = r.transaction_id
+         JOIN outputs o ON r.transaction_id = o.transaction_id
+         JOIN output_addresses oa ON oa.output_id = o.id;
+
+CREATE VIEW resolved_senders AS
+    SELECT r.base58check AS sender_base58check,
+        oa.base58check AS receiver_base58check,
+        r.value AS received_value,
+        t.id AS transaction_id
+    FROM output_addresses oa
+        JOIN outputs o ON o.id = oa.output_id
+        JOIN transactions t ON t.id = o.transaction_id
+        JOIN resolved_inputs r ON r.transaction_id = t.id;
@@ -0,0 +1 @@
+ALTER TABLE output_addresses DROP COLUMN witness_version;
@@ -0,0 +1 @@
+ALTER TABLE output_addresses ADD witness_version SMALLINT;
@@ -0,0 +1 @@
+ALTER TABLE outputs DROP COLUMN script_type;
@@ -0,0 +1 @@
+ALTER TABLE outputs ADD script_type VARCHAR(32);
@@ -0,0 +1,8 @@
+CREATE TABLE multisig_participants (
+    id BIGSERIAL PRIMARY KEY,
+    output_id BIGINT NOT NULL REFERENCES outputs (id),
+    participant_index SMALLINT NOT NULL,
+    required_signatures SMALLINT NOT NULL,
+    public_key BYTEA NOT NULL,
+    base58check VARCHAR(36) NOT NULL
+)
@@ -0,0 +1,2 @@
+ALTER TABLE blocks DROP COLUMN size_in_bytes;
+ALTER TABLE blocks DROP COLUMN offset_in_blk_file;
@@ -0,0 +1,2 @@
+ALTER TABLE blocks ADD offset_in_blk_file BIGINT;
+ALTER TABLE blocks ADD size_in_bytes INT;
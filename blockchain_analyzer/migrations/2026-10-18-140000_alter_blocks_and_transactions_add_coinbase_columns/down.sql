@@ -0,0 +1,3 @@
+ALTER TABLE transactions DROP COLUMN is_coinbase;
+ALTER TABLE blocks DROP COLUMN coinbase_tag;
+ALTER TABLE blocks DROP COLUMN bip34_height;
@@ -0,0 +1,3 @@
+ALTER TABLE blocks ADD bip34_height INT;
+ALTER TABLE blocks ADD coinbase_tag TEXT;
+ALTER TABLE transactions ADD is_coinbase BOOLEAN;
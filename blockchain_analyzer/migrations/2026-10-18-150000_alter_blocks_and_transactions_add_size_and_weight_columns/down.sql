@@ -0,0 +1,5 @@
+ALTER TABLE transactions DROP COLUMN virtual_size;
+ALTER TABLE transactions DROP COLUMN stripped_size_in_bytes;
+ALTER TABLE blocks DROP COLUMN number_of_segwit_transactions;
+ALTER TABLE blocks DROP COLUMN weight;
+ALTER TABLE blocks DROP COLUMN stripped_size_in_bytes;
@@ -0,0 +1,5 @@
+ALTER TABLE blocks ADD stripped_size_in_bytes INT;
+ALTER TABLE blocks ADD weight INT;
+ALTER TABLE blocks ADD number_of_segwit_transactions INT;
+ALTER TABLE transactions ADD stripped_size_in_bytes INT;
+ALTER TABLE transactions ADD virtual_size INT;
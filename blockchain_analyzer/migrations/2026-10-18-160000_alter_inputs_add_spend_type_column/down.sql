@@ -0,0 +1 @@
+ALTER TABLE inputs DROP COLUMN spend_type;
@@ -0,0 +1 @@
+ALTER TABLE inputs ADD spend_type VARCHAR(32);
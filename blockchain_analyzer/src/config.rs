@@ -1,6 +1,11 @@
+use blk_file_reader::{Network, ReadOptions};
 use dotenv::dotenv;
 use failure::Error;
 use std::env;
+use std::error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 /// Analysis suite configuration
 ///
@@ -11,10 +16,15 @@ pub struct Config {
     pub db_url: String,
     pub max_db_connections: u32,
     pub blk_file_path: String,
+    pub network: Network,
     pub unresolved_bir_file_path: String,
     pub resolved_bir_file_path: String,
     pub bir_construction_state_file_path: String,
     pub load_addresses_into_memory: bool,
+    pub input_resolution: InputResolution,
+    pub validate_blocks: bool,
+    pub resync: bool,
+    pub block_order: BlockOrder,
 }
 
 impl Config {
@@ -25,10 +35,15 @@ impl Config {
             db_url: env::var("DATABASE_URL")?,
             max_db_connections: env::var("MAX_DB_CONNECTIONS")?.parse()?,
             blk_file_path: env::var("BLK_FILE_PATH")?,
+            network: load_network("NETWORK")?,
             unresolved_bir_file_path: env::var("UNRESOLVED_BIR_FILE_PATH")?,
             resolved_bir_file_path: env::var("RESOLVED_BIR_FILE_PATH")?,
             bir_construction_state_file_path: env::var("BIR_CONSTRUCTION_STATE_FILE_PATH")?,
             load_addresses_into_memory: env::var("LOAD_ADDRESSES_INTO_MEMORY")?.parse()?,
+            input_resolution: load_input_resolution()?,
+            validate_blocks: load_validate_blocks()?,
+            resync: load_resync()?,
+            block_order: load_block_order()?,
         };
 
         Ok(config)
@@ -41,12 +56,163 @@ impl Config {
             db_url: env::var("TEST_DATABASE_URL")?,
             max_db_connections: env::var("MAX_DB_CONNECTIONS")?.parse()?,
             blk_file_path: env::var("TEST_BLK_FILE_PATH")?,
+            network: load_network("TEST_NETWORK")?,
             unresolved_bir_file_path: env::var("UNRESOLVED_BIR_FILE_PATH")?,
             resolved_bir_file_path: env::var("RESOLVED_BIR_FILE_PATH")?,
             bir_construction_state_file_path: env::var("TEST_BIR_CONSTRUCTION_STATE_FILE_PATH")?,
             load_addresses_into_memory: env::var("LOAD_ADDRESSES_INTO_MEMORY")?.parse()?,
+            input_resolution: load_input_resolution()?,
+            validate_blocks: load_validate_blocks()?,
+            resync: load_resync()?,
+            block_order: load_block_order()?,
         };
 
         Ok(config)
     }
+
+    /// Returns the path to Bitcoin Core's block index, which is expected to be
+    /// located next to the blk files.
+    pub fn block_index_path(&self) -> PathBuf {
+        Path::new(&self.blk_file_path).join("index")
+    }
+
+    /// Returns the options for reading the blocks of the configured blk files.
+    pub fn read_options(&self) -> ReadOptions {
+        ReadOptions {
+            validate_blocks: self.validate_blocks,
+            resync: self.resync,
+            ..ReadOptions::new(self.network)
+        }
+    }
+}
+
+/// Loads the optional network from the given variable, defaulting to
+/// `Network::Mainnet`.
+fn load_network(var: &str) -> Result<Network, Error> {
+    match env::var(var) {
+        Ok(network) => Ok(network.parse()?),
+        Err(env::VarError::NotPresent) => Ok(Network::Mainnet),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Loads the optional input resolution, defaulting to `InputResolution::UtxoCache`.
+fn load_input_resolution() -> Result<InputResolution, Error> {
+    match env::var("INPUT_RESOLUTION") {
+        Ok(input_resolution) => Ok(input_resolution.parse()?),
+        Err(env::VarError::NotPresent) => Ok(InputResolution::UtxoCache),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Loads whether blocks should be validated when imported, defaulting to `false`.
+fn load_validate_blocks() -> Result<bool, Error> {
+    match env::var("VALIDATE_BLOCKS") {
+        Ok(validate_blocks) => Ok(validate_blocks.parse()?),
+        Err(env::VarError::NotPresent) => Ok(false),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Loads whether corrupt data within blk files should be skipped, defaulting
+/// to `false`.
+fn load_resync() -> Result<bool, Error> {
+    match env::var("RESYNC") {
+        Ok(resync) => Ok(resync.parse()?),
+        Err(env::VarError::NotPresent) => Ok(false),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Loads the optional block order, defaulting to `BlockOrder::PreviousBlockHash`.
+fn load_block_order() -> Result<BlockOrder, Error> {
+    match env::var("BLOCK_ORDER") {
+        Ok(block_order) => Ok(block_order.parse()?),
+        Err(env::VarError::NotPresent) => Ok(BlockOrder::PreviousBlockHash),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Determines how the addresses and values of the outputs spent by inputs are
+/// resolved when constructing the bir.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputResolution {
+    /// Blocks are ordered by buffering them in memory and spent outputs are
+    /// looked up in a cache of unspent outputs, falling back to the DB. Both
+    /// are persisted in the bir construction state file.
+    UtxoCache,
+    /// Blocks are read in the order of the best chain of Bitcoin Core's block
+    /// index and spent outputs are read from its rev files, which requires
+    /// the `index` directory and the rev files to be located next to the blk
+    /// files.
+    UndoFiles,
 }
+
+impl FromStr for InputResolution {
+    type Err = UnknownInputResolutionError;
+
+    fn from_str(name: &str) -> Result<InputResolution, UnknownInputResolutionError> {
+        match name.to_lowercase().as_str() {
+            "utxo_cache" => Ok(InputResolution::UtxoCache),
+            "undo_files" => Ok(InputResolution::UndoFiles),
+            _ => Err(UnknownInputResolutionError(String::from(name))),
+        }
+    }
+}
+
+/// Returned when parsing an `InputResolution` from an unknown name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownInputResolutionError(pub String);
+
+impl fmt::Display for UnknownInputResolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown input resolution {} (expected utxo_cache or undo_files)",
+            self.0
+        )
+    }
+}
+
+impl error::Error for UnknownInputResolutionError {}
+
+/// Determines how the heights of blocks and the order in which blocks are
+/// added to the bir are calculated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockOrder {
+    /// Blocks are linked via their previous block hash, starting at the
+    /// genesis block. Stale blocks are assigned a height as well.
+    PreviousBlockHash,
+    /// Only the blocks of the best chain of Bitcoin Core's block index are
+    /// ordered, which requires the `index` directory to be located next to the
+    /// blk files. Stale blocks are not assigned a height.
+    BlockIndex,
+}
+
+impl FromStr for BlockOrder {
+    type Err = UnknownBlockOrderError;
+
+    fn from_str(name: &str) -> Result<BlockOrder, UnknownBlockOrderError> {
+        match name.to_lowercase().as_str() {
+            "previous_block_hash" => Ok(BlockOrder::PreviousBlockHash),
+            "block_index" => Ok(BlockOrder::BlockIndex),
+            _ => Err(UnknownBlockOrderError(String::from(name))),
+        }
+    }
+}
+
+/// Returned when parsing a `BlockOrder` from an unknown name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownBlockOrderError(pub String);
+
+impl fmt::Display for UnknownBlockOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown block order {} (expected previous_block_hash or block_index)",
+            self.0
+        )
+    }
+}
+
+impl error::Error for UnknownBlockOrderError {}
@@ -1,4 +1,6 @@
-use diesel::{self, dsl::max, pg::PgConnection, QueryDsl, RunQueryDsl};
+use diesel::{
+    self, dsl::max, pg::PgConnection, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl,
+};
 use schema::blocks::dsl::*;
 use std::result::Result;
 
@@ -14,6 +16,18 @@ pub struct Block {
     pub nonce: i32,
     pub height: Option<i32>,
     pub blk_file_id: i64,
+    /// The offset of the block within its blk file, which is missing for
+    /// blocks imported before offsets have been recorded.
+    pub offset_in_blk_file: Option<i64>,
+    pub size_in_bytes: Option<i32>,
+    /// The height the block commits to in its coinbase script, see BIP 34.
+    pub bip34_height: Option<i32>,
+    pub coinbase_tag: Option<String>,
+    /// Missing for blocks imported before sizes and weights have been
+    /// recorded.
+    pub stripped_size_in_bytes: Option<i32>,
+    pub weight: Option<i32>,
+    pub number_of_segwit_transactions: Option<i32>,
 }
 
 impl Block {
@@ -27,6 +41,27 @@ impl Block {
         blocks.select(max(height)).first(db_connection)
     }
 
+    /// The offset within its blk file right after this block, if its offset
+    /// has been recorded.
+    pub fn end_offset_in_blk_file(&self) -> Option<u64> {
+        match (self.offset_in_blk_file, self.size_in_bytes) {
+            (Some(offset), Some(size)) => Some(offset as u64 + 8 + size as u64),
+            _ => None,
+        }
+    }
+
+    /// Read the block that has been imported last from the given blk file.
+    pub fn read_last_of_blk_file(
+        db_connection: &PgConnection,
+        parent_blk_file_id: i64,
+    ) -> Result<Option<Block>, diesel::result::Error> {
+        blocks
+            .filter(blk_file_id.eq(parent_blk_file_id))
+            .order(id.desc())
+            .first(db_connection)
+            .optional()
+    }
+
     /// Read all blocks, ordered by id.
     pub fn read_all(db_connection: &PgConnection) -> Result<Vec<Block>, diesel::result::Error> {
         // TODO Return error instead of panicking.
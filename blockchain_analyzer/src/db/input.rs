@@ -1,3 +1,5 @@
+use blk_file_reader;
+
 #[derive(Queryable)]
 pub struct Input {
     pub id: i64,
@@ -6,4 +8,40 @@ pub struct Input {
     pub previous_tx_output_index: i32,
     pub script: Vec<u8>,
     pub transaction_id: i64,
+    /// How the input spends its output, see `blk_file_reader::SpendType`.
+    pub spend_type: Option<String>,
+}
+
+impl Input {
+    /// Returns the script of this input in ASM notation, see
+    /// `blk_file_reader::input_script_to_asm`.
+    pub fn script_asm(&self) -> String {
+        blk_file_reader::input_script_to_asm(&self.script)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn derives_script_asm_from_script() {
+        // Given
+        let input = Input {
+            id: 1,
+            sequence_number: -1,
+            previous_tx_hash: vec![0u8; 32],
+            previous_tx_output_index: 0,
+            script: vec![0x04, 0xFF, 0xFF, 0x00, 0x1D, 0x01, 0x04],
+            transaction_id: 1,
+            spend_type: None,
+        };
+
+        // When
+        let script_asm = input.script_asm();
+
+        // Then
+        assert_eq!(script_asm, "486604799 4");
+    }
 }
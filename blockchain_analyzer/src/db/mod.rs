@@ -5,10 +5,12 @@ mod blk_file;
 mod block;
 mod cluster_assignment;
 mod input;
+mod multisig_participant;
 mod new_address;
 mod new_blk_file;
 mod new_block;
 mod new_input;
+mod new_multisig_participant;
 mod new_output;
 mod new_output_address;
 mod new_script_witness_item;
@@ -23,10 +25,12 @@ pub use self::blk_file::BlkFile;
 pub use self::block::Block;
 pub use self::cluster_assignment::ClusterAssignment;
 pub use self::input::Input;
+pub use self::multisig_participant::MultisigParticipant;
 pub use self::new_address::NewAddress;
 pub use self::new_blk_file::NewBlkFile;
 pub use self::new_block::NewBlock;
 pub use self::new_input::NewInput;
+pub use self::new_multisig_participant::NewMultisigParticipant;
 pub use self::new_output::NewOutput;
 pub use self::new_output_address::NewOutputAddress;
 pub use self::new_script_witness_item::NewScriptWitnessItem;
@@ -0,0 +1,9 @@
+#[derive(Queryable)]
+pub struct MultisigParticipant {
+    pub id: i64,
+    pub output_id: i64,
+    pub participant_index: i16,
+    pub required_signatures: i16,
+    pub public_key: Vec<u8>,
+    pub base58check: String,
+}
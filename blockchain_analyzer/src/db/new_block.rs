@@ -15,6 +15,13 @@ pub struct NewBlock {
     pub bits: i32,
     pub nonce: i32,
     pub blk_file_id: i64,
+    pub offset_in_blk_file: i64,
+    pub size_in_bytes: i32,
+    pub bip34_height: Option<i32>,
+    pub coinbase_tag: Option<String>,
+    pub stripped_size_in_bytes: i32,
+    pub weight: i32,
+    pub number_of_segwit_transactions: i32,
 }
 
 impl NewBlock {
@@ -28,6 +35,13 @@ impl NewBlock {
             bits: block.bits as i32,
             nonce: block.nonce as i32,
             blk_file_id,
+            offset_in_blk_file: block.offset_in_blk_file as i64,
+            size_in_bytes: block.size_in_bytes as i32,
+            bip34_height: block.bip34_height().map(|height| height as i32),
+            coinbase_tag: block.coinbase_tag(),
+            stripped_size_in_bytes: block.stripped_size_in_bytes() as i32,
+            weight: block.weight() as i32,
+            number_of_segwit_transactions: block.number_of_segwit_transactions() as i32,
         }
     }
 
@@ -80,6 +94,13 @@ mod test {
                 bits: 486604799,
                 nonce: 2083236893,
                 blk_file_id: blk_file.id,
+                offset_in_blk_file: 0,
+                size_in_bytes: 285,
+                bip34_height: None,
+                coinbase_tag: None,
+                stripped_size_in_bytes: 285,
+                weight: 1140,
+                number_of_segwit_transactions: 0,
             };
 
             // When
@@ -96,6 +117,8 @@ mod test {
             assert_eq!(saved_block.creation_time, new_block.creation_time);
             assert_eq!(saved_block.nonce, new_block.nonce);
             assert_eq!(saved_block.height, None);
+            assert_eq!(saved_block.offset_in_blk_file, Some(0));
+            assert_eq!(saved_block.size_in_bytes, Some(285));
             Ok(())
         });
     }
@@ -121,6 +144,13 @@ mod test {
                 bits: 486604799,
                 nonce: 2083236893,
                 blk_file_id: blk_file.id,
+                offset_in_blk_file: 0,
+                size_in_bytes: 0,
+                bip34_height: None,
+                coinbase_tag: None,
+                stripped_size_in_bytes: 0,
+                weight: 0,
+                number_of_segwit_transactions: 0,
             };
 
             // When
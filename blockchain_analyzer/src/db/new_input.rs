@@ -12,10 +12,15 @@ pub struct NewInput {
     pub previous_tx_output_index: i32,
     pub script: Vec<u8>,
     pub transaction_id: i64,
+    pub spend_type: String,
 }
 
 impl NewInput {
-    pub fn new(input: &blk_file_reader::Input, transaction_id: i64) -> NewInput {
+    pub fn new(
+        input: &blk_file_reader::Input,
+        spend_type: blk_file_reader::SpendType,
+        transaction_id: i64,
+    ) -> NewInput {
         NewInput {
             sequence_number: input.sequence_number as i32,
             // TODO Avoid copy.
@@ -24,6 +29,7 @@ impl NewInput {
             // TODO Avoid copy.
             script: input.script.to_vec(),
             transaction_id,
+            spend_type: spend_type.name().to_owned(),
         }
     }
 
@@ -0,0 +1,41 @@
+use super::MultisigParticipant;
+use blk_file_reader;
+use diesel::{self, pg::PgConnection, RunQueryDsl};
+use schema::multisig_participants;
+use std::result::Result;
+
+#[derive(Insertable)]
+#[table_name = "multisig_participants"]
+pub struct NewMultisigParticipant {
+    pub output_id: i64,
+    pub participant_index: i16,
+    pub required_signatures: i16,
+    pub public_key: Vec<u8>,
+    pub base58check: String,
+}
+
+impl NewMultisigParticipant {
+    pub fn new(
+        participant: &blk_file_reader::MultisigParticipant,
+        participant_index: usize,
+        required_signatures: u8,
+        output_id: i64,
+    ) -> NewMultisigParticipant {
+        NewMultisigParticipant {
+            output_id,
+            participant_index: participant_index as i16,
+            required_signatures: i16::from(required_signatures),
+            public_key: participant.public_key.to_vec(),
+            base58check: participant.address.encoded.clone(),
+        }
+    }
+
+    pub fn save(
+        &self,
+        db_connection: &PgConnection,
+    ) -> Result<MultisigParticipant, diesel::result::Error> {
+        diesel::insert_into(multisig_participants::table)
+            .values(self)
+            .get_result(db_connection)
+    }
+}
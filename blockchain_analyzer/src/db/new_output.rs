@@ -11,6 +11,7 @@ pub struct NewOutput {
     pub value: i64,
     pub script: Vec<u8>,
     pub transaction_id: i64,
+    pub script_type: String,
 }
 
 impl NewOutput {
@@ -21,6 +22,7 @@ impl NewOutput {
             // TODO Avoid copy.
             script: output.script.to_vec(),
             transaction_id,
+            script_type: output.script_type.name().to_owned(),
         }
     }
 
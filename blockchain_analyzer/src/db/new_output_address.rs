@@ -10,14 +10,16 @@ pub struct NewOutputAddress {
     pub output_id: i64,
     pub hash: Vec<u8>,
     pub base58check: String,
+    pub witness_version: Option<i16>,
 }
 
 impl NewOutputAddress {
     pub fn new(address: &blk_file_reader::Address, output_id: i64) -> NewOutputAddress {
         NewOutputAddress {
             output_id,
-            hash: address.hash.to_vec(),
-            base58check: address.base58check.clone(),
+            hash: address.payload.as_bytes().to_vec(),
+            base58check: address.encoded.clone(),
+            witness_version: address.payload.witness_version().map(i16::from),
         }
     }
 
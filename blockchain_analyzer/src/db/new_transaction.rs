@@ -13,6 +13,9 @@ pub struct NewTransaction {
     pub size_in_bytes: i32,
     pub weight: i32,
     pub block_id: i64,
+    pub is_coinbase: bool,
+    pub stripped_size_in_bytes: i32,
+    pub virtual_size: i32,
 }
 
 impl NewTransaction {
@@ -24,6 +27,9 @@ impl NewTransaction {
             size_in_bytes: transaction.size_in_bytes as i32,
             weight: transaction.weight as i32,
             block_id,
+            is_coinbase: transaction.is_coinbase(),
+            stripped_size_in_bytes: transaction.stripped_size_in_bytes as i32,
+            virtual_size: transaction.virtual_size() as i32,
         }
     }
 
@@ -1,3 +1,5 @@
+use blk_file_reader;
+
 #[derive(Queryable)]
 pub struct Output {
     pub id: i64,
@@ -5,4 +7,44 @@ pub struct Output {
     pub value: i64,
     pub script: Vec<u8>,
     pub transaction_id: i64,
+    pub script_type: Option<String>,
+}
+
+impl Output {
+    /// Returns the script of this output in ASM notation, see
+    /// `blk_file_reader::script_to_asm`.
+    pub fn script_asm(&self) -> String {
+        blk_file_reader::script_to_asm(&self.script)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn derives_script_asm_from_script() {
+        // Given
+        let mut script = vec![0x76, 0xA9, 0x14];
+        script.extend_from_slice(&[0x62; 20]);
+        script.extend_from_slice(&[0x88, 0xAC]);
+        let output = Output {
+            id: 1,
+            output_index: 0,
+            value: 50,
+            script,
+            transaction_id: 1,
+            script_type: None,
+        };
+
+        // When
+        let script_asm = output.script_asm();
+
+        // Then
+        assert_eq!(
+            script_asm,
+            "OP_DUP OP_HASH160 6262626262626262626262626262626262626262 OP_EQUALVERIFY OP_CHECKSIG"
+        );
+    }
 }
@@ -7,6 +7,7 @@ pub struct OutputAddress {
     pub output_id: i64,
     pub hash: Vec<u8>,
     pub base58check: String,
+    pub witness_version: Option<i16>,
 }
 
 impl OutputAddress {
@@ -33,6 +33,13 @@ table! {
         nonce -> Int4,
         height -> Nullable<Int4>,
         blk_file_id -> Int8,
+        offset_in_blk_file -> Nullable<Int8>,
+        size_in_bytes -> Nullable<Int4>,
+        bip34_height -> Nullable<Int4>,
+        coinbase_tag -> Nullable<Text>,
+        stripped_size_in_bytes -> Nullable<Int4>,
+        weight -> Nullable<Int4>,
+        number_of_segwit_transactions -> Nullable<Int4>,
     }
 }
 
@@ -44,6 +51,18 @@ table! {
         previous_tx_output_index -> Int4,
         script -> Bytea,
         transaction_id -> Int8,
+        spend_type -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    multisig_participants (id) {
+        id -> Int8,
+        output_id -> Int8,
+        participant_index -> Int2,
+        required_signatures -> Int2,
+        public_key -> Bytea,
+        base58check -> Varchar,
     }
 }
 
@@ -52,6 +71,7 @@ table! {
         output_id -> Int8,
         hash -> Bytea,
         base58check -> Varchar,
+        witness_version -> Nullable<Int2>,
     }
 }
 
@@ -62,6 +82,7 @@ table! {
         value -> Int8,
         script -> Bytea,
         transaction_id -> Int8,
+        script_type -> Nullable<Varchar>,
     }
 }
 
@@ -82,12 +103,16 @@ table! {
         size_in_bytes -> Int4,
         weight -> Int4,
         block_id -> Int8,
+        is_coinbase -> Nullable<Bool>,
+        stripped_size_in_bytes -> Nullable<Int4>,
+        virtual_size -> Nullable<Int4>,
     }
 }
 
 joinable!(address_deduplicator_states -> output_addresses (output_address_id));
 joinable!(blocks -> blk_files (blk_file_id));
 joinable!(inputs -> transactions (transaction_id));
+joinable!(multisig_participants -> outputs (output_id));
 joinable!(output_addresses -> outputs (output_id));
 joinable!(outputs -> transactions (transaction_id));
 joinable!(script_witness_items -> inputs (input_id));
@@ -99,6 +124,7 @@ allow_tables_to_appear_in_same_query!(
     blk_files,
     blocks,
     inputs,
+    multisig_participants,
     output_addresses,
     outputs,
     script_witness_items,
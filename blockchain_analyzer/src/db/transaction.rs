@@ -7,4 +7,9 @@ pub struct Transaction {
     pub size_in_bytes: i32,
     pub weight: i32,
     pub block_id: i64,
+    /// Missing for transactions imported before it has been recorded.
+    pub is_coinbase: Option<bool>,
+    /// Missing for transactions imported before it has been recorded.
+    pub stripped_size_in_bytes: Option<i32>,
+    pub virtual_size: Option<i32>,
 }
@@ -24,5 +24,5 @@ mod db;
 pub mod task_manager;
 pub mod tasks;
 
-pub use config::Config;
+pub use config::{BlockOrder, Config, InputResolution};
 use db::schema;
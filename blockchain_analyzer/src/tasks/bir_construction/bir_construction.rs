@@ -1,64 +1,236 @@
-use super::{InputAddressResolver, OrderedBlocks, State};
+use super::{
+    resolve_input_addresses_from_undo, BlockHeight, InputAddressResolver, OrderedBlock,
+    OrderedBlocks, State,
+};
 use bir;
-use blk_file_reader;
-use config::Config;
+use blk_file_reader::{self, Block, BlockIndex, Blocks, ReadOptions};
+use config::{BlockOrder, Config};
 use diesel::PgConnection;
+use failure::Error;
+use std::cell::Cell;
+use std::io;
+use std::iter;
 use std::path::Path;
+use std::rc::Rc;
+use tasks::blk_file_import_task::without_skipped_ranges;
 
 /// Constructs the blockchain intermediate representation.
+///
+/// Depending on the configured block order, blocks are either read from the blk
+/// files and ordered by their previous block hash, or read in the order of the
+/// best chain of Bitcoin Core's block index, which already provides their
+/// heights. The construction stops at the first block that cannot be read,
+/// whose error is returned after all blocks that could be ordered up to this
+/// point.
 pub fn construct_bir<'bir, 'state, 'conn>(
     config: &Config,
     state: &'state mut State,
     db_connection: &'conn PgConnection,
-) -> impl Iterator<Item = bir::Block> + LifetimeCapture<'state> + LifetimeCapture<'conn> + 'bir
+) -> Result<
+    impl Iterator<Item = io::Result<bir::Block>>
+        + LifetimeCapture<'state>
+        + LifetimeCapture<'conn>
+        + 'bir,
+    Error,
+>
 where
     'state: 'bir,
     'conn: 'bir,
 {
-    let current_blk_file = &mut state.current_blk_file;
-    let current_blk_file_offset = &mut state.current_blk_file_offset;
-    let blocks_to_skip = *current_blk_file_offset;
+    let network = config.network;
+    let options = config.read_options();
 
-    let raw_blocks = blk_file_reader::read_blk_files(&config.blk_file_path)
-        .unwrap()
-        .into_iter()
-        .skip(*current_blk_file)
-        .flat_map(move |blk_file_path| {
-            info!("Construct bir for {}", &blk_file_path);
-
-            let blk_file_name = Path::new(&blk_file_path)
-                .file_name()
-                .unwrap()
-                .to_str()
-                .unwrap();
-            let blk_file_index = blk_file_name[3..8].parse::<usize>().unwrap();
-
-            *current_blk_file = blk_file_index;
-            blk_file_reader::read_blocks(&blk_file_path).unwrap()
-        }).map(move |block| {
-            let block = block.unwrap();
-            *current_blk_file_offset = block.index_in_blk_file + 1;
-            block
-        }).skip(blocks_to_skip);
-
-    let ordered_blocks = OrderedBlocks::new(
-        &mut state.consumed_blocks,
-        &mut state.unresolved_blocks,
-        &mut state.consumable_blocks,
-        raw_blocks,
-    );
+    // Stop at the first error and hand it out once the readable blocks have
+    // been ordered.
+    let read_error = Rc::new(Cell::new(None));
+
+    let ordered_blocks: Box<dyn Iterator<Item = OrderedBlock> + 'bir> = match config.block_order {
+        BlockOrder::PreviousBlockHash => {
+            let current_blk_file = &mut state.current_blk_file;
+            let current_blk_file_offset = &mut state.current_blk_file_offset;
+            let current_blk_file_position = &mut state.current_blk_file_position;
+            let resumed_blk_file = *current_blk_file;
+            let resumed_blk_file_offset = *current_blk_file_offset;
+            let resumed_blk_file_position = *current_blk_file_position;
+
+            let raw_blocks = blk_file_reader::read_blk_files(&config.blk_file_path)?
+                .into_iter()
+                .skip(*current_blk_file)
+                .flat_map(move |blk_file_path| {
+                    info!("Construct bir for {}", &blk_file_path);
+
+                    let blk_file_name = Path::new(&blk_file_path)
+                        .file_name()
+                        .unwrap()
+                        .to_str()
+                        .unwrap()
+                        .to_owned();
+                    let blk_file_index = blk_file_name[3..8].parse::<usize>().unwrap();
+
+                    *current_blk_file = blk_file_index;
+                    let blocks = read_blocks(
+                        &blk_file_path,
+                        options,
+                        blk_file_index == resumed_blk_file,
+                        resumed_blk_file_position,
+                        resumed_blk_file_offset,
+                    );
+                    let blocks: Box<dyn Iterator<Item = io::Result<Block>>> = match blocks {
+                        Ok(blocks) => Box::new(without_skipped_ranges(blocks, blk_file_name)),
+                        Err(error) => Box::new(iter::once(Err(error))),
+                    };
+                    blocks
+                }).map(move |block| {
+                    if let Ok(ref block) = block {
+                        *current_blk_file_offset = block.index_in_blk_file + 1;
+                        *current_blk_file_position = block.end_offset_in_blk_file();
+                    }
+                    block
+                });
+            Box::new(OrderedBlocks::new(
+                &mut state.consumed_blocks,
+                &mut state.unresolved_blocks,
+                &mut state.consumable_blocks,
+                stop_at_error(raw_blocks, read_error.clone()),
+            ))
+        }
+        BlockOrder::BlockIndex => Box::new(stop_at_error(
+            read_best_chain(config, state.next_block_height)?,
+            read_error.clone(),
+        )),
+    };
 
     let mut input_address_resolver =
-        InputAddressResolver::new(db_connection, &mut state.utxo_cache);
+        InputAddressResolver::new(db_connection, &mut state.utxo_cache, network);
 
     // Construct the BIR by chaining the above iterators.
     let next_block_height = &mut state.next_block_height;
-    ordered_blocks.map(move |ordered_block| {
+    let bir_blocks = ordered_blocks.map(move |ordered_block| {
         *next_block_height = ordered_block.height as u32 + 1;
-        input_address_resolver.resolve_input_addresses(ordered_block)
+        Ok(input_address_resolver.resolve_input_addresses(ordered_block))
+    });
+    Ok(bir_blocks.chain(iter::once(()).filter_map(move |_| read_error.take().map(Err))))
+}
+
+/// Reads the blocks of a blk file, continuing right after the last block that
+/// has been read if `resume` is set.
+fn read_blocks(
+    blk_file_path: &str,
+    options: ReadOptions,
+    resume: bool,
+    resumed_blk_file_position: u64,
+    resumed_blk_file_offset: usize,
+) -> io::Result<Blocks> {
+    let mut blocks = blk_file_reader::read_blocks_with_options(blk_file_path, options)?;
+    if resume {
+        blocks.seek_to_offset(resumed_blk_file_position, resumed_blk_file_offset)?;
+    }
+    Ok(blocks)
+}
+
+/// Ends the given iterator at its first error, which is stored in `read_error`.
+fn stop_at_error<T, I>(
+    results: I,
+    read_error: Rc<Cell<Option<io::Error>>>,
+) -> impl Iterator<Item = T>
+where
+    I: Iterator<Item = io::Result<T>>,
+{
+    results.scan((), move |_, result| match result {
+        Ok(item) => Some(item),
+        Err(error) => {
+            read_error.set(Some(error));
+            None
+        }
     })
 }
 
+/// Reads the blocks of the best chain of Bitcoin Core's block index, starting
+/// at the given height.
+///
+/// Since the best chain is already ordered, the blocks are not buffered in
+/// `State` and their heights are taken from the block index.
+fn read_best_chain(
+    config: &Config,
+    start_height: u32,
+) -> Result<impl Iterator<Item = io::Result<OrderedBlock>>, Error> {
+    let block_index_path = config.block_index_path();
+    info!("Read block index {}", block_index_path.display());
+    let block_index = BlockIndex::open(&block_index_path)?;
+
+    let best_chain: Vec<_> = block_index
+        .best_chain()
+        .into_iter()
+        .skip(start_height as usize)
+        .cloned()
+        .collect();
+
+    let blk_file_path = config.blk_file_path.clone();
+    let options = config.read_options();
+
+    Ok(best_chain.into_iter().map(move |entry| {
+        let block = blk_file_reader::read_indexed_block(&blk_file_path, &entry, options)?;
+        Ok(OrderedBlock {
+            block,
+            height: entry.height as BlockHeight,
+        })
+    }))
+}
+
+/// Constructs the blockchain intermediate representation by following the best
+/// chain of Bitcoin Core's block index and resolving inputs from its rev files.
+///
+/// The construction stops at the first block of the best chain whose undo data
+/// has not been written yet, and at the first block or undo data that cannot be
+/// read or does not match the block. Apart from the height of the next block,
+/// this does not depend on `state`.
+pub fn construct_bir_from_undo_files<'state>(
+    config: &Config,
+    state: &'state mut State,
+) -> Result<impl Iterator<Item = io::Result<bir::Block>> + 'state, Error> {
+    let block_index_path = config.block_index_path();
+    info!("Read block index {}", block_index_path.display());
+    let block_index = BlockIndex::open(&block_index_path)?;
+
+    // The genesis block does not spend any outputs and has no undo data.
+    let next_block_height = &mut state.next_block_height;
+    let best_chain: Vec<_> = block_index
+        .best_chain()
+        .into_iter()
+        .take_while(|entry| entry.height == 0 || entry.has_undo())
+        .skip(*next_block_height as usize)
+        .cloned()
+        .collect();
+
+    if let Some(last_entry) = best_chain.last() {
+        info!(
+            "Serialize {} blocks up to block height {}",
+            best_chain.len(),
+            last_entry.height
+        );
+    }
+
+    let blk_file_path = config.blk_file_path.clone();
+    let options = config.read_options();
+
+    Ok(best_chain.into_iter().map(move |entry| {
+        let block = blk_file_reader::read_indexed_block(&blk_file_path, &entry, options)?;
+        let block_undo = if entry.height == 0 {
+            None
+        } else {
+            Some(blk_file_reader::read_indexed_block_undo(
+                &blk_file_path,
+                &entry,
+                options,
+            )?)
+        };
+
+        let bir_block = resolve_input_addresses_from_undo(block, entry.height, block_undo)?;
+        *next_block_height = entry.height + 1;
+        Ok(bir_block)
+    }))
+}
+
 /// Helper-trait for capturing lifetimes in `impl Trait` return types.
 ///
 /// Based on a suggestion in [this issue](https://github.com/rust-lang/rust/issues/49431)
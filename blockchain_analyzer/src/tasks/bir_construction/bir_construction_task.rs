@@ -1,13 +1,13 @@
 use super::*;
 use bincode;
-use config::Config;
+use config::{Config, InputResolution};
 use db::Block;
 use diesel::prelude::*;
 use failure::Error;
 use r2d2::Pool;
 use r2d2_diesel::ConnectionManager;
 use std::fs::{create_dir_all, File, OpenOptions};
-use std::io::{BufWriter, Write};
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
 use std::result::Result;
 use task_manager::{Index, Task};
@@ -36,31 +36,23 @@ impl Task for BirConstructionTask {
 
         let db_connection = db_connection_pool.get()?;
 
-        if let Some(max_block_height) = Block::max_height(&db_connection)? {
-            // TODO Fix possibly truncating cast.
-            let max_block_height = max_block_height as u32;
-
-            let mut state = state::load_state(&config.bir_construction_state_file_path);
-
-            // TODO Make intent more obvious.
-            let number_of_blocks_to_write = max_block_height + 1 - state.next_block_height;
-
-            if number_of_blocks_to_write > 0 {
-                info!(
-                    "Serialize {} blocks up to block height {}",
-                    number_of_blocks_to_write, max_block_height
-                );
-
-                serialize_bir_into_files(
-                    &config,
-                    &mut state,
-                    &db_connection,
-                    number_of_blocks_to_write,
-                )?;
+        let mut state = state::load_state(&config.bir_construction_state_file_path)?;
 
-                state::save_state(state, &config.bir_construction_state_file_path);
+        let result = match config.input_resolution {
+            InputResolution::UtxoCache => {
+                serialize_bir_up_to_max_block_height(&config, &mut state, &db_connection)
             }
-        }
+            InputResolution::UndoFiles => serialize_bir_into_files(
+                &config,
+                construct_bir_from_undo_files(&config, &mut state)?,
+            ),
+        };
+
+        // The state only covers the blocks that have been serialized, even if
+        // the construction stopped because of an error.
+        let saved = state::save_state(state, &config.bir_construction_state_file_path);
+        result?;
+        saved?;
 
         info!("Finished BirConstructionTask");
 
@@ -72,17 +64,44 @@ impl Task for BirConstructionTask {
     }
 }
 
-fn serialize_bir_into_files(
+/// Serializes the bir of all blocks up to the maximum block height in the DB.
+fn serialize_bir_up_to_max_block_height(
     config: &Config,
     state: &mut State,
     db_connection: &PgConnection,
-    number_of_blocks_to_write: u32,
 ) -> Result<(), Error> {
-    let mut blocks = construct_bir(config, state, db_connection)
+    if let Some(max_block_height) = Block::max_height(db_connection)? {
         // TODO Fix possibly truncating cast.
-        .take(number_of_blocks_to_write as usize);
+        let max_block_height = max_block_height as u32;
+
+        // TODO Make intent more obvious.
+        let number_of_blocks_to_write = max_block_height + 1 - state.next_block_height;
+
+        if number_of_blocks_to_write > 0 {
+            info!(
+                "Serialize {} blocks up to block height {}",
+                number_of_blocks_to_write, max_block_height
+            );
 
+            // TODO Fix possibly truncating cast.
+            let number_of_blocks_to_write = number_of_blocks_to_write as usize;
+            serialize_bir_into_files(
+                config,
+                construct_bir(config, state, db_connection)?.take(number_of_blocks_to_write),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Serializes blocks into the unresolved bir files until the first error.
+fn serialize_bir_into_files<I>(config: &Config, mut blocks: I) -> Result<(), Error>
+where
+    I: Iterator<Item = io::Result<bir::Block>>,
+{
     if let Some(block) = blocks.next() {
+        let block = block?;
         let unresolved_bir_files = bir::read_bir_files(&config.unresolved_bir_file_path)?;
 
         let mut bir_file_size: u64;
@@ -112,7 +131,10 @@ fn serialize_bir_into_files(
             bir_file.write_all(&serialized_block)?;
             bir_file_size += serialized_block.len() as u64;
 
-            next_block = blocks.next();
+            next_block = match blocks.next() {
+                Some(block) => Some(block?),
+                None => None,
+            };
         }
     }
 
@@ -10,16 +10,19 @@ use diesel::{
 pub struct InputAddressResolver<'conn, 'utxo> {
     db_connection: &'conn PgConnection,
     utxo_cache: &'utxo mut UtxoCache,
+    network: blk_file_reader::Network,
 }
 
 impl<'conn, 'utxo> InputAddressResolver<'conn, 'utxo> {
     pub fn new(
         db_connection: &'conn PgConnection,
         utxo_cache: &'utxo mut UtxoCache,
+        network: blk_file_reader::Network,
     ) -> InputAddressResolver<'conn, 'utxo> {
         InputAddressResolver {
             db_connection,
             utxo_cache,
+            network,
         }
     }
 
@@ -72,12 +75,16 @@ impl<'conn, 'utxo> InputAddressResolver<'conn, 'utxo> {
         transaction: blk_file_reader::Transaction,
     ) -> bir::Transaction {
         // Resolve inputs.
+        let network = self.network;
+        let is_coinbase = transaction.is_coinbase();
+        let script_witnesses = transaction.script_witnesses;
         let inputs: Vec<bir::Input> = transaction
             .inputs
             .into_vec()
             .into_iter()
-            .map(|input| {
-                let utxo = if input.previous_tx_hash.0 == [0u8; 32] {
+            .enumerate()
+            .map(|(input_index, input)| {
+                let utxo = if is_coinbase {
                     // TODO Use enum to distinguish resolved and unresolved utxos.
                     Utxo {
                         address: bir::Address::UnresolvedAddress,
@@ -91,6 +98,15 @@ impl<'conn, 'utxo> InputAddressResolver<'conn, 'utxo> {
 
                     if let Some(utxo) = utxo {
                         utxo
+                    } else if let Some(input_address) =
+                        input.derive_address(script_witnesses.get(input_index), network)
+                    {
+                        // The funding transaction is unknown, but the input
+                        // reveals the address it spends from.
+                        Utxo {
+                            address: bir::Address::Base58Check(input_address.address.encoded),
+                            value: 0,
+                        }
                     } else {
                         // TODO Use enum to distinguish resolved and unresolved utxos.
                         Utxo {
@@ -142,7 +158,7 @@ impl<'conn, 'utxo> InputAddressResolver<'conn, 'utxo> {
 
     fn get_output_address(&mut self, output: &blk_file_reader::Output) -> bir::Address {
         if let Some(ref address) = output.address {
-            bir::Address::Base58Check(address.base58check.clone())
+            bir::Address::Base58Check(address.encoded.clone())
         } else {
             bir::Address::UnresolvedAddress
         }
@@ -222,6 +238,7 @@ mod load_resolved_output_test {
                 output_index: 1,
                 value: 50,
                 script: vec![],
+                script_type: String::from("nonstandard"),
             };
             let output: Output = diesel::insert_into(schema::outputs::dsl::outputs)
                 .values(&new_output)
@@ -232,6 +249,7 @@ mod load_resolved_output_test {
                 output_id: output.id,
                 hash: vec![],
                 base58check: base58check.clone(),
+                witness_version: None,
             };
             diesel::insert_into(schema::output_addresses::dsl::output_addresses)
                 .values(&new_output_address)
@@ -6,13 +6,15 @@ mod bir_construction_task;
 mod input_address_resolver;
 mod ordered_blocks;
 pub mod state;
+mod undo_input_resolver;
 
-pub use self::bir_construction::construct_bir;
+pub use self::bir_construction::{construct_bir, construct_bir_from_undo_files};
 pub use self::bir_construction_task::BirConstructionTask;
 pub use self::state::State;
 
 use self::input_address_resolver::InputAddressResolver;
 use self::ordered_blocks::{OrderedBlock, OrderedBlocks};
+use self::undo_input_resolver::resolve_input_addresses_from_undo;
 
 type TxHash = [u8; 32];
 
@@ -145,6 +145,8 @@ mod test {
             creation_time: 0,
             nonce: 0,
             index_in_blk_file: 0,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
         }
     }
 
@@ -160,6 +162,8 @@ mod test {
             creation_time: 0,
             nonce: 0,
             index_in_blk_file: 1,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
         }
     }
 
@@ -175,6 +179,8 @@ mod test {
             creation_time: 0,
             nonce: 0,
             index_in_blk_file: 2,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
         }
     }
 
@@ -1,15 +1,21 @@
 use super::{BlockHash, BlockHeight, PreviousBlockHash, UtxoCache};
 use bincode;
 use blk_file_reader;
+use failure::Error;
 use std::collections::{HashMap, VecDeque};
+use std::error;
+use std::fmt;
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::io::{self, BufReader, BufWriter, Read, Write};
 use std::path::Path;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct State {
     pub current_blk_file: usize,
+    /// The number of blocks that have been read from the current blk file.
     pub current_blk_file_offset: usize,
+    /// The offset within the current blk file at which the next block starts.
+    pub current_blk_file_position: u64,
     pub next_block_height: u32,
     pub consumed_blocks: HashMap<BlockHash, BlockHeight>,
     pub unresolved_blocks: HashMap<PreviousBlockHash, Vec<blk_file_reader::Block>>,
@@ -17,32 +23,62 @@ pub struct State {
     pub utxo_cache: UtxoCache,
 }
 
-pub fn save_state<P>(state: State, path: P)
+/// Identifies bir construction state files.
+const STATE_FILE_MAGIC_NUMBER: [u8; 4] = *b"BIRS";
+
+/// The version of the serialized `State`, which has to be incremented whenever
+/// its layout, including the layout of `blk_file_reader::Block`, changes.
+const STATE_FORMAT_VERSION: u32 = 1;
+
+pub fn save_state<P>(state: State, path: P) -> Result<(), Error>
 where
     P: AsRef<Path>,
 {
-    // TODO Return error instead of panicking.
-    let state_file = File::create(path).unwrap();
+    let state_file = File::create(path)?;
     let mut state_file = BufWriter::new(state_file);
-    // TODO Return error instead of panicking.
-    bincode::serialize_into(&mut state_file, &state).unwrap();
+    state_file.write_all(&state_file_header(STATE_FORMAT_VERSION))?;
+    bincode::serialize_into(&mut state_file, &state)?;
+    state_file.flush()?;
+    Ok(())
 }
 
-pub fn load_state<P>(path: P) -> State
+/// Loads the state at the given path, or the initial state if there is no
+/// state file yet.
+///
+/// State files that have been written by an older version cannot be loaded and
+/// result in an `OutdatedStateFileError`.
+pub fn load_state<P>(path: P) -> Result<State, Error>
 where
     P: AsRef<Path>,
 {
-    if path.as_ref().exists() {
-        // TODO Return error instead of panicking.
-        let state_file = File::open(path).unwrap();
-        let mut state_file = BufReader::new(state_file);
-        // TODO Return error instead of panicking.
-        bincode::deserialize_from(&mut state_file).unwrap()
-    } else {
-        initial_state()
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(initial_state());
+    }
+
+    let state_file = File::open(path)?;
+    let mut state_file = BufReader::new(state_file);
+    let mut header = [0u8; 8];
+    match state_file.read_exact(&mut header) {
+        Ok(()) if header == state_file_header(STATE_FORMAT_VERSION) => {
+            Ok(bincode::deserialize_from(&mut state_file)?)
+        }
+        Ok(()) => Err(OutdatedStateFileError(path.display().to_string()).into()),
+        Err(ref error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+            Err(OutdatedStateFileError(path.display().to_string()).into())
+        }
+        Err(error) => Err(error.into()),
     }
 }
 
+/// Returns the magic number followed by the given format version.
+fn state_file_header(version: u32) -> [u8; 8] {
+    let mut header = [0u8; 8];
+    header[..4].copy_from_slice(&STATE_FILE_MAGIC_NUMBER);
+    header[4..].copy_from_slice(&version.to_le_bytes());
+    header
+}
+
 pub fn initial_state() -> State {
     let mut consumed_blocks = HashMap::new();
     consumed_blocks.insert([0u8; 32], -1);
@@ -50,6 +86,7 @@ pub fn initial_state() -> State {
     State {
         current_blk_file: 0,
         current_blk_file_offset: 0,
+        current_blk_file_position: 0,
         next_block_height: 0,
         consumed_blocks,
         unresolved_blocks: HashMap::new(),
@@ -57,3 +94,104 @@ pub fn initial_state() -> State {
         utxo_cache: UtxoCache::new(),
     }
 }
+
+/// Returned when loading a state file that has been written by an older
+/// version, whose layout differs from the current one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutdatedStateFileError(pub String);
+
+impl fmt::Display for OutdatedStateFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "state file {} has been written by an older version, delete it together with the \
+             unresolved bir files to construct the bir from scratch",
+            self.0
+        )
+    }
+}
+
+impl error::Error for OutdatedStateFileError {}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+
+    fn state_file_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!(
+            "blockchain_analyzer_{}_{}.dat",
+            name,
+            process::id()
+        ))
+    }
+
+    #[test]
+    fn loads_saved_state() {
+        // Given
+        let path = state_file_path("loads_saved_state");
+        let mut state = initial_state();
+        state.current_blk_file = 3;
+        state.current_blk_file_position = 1234;
+        state.next_block_height = 42;
+        save_state(state.clone(), &path).unwrap();
+
+        // When
+        let loaded_state = load_state(&path).unwrap();
+
+        // Then
+        assert_eq!(loaded_state, state);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn loads_initial_state_if_there_is_no_state_file() {
+        // Given
+        let path = state_file_path("loads_initial_state_if_there_is_no_state_file");
+
+        // When
+        let loaded_state = load_state(&path).unwrap();
+
+        // Then
+        assert_eq!(loaded_state, initial_state());
+    }
+
+    #[test]
+    fn rejects_state_file_without_header() {
+        // Given
+        let path = state_file_path("rejects_state_file_without_header");
+        let mut state_file = File::create(&path).unwrap();
+        bincode::serialize_into(&mut state_file, &initial_state()).unwrap();
+
+        // When
+        let error = load_state(&path).unwrap_err();
+
+        // Then
+        assert_eq!(
+            error.downcast_ref::<OutdatedStateFileError>(),
+            Some(&OutdatedStateFileError(path.display().to_string()))
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_state_file_of_other_version() {
+        // Given
+        let path = state_file_path("rejects_state_file_of_other_version");
+        let mut state_file = File::create(&path).unwrap();
+        state_file
+            .write_all(&state_file_header(STATE_FORMAT_VERSION + 1))
+            .unwrap();
+
+        // When
+        let error = load_state(&path).unwrap_err();
+
+        // Then
+        assert!(error.downcast_ref::<OutdatedStateFileError>().is_some());
+        fs::remove_file(&path).unwrap();
+    }
+}
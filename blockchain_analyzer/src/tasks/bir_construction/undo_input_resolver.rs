@@ -0,0 +1,278 @@
+use bir;
+use blk_file_reader;
+use std::io;
+
+/// Resolves the addresses and values of the inputs of the given block from the
+/// outputs that are recorded as spent in its undo data.
+///
+/// In contrast to `InputAddressResolver`, this requires neither a cache of
+/// unspent outputs nor the DB. `block_undo` is `None` for the genesis block.
+///
+/// Fails with `ErrorKind::InvalidData` if the undo data does not match the
+/// block, i.e. if it does not record exactly one spent output per input of
+/// every non-coinbase transaction.
+pub fn resolve_input_addresses_from_undo(
+    block: blk_file_reader::Block,
+    height: u32,
+    block_undo: Option<blk_file_reader::BlockUndo>,
+) -> io::Result<bir::Block> {
+    let transaction_undos = block_undo
+        .map(|block_undo| block_undo.transactions.into_vec())
+        .unwrap_or_default();
+    // The coinbase transaction has no undo data.
+    let number_of_non_coinbase_transactions = block.transactions.len().saturating_sub(1);
+    if transaction_undos.len() != number_of_non_coinbase_transactions {
+        return Err(undo_mismatch_error(format!(
+            "undo data of block {} covers {} transactions instead of {}",
+            height,
+            transaction_undos.len(),
+            number_of_non_coinbase_transactions
+        )));
+    }
+
+    let mut transaction_undos = transaction_undos.into_iter();
+    let transactions = block
+        .transactions
+        .into_vec()
+        .into_iter()
+        .enumerate()
+        .map(|(index, transaction)| {
+            let transaction_undo = if index == 0 {
+                None
+            } else {
+                transaction_undos.next()
+            };
+            resolve_transaction(transaction, transaction_undo, height)
+        })
+        .collect::<io::Result<Vec<bir::Transaction>>>()?;
+
+    Ok(bir::Block {
+        height,
+        transactions,
+    })
+}
+
+fn resolve_transaction(
+    transaction: blk_file_reader::Transaction,
+    transaction_undo: Option<blk_file_reader::TransactionUndo>,
+    height: u32,
+) -> io::Result<bir::Transaction> {
+    // Resolve inputs, leaving those of the coinbase transaction unresolved.
+    let inputs: Vec<bir::Input> = match transaction_undo {
+        Some(transaction_undo) => {
+            let spent_outputs = transaction_undo.spent_outputs.into_vec();
+            if spent_outputs.len() != transaction.inputs.len() {
+                return Err(undo_mismatch_error(format!(
+                    "undo data of transaction {:?} in block {} covers {} inputs instead of {}",
+                    transaction.tx_hash,
+                    height,
+                    spent_outputs.len(),
+                    transaction.inputs.len()
+                )));
+            }
+            spent_outputs
+                .into_iter()
+                .map(|spent_output| bir::Input {
+                    address: to_bir_address(spent_output.address),
+                    value: spent_output.value,
+                })
+                .collect()
+        }
+        // TODO Use enum to distinguish resolved and unresolved utxos.
+        None => transaction
+            .inputs
+            .iter()
+            .map(|_| bir::Input {
+                address: bir::Address::UnresolvedAddress,
+                value: 0,
+            })
+            .collect(),
+    };
+
+    // Map outputs.
+    let outputs: Vec<bir::Output> = transaction
+        .outputs
+        .into_vec()
+        .into_iter()
+        .map(|output| bir::Output {
+            value: output.value,
+            address: to_bir_address(output.address),
+        })
+        .collect();
+
+    Ok(bir::Transaction { inputs, outputs })
+}
+
+fn undo_mismatch_error(reason: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+fn to_bir_address(address: Option<blk_file_reader::Address>) -> bir::Address {
+    if let Some(address) = address {
+        bir::Address::Base58Check(address.encoded)
+    } else {
+        bir::Address::UnresolvedAddress
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use blk_file_reader::{
+        Address, AddressDerivation, AddressEncoding, AddressPayload, Block, BlockUndo, Hash, Input,
+        Output, ScriptType, SpentOutput, Transaction, TransactionUndo,
+    };
+
+    fn address(encoded: &str) -> Address {
+        Address {
+            payload: AddressPayload::PubKeyHash([0u8; 20]),
+            encoded: String::from(encoded),
+            encoding: AddressEncoding::Base58Check,
+        }
+    }
+
+    fn transaction(number_of_inputs: usize, output_address: &str) -> Transaction {
+        let input = Input {
+            sequence_number: 0,
+            previous_tx_hash: Hash([0u8; 32]),
+            previous_tx_output_index: 0,
+            script: Box::new([]),
+        };
+        let output = Output {
+            index: 0,
+            value: 10,
+            address: Some(address(output_address)),
+            address_derivation: Some(AddressDerivation::Direct),
+            script: Box::new([]),
+            script_type: ScriptType::P2PKH,
+            multisig: None,
+        };
+        Transaction {
+            tx_hash: Hash([0u8; 32]),
+            witness_hash: Hash([0u8; 32]),
+            version: 1,
+            lock_time: 0,
+            inputs: vec![input; number_of_inputs].into_boxed_slice(),
+            outputs: vec![output].into_boxed_slice(),
+            script_witnesses: Box::new([]),
+            size_in_bytes: 0,
+            stripped_size_in_bytes: 0,
+            weight: 0,
+        }
+    }
+
+    fn spent_output(encoded_address: &str, value: u64) -> SpentOutput {
+        SpentOutput {
+            height: 1,
+            is_coinbase: true,
+            value,
+            address: Some(address(encoded_address)),
+            address_derivation: Some(AddressDerivation::Direct),
+            script: Box::new([]),
+            script_type: ScriptType::P2PKH,
+        }
+    }
+
+    fn block(transactions: Vec<Transaction>) -> Block {
+        Block {
+            version: 0,
+            hash: Hash([0u8; 32]),
+            previous_block_hash: Hash([0u8; 32]),
+            merkle_root: Hash([0u8; 32]),
+            transactions: transactions.into_boxed_slice(),
+            bits: 0,
+            creation_time: 0,
+            nonce: 0,
+            index_in_blk_file: 0,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
+        }
+    }
+
+    fn block_undo(spent_outputs_by_transaction: Vec<Vec<SpentOutput>>) -> BlockUndo {
+        BlockUndo {
+            transactions: spent_outputs_by_transaction
+                .into_iter()
+                .map(|spent_outputs| TransactionUndo {
+                    spent_outputs: spent_outputs.into_boxed_slice(),
+                })
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+        }
+    }
+
+    #[test]
+    fn resolves_inputs_of_non_coinbase_transactions_from_undo_data() {
+        // Given
+        let block = block(vec![transaction(1, "coinbase"), transaction(2, "receiver")]);
+        let block_undo = block_undo(vec![vec![
+            spent_output("sender1", 5),
+            spent_output("sender2", 6),
+        ]]);
+
+        // When
+        let bir_block = resolve_input_addresses_from_undo(block, 2, Some(block_undo)).unwrap();
+
+        // Then
+        assert_eq!(bir_block.height, 2);
+        assert_eq!(
+            bir_block.transactions[0].inputs,
+            vec![bir::Input {
+                address: bir::Address::UnresolvedAddress,
+                value: 0,
+            }]
+        );
+        assert_eq!(
+            bir_block.transactions[1].inputs,
+            vec![
+                bir::Input {
+                    address: bir::Address::Base58Check(String::from("sender1")),
+                    value: 5,
+                },
+                bir::Input {
+                    address: bir::Address::Base58Check(String::from("sender2")),
+                    value: 6,
+                },
+            ]
+        );
+        assert_eq!(
+            bir_block.transactions[1].outputs[0].address,
+            bir::Address::Base58Check(String::from("receiver"))
+        );
+    }
+
+    #[test]
+    fn fails_if_undo_data_does_not_cover_every_non_coinbase_transaction() {
+        // Given
+        let block = block(vec![
+            transaction(1, "coinbase"),
+            transaction(1, "receiver1"),
+            transaction(1, "receiver2"),
+        ]);
+        let block_undo = block_undo(vec![vec![spent_output("sender", 5)]]);
+
+        // When
+        let result = resolve_input_addresses_from_undo(block, 2, Some(block_undo));
+
+        // Then
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn fails_if_undo_data_does_not_cover_every_input() {
+        // Given
+        let block = block(vec![transaction(1, "coinbase"), transaction(2, "receiver")]);
+        let block_undo = block_undo(vec![vec![
+            spent_output("sender1", 5),
+            spent_output("sender2", 6),
+            spent_output("sender3", 7),
+        ]]);
+
+        // When
+        let result = resolve_input_addresses_from_undo(block, 2, Some(block_undo));
+
+        // Then
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -40,8 +40,11 @@ impl Task for BlkFileImportTask {
         // TODO Make number of threads configurable.
         // TODO Handle failing threads.
         blk_files.par_iter().for_each(|blk_file| {
-            let import_result =
-                import_blk_file_in_separate_connection(db_connection_pool, blk_file);
+            let import_result = import_blk_file_in_separate_connection(
+                db_connection_pool,
+                blk_file,
+                config.read_options(),
+            );
 
             match import_result {
                 Ok(_) => {
@@ -99,6 +102,16 @@ impl Task for BlkFileImportTask {
                 column: String::from("base58check"),
                 unique: false,
             },
+            Index {
+                table: String::from("multisig_participants"),
+                column: String::from("output_id"),
+                unique: false,
+            },
+            Index {
+                table: String::from("multisig_participants"),
+                column: String::from("base58check"),
+                unique: false,
+            },
         ]
     }
 }
@@ -113,9 +126,26 @@ fn continue_import_of_latest_blk_file(
 
         info!("Continue import of {:?}", latest_imported_blk_file_path);
 
-        let mut blocks =
-            blk_file_reader::read_blocks(latest_imported_blk_file_path.to_str().unwrap())?;
-        let mut blocks = blocks.skip(latest_imported_blk_file.number_of_blocks as usize);
+        let mut blocks = blk_file_reader::read_blocks_with_options(
+            latest_imported_blk_file_path.to_str().unwrap(),
+            config.read_options(),
+        )?;
+        let number_of_imported_blocks = latest_imported_blk_file.number_of_blocks as usize;
+        let last_imported_block =
+            Block::read_last_of_blk_file(db_connection, latest_imported_blk_file.id)?;
+        let blocks_to_skip =
+            match last_imported_block.and_then(|block| block.end_offset_in_blk_file()) {
+                Some(end_offset) => {
+                    blocks.seek_to_offset(end_offset, number_of_imported_blocks)?;
+                    0
+                }
+                // Blocks imported before their offsets have been recorded have to
+                // be read again.
+                None => number_of_imported_blocks,
+            };
+        // Skipped byte ranges are not counted as blocks.
+        let blocks = without_skipped_ranges(blocks, latest_imported_blk_file.name.clone());
+        let mut blocks = blocks.skip(blocks_to_skip);
         import_blocks(db_connection, blocks, &latest_imported_blk_file)?;
     }
 
@@ -155,9 +185,11 @@ pub fn get_blk_file_name(blk_file_path: &str) -> String {
 fn import_blk_file_in_separate_connection(
     db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
     blk_file: &str,
+    options: blk_file_reader::ReadOptions,
 ) -> Result<(), Error> {
     info!("Import {}", blk_file);
-    let blocks = blk_file_reader::read_blocks(blk_file)?;
+    let blocks = blk_file_reader::read_blocks_with_options(blk_file, options)?;
+    let blocks = without_skipped_ranges(blocks, blk_file.to_owned());
     let db_connection = db_connection_pool.get()?;
     db_connection.transaction(|| import_blk_file(&db_connection, blk_file, blocks))?;
     Ok(())
@@ -185,6 +217,28 @@ where
     Ok(())
 }
 
+/// Logs and drops the byte ranges of the given blk file that have been skipped
+/// because of corrupt data, see `blk_file_reader::ReadOptions::resync`.
+pub(crate) fn without_skipped_ranges<'a, B>(
+    blocks: B,
+    blk_file: String,
+) -> impl Iterator<Item = ::std::io::Result<blk_file_reader::Block>> + 'a
+where
+    B: IntoIterator<Item = ::std::io::Result<blk_file_reader::Block>>,
+    B::IntoIter: 'a,
+{
+    blocks.into_iter().filter(move |block| match *block {
+        Err(ref error) => match blk_file_reader::ParseError::from_io_error(error) {
+            Some(skipped @ &blk_file_reader::ParseError::Skipped { .. }) => {
+                warn!("Skipped corrupt data in {} ({})", blk_file, skipped);
+                false
+            }
+            _ => true,
+        },
+        Ok(_) => true,
+    })
+}
+
 pub fn import_blocks<B>(
     db_connection: &PgConnection,
     blocks: B,
@@ -196,7 +250,7 @@ where
     let mut number_of_blocks = 0;
 
     for block in blocks.into_iter() {
-        let block = block.unwrap();
+        let block = block?;
         let _ = import_block(db_connection, &block, blk_file.id)?;
         number_of_blocks += 1;
     }
@@ -247,11 +301,13 @@ fn import_inputs(
     transaction: &blk_file_reader::Transaction,
     transaction_id: i64,
 ) -> Result<(), Error> {
+    let spend_types = transaction.input_spend_types();
     for (input_index, input) in transaction.inputs.iter().enumerate() {
         import_input(
             db_connection,
             input,
             input_index,
+            spend_types[input_index],
             transaction,
             transaction_id,
         )?;
@@ -264,14 +320,14 @@ fn import_input(
     db_connection: &PgConnection,
     input: &blk_file_reader::Input,
     input_index: usize,
+    spend_type: blk_file_reader::SpendType,
     transaction: &blk_file_reader::Transaction,
     transaction_id: i64,
 ) -> Result<(), Error> {
-    let new_input = NewInput::new(input, transaction_id);
+    let new_input = NewInput::new(input, spend_type, transaction_id);
     let saved_input = new_input.save(db_connection)?;
 
-    let is_segwit_tx = transaction.script_witnesses.len() > 0;
-    if is_segwit_tx {
+    if transaction.is_segwit() {
         for script_witness_item in transaction.script_witnesses[input_index].items.iter() {
             let new_script_witness_item = NewScriptWitnessItem {
                 content: script_witness_item.to_vec(),
@@ -309,6 +365,28 @@ fn import_output(
         import_address(db_connection, address, saved_output.id);
     };
 
+    if let Some(ref multisig) = output.multisig {
+        import_multisig_participants(db_connection, multisig, saved_output.id)?;
+    };
+
+    Ok(())
+}
+
+fn import_multisig_participants(
+    db_connection: &PgConnection,
+    multisig: &blk_file_reader::Multisig,
+    output_id: i64,
+) -> Result<(), Error> {
+    for (participant_index, participant) in multisig.participants.iter().enumerate() {
+        let new_multisig_participant = NewMultisigParticipant::new(
+            participant,
+            participant_index,
+            multisig.required_signatures,
+            output_id,
+        );
+        new_multisig_participant.save(db_connection)?;
+    }
+
     Ok(())
 }
 
@@ -335,7 +413,11 @@ mod test {
     pub fn genesis_block_is_imported_correctly() {
         // Given
         let db_connection = PgConnection::establish(TEST_DATABASE_URL).unwrap();
-        let blocks = blk_file_reader::read_blocks("../sample-blk-files/blk00000.dat").unwrap();
+        let blocks = blk_file_reader::read_blocks(
+            "../sample-blk-files/blk00000.dat",
+            blk_file_reader::Network::Mainnet,
+        )
+        .unwrap();
         let blocks = blocks.take(1);
 
         db_connection.test_transaction::<_, Error, _>(|| {
@@ -367,6 +449,13 @@ mod test {
             );
             assert_eq!(genesis_block.creation_time, 1231006505);
             assert_eq!(genesis_block.nonce, 2083236893);
+            assert_eq!(genesis_block.bip34_height, None);
+            assert_eq!(
+                genesis_block.coinbase_tag,
+                Some(String::from(
+                    "The Times 03/Jan/2009 Chancellor on brink of second bailout for banks"
+                ))
+            );
 
             Ok(())
         });
@@ -376,7 +465,11 @@ mod test {
     pub fn imports_all_provided_blocks() {
         // Given
         let db_connection = PgConnection::establish(TEST_DATABASE_URL).unwrap();
-        let blocks = blk_file_reader::read_blocks("../sample-blk-files/blk00000.dat").unwrap();
+        let blocks = blk_file_reader::read_blocks(
+            "../sample-blk-files/blk00000.dat",
+            blk_file_reader::Network::Mainnet,
+        )
+        .unwrap();
         let blocks = blocks.take(5);
 
         db_connection.test_transaction::<_, Error, _>(|| {
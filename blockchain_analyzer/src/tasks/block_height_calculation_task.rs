@@ -1,4 +1,5 @@
-use config::Config;
+use blk_file_reader::BlockIndex;
+use config::{BlockOrder, Config};
 use db::schema::blocks::dsl::*;
 use diesel::{self, prelude::*};
 use failure::Error;
@@ -18,14 +19,26 @@ impl BlockHeightCalculationTask {
 impl Task for BlockHeightCalculationTask {
     fn run(
         &self,
-        _config: &Config,
+        config: &Config,
         db_connection_pool: &Pool<ConnectionManager<PgConnection>>,
     ) -> Result<(), Error> {
         info!("Run BlockHeightCalculationTask");
 
         let db_connection = db_connection_pool.get()?;
 
-        db_connection.transaction(|| calculate_height_for_all_blocks(&db_connection))?;
+        match config.block_order {
+            BlockOrder::PreviousBlockHash => {
+                db_connection.transaction(|| calculate_height_for_all_blocks(&db_connection))?
+            }
+            BlockOrder::BlockIndex => {
+                let block_index_path = config.block_index_path();
+                info!("Read block index {}", block_index_path.display());
+                let block_index = BlockIndex::open(&block_index_path)?;
+                db_connection.transaction(|| {
+                    calculate_height_from_block_index(&db_connection, &block_index)
+                })?
+            }
+        }
 
         info!("Finished BlockHeightCalculationTask");
 
@@ -68,6 +81,25 @@ fn calculate_height_for_all_blocks(
     Ok(())
 }
 
+/// Sets the height of the blocks of the best chain of the given block index,
+/// leaving the height of stale blocks untouched.
+fn calculate_height_from_block_index(
+    db_connection: &PgConnection,
+    block_index: &BlockIndex,
+) -> Result<(), diesel::result::Error> {
+    let best_chain = block_index.best_chain();
+    for entry in &best_chain {
+        // TODO Fix possibly truncating cast.
+        set_block_height(db_connection, &entry.hash.0, entry.height as i32)?;
+    }
+
+    if let Some(best_tip) = best_chain.last() {
+        info!("New block height is {}", best_tip.height);
+    }
+
+    Ok(())
+}
+
 fn set_block_height(
     db_connection: &PgConnection,
     current_hash: &[u8],
@@ -95,6 +127,7 @@ mod test {
 
     use self::data_encoding::HEXLOWER;
     use super::*;
+    use blk_file_reader::{BlockIndexEntry, ChainWork, Hash};
     use db::{Block, NewBlkFile, NewBlock};
 
     fn block0(parent_blk_file_id: i64) -> NewBlock {
@@ -111,6 +144,13 @@ mod test {
             bits: 0,
             nonce: 0,
             blk_file_id: parent_blk_file_id,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
+            bip34_height: None,
+            coinbase_tag: None,
+            stripped_size_in_bytes: 0,
+            weight: 0,
+            number_of_segwit_transactions: 0,
         }
     }
 
@@ -128,6 +168,13 @@ mod test {
             bits: 0,
             nonce: 0,
             blk_file_id: parent_blk_file_id,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
+            bip34_height: None,
+            coinbase_tag: None,
+            stripped_size_in_bytes: 0,
+            weight: 0,
+            number_of_segwit_transactions: 0,
         }
     }
 
@@ -145,6 +192,13 @@ mod test {
             bits: 0,
             nonce: 0,
             blk_file_id: parent_blk_file_id,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
+            bip34_height: None,
+            coinbase_tag: None,
+            stripped_size_in_bytes: 0,
+            weight: 0,
+            number_of_segwit_transactions: 0,
         }
     }
 
@@ -162,6 +216,13 @@ mod test {
             bits: 0,
             nonce: 0,
             blk_file_id: parent_blk_file_id,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
+            bip34_height: None,
+            coinbase_tag: None,
+            stripped_size_in_bytes: 0,
+            weight: 0,
+            number_of_segwit_transactions: 0,
         }
     }
 
@@ -179,6 +240,13 @@ mod test {
             bits: 0,
             nonce: 0,
             blk_file_id: parent_blk_file_id,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
+            bip34_height: None,
+            coinbase_tag: None,
+            stripped_size_in_bytes: 0,
+            weight: 0,
+            number_of_segwit_transactions: 0,
         }
     }
 
@@ -196,6 +264,41 @@ mod test {
             bits: 0,
             nonce: 0,
             blk_file_id: parent_blk_file_id,
+            offset_in_blk_file: 0,
+            size_in_bytes: 0,
+            bip34_height: None,
+            coinbase_tag: None,
+            stripped_size_in_bytes: 0,
+            weight: 0,
+            number_of_segwit_transactions: 0,
+        }
+    }
+
+    fn block_index_entry(
+        hex_hash: &str,
+        hex_previous_block_hash: &str,
+        block_height: u32,
+    ) -> BlockIndexEntry {
+        let mut hash_bytes = [0u8; 32];
+        hash_bytes.copy_from_slice(&HEXLOWER.decode(hex_hash.as_bytes()).unwrap());
+        let mut previous_block_hash_bytes = [0u8; 32];
+        previous_block_hash_bytes
+            .copy_from_slice(&HEXLOWER.decode(hex_previous_block_hash.as_bytes()).unwrap());
+        BlockIndexEntry {
+            hash: Hash(hash_bytes),
+            height: block_height,
+            status: 8,
+            number_of_transactions: 1,
+            file_number: Some(0),
+            data_offset: Some(0),
+            undo_offset: None,
+            version: 1,
+            previous_block_hash: Hash(previous_block_hash_bytes),
+            merkle_root: Hash([0u8; 32]),
+            creation_time: 0,
+            bits: 0x1d00ffff,
+            nonce: 0,
+            chain_work: ChainWork::from_bits(0x1d00ffff),
         }
     }
 
@@ -273,4 +376,62 @@ mod test {
             Ok(())
         });
     }
+
+    #[test]
+    fn can_calculate_block_height_from_block_index() {
+        let config = Config::load_test().unwrap();
+        let db_connection = PgConnection::establish(&config.db_url).unwrap();
+
+        db_connection.test_transaction::<_, diesel::result::Error, _>(|| {
+            // Given
+            let new_blk_file = NewBlkFile {
+                name: String::new(),
+                number_of_blocks: 0,
+            };
+            let blk_file = new_blk_file.save(&db_connection).unwrap();
+
+            let new_block0 = block0(blk_file.id);
+            let new_block1a = block1a(blk_file.id);
+            let new_block1b = block1b(blk_file.id);
+            let new_block2a = block2a(blk_file.id);
+            let block_index = BlockIndex::from_entries(vec![
+                block_index_entry(
+                    "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+                    "0000000000000000000000000000000000000000000000000000000000000000",
+                    0,
+                ),
+                block_index_entry(
+                    "00000000839a8e6886ab5951d76f411475428afc90947ee320161bbf18eb6048",
+                    "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+                    1,
+                ),
+                block_index_entry(
+                    "000000000000000000000000000000000000000000000000000000000000001b",
+                    "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+                    1,
+                ),
+                block_index_entry(
+                    "000000006a625f06636b8bb6ac7b960a8d03705d1ace08b1a19da3fdcc99ddbd",
+                    "00000000839a8e6886ab5951d76f411475428afc90947ee320161bbf18eb6048",
+                    2,
+                ),
+            ]);
+
+            // When
+            let _ = new_block0.save(&db_connection).unwrap();
+            let _ = new_block1a.save(&db_connection).unwrap();
+            let _ = new_block1b.save(&db_connection).unwrap();
+            let _ = new_block2a.save(&db_connection).unwrap();
+            calculate_height_from_block_index(&db_connection, &block_index).unwrap();
+
+            // Then
+            let saved_blocks = Block::read_all(&db_connection).unwrap();
+            assert_eq!(saved_blocks.len(), 4);
+            assert_eq!(saved_blocks[0].height, Some(0));
+            assert_eq!(saved_blocks[1].height, Some(1));
+            assert_eq!(saved_blocks[2].height, None);
+            assert_eq!(saved_blocks[3].height, Some(2));
+            Ok(())
+        });
+    }
 }